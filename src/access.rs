@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::walk;
+
+/// The direction of a data exchange, which decides whether `readOnly` or
+/// `writeOnly` properties may appear in the instance.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessContext {
+    /// The instance is being returned to a client; `writeOnly` values must not appear.
+    Read,
+    /// The instance is being sent by a client; `readOnly` values must not appear.
+    Write,
+}
+
+/// A property whose presence is not allowed in the given context.
+pub(crate) struct AccessViolation {
+    pub instance_path: String,
+    pub schema_path: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+pub(crate) fn access_violations(
+    schema: &Value,
+    instance: &Value,
+    context: AccessContext,
+) -> Vec<AccessViolation> {
+    let (keyword, code, description) = match context {
+        AccessContext::Write => (
+            "readOnly",
+            "readonly_write",
+            "read-only and must not be written",
+        ),
+        AccessContext::Read => (
+            "writeOnly",
+            "writeonly_read",
+            "write-only and must not be read",
+        ),
+    };
+    let mut violations = Vec::new();
+    walk::for_each_applied(schema, instance, &mut |node| {
+        let flagged = !node.instance_path.is_empty()
+            && node.schema.get(keyword).and_then(Value::as_bool) == Some(true);
+        if flagged {
+            violations.push(AccessViolation {
+                instance_path: node.instance_path.clone(),
+                schema_path: walk::join(&node.schema_path, keyword),
+                code,
+                message: format!("{} is {}", node.instance_path, description),
+            });
+        }
+        // Everything below a flagged property is covered by its violation.
+        !flagged
+    });
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_account_schema() -> Value {
+        json!({
+          "type": "object",
+          "properties": {
+            "id": { "type": "integer", "readOnly": true },
+            "password": { "type": "string", "writeOnly": true },
+            "profile": {
+              "type": "object",
+              "properties": { "createdAt": { "type": "string", "readOnly": true } },
+            },
+          },
+        })
+    }
+
+    #[test]
+    fn test_write_rejects_read_only_properties() {
+        let payload = json!({ "id": 7, "password": "secret", "profile": { "createdAt": "now" } });
+        let violations = access_violations(&get_account_schema(), &payload, AccessContext::Write);
+        let paths: Vec<_> = violations
+            .iter()
+            .map(|v| v.instance_path.as_str())
+            .collect();
+        assert_eq!(paths, ["/id", "/profile/createdAt"]);
+        assert_eq!(violations[0].code, "readonly_write");
+        assert_eq!(violations[0].schema_path, "/properties/id/readOnly");
+    }
+
+    #[test]
+    fn test_read_rejects_write_only_properties() {
+        let payload = json!({ "id": 7, "password": "secret" });
+        let violations = access_violations(&get_account_schema(), &payload, AccessContext::Read);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].instance_path, "/password");
+        assert_eq!(violations[0].code, "writeonly_read");
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::walk;
+
+/// Base URI assumed for a root schema without an `$id`.
+const ROOT_URI: &str = "urn:wasm-validator:root";
+
+/// Embeds every document `root` references, directly or transitively, under the
+/// root's `$defs` and rewrites each `$ref` into a same-document pointer, so the
+/// result compiles without any resources. Documents are embedded once each and
+/// referenced by pointer, so reference cycles stay cycles instead of being
+/// expanded. Embedded documents lose their top-level `$id` and `$schema`; only
+/// JSON Pointer fragments are supported, not anchors or nested `$id`s.
+pub(crate) fn bundle(root: &Value, resources: &HashMap<String, Value>) -> Result<Value, String> {
+    let resources: HashMap<&str, &Value> = resources
+        .iter()
+        .map(|(uri, document)| (uri.trim_end_matches('#'), document))
+        .collect();
+    let root_uri = root.get("$id").and_then(Value::as_str).unwrap_or(ROOT_URI);
+    let root_uri = root_uri.trim_end_matches('#').to_string();
+
+    let mut bundled = Value::Null;
+    let mut taken: HashSet<String> = root
+        .get("$defs")
+        .and_then(Value::as_object)
+        .map(|defs| defs.keys().cloned().collect())
+        .unwrap_or_default();
+    // Pointer within the bundle of every document seen so far, by URI.
+    let mut prefixes = HashMap::from([(root_uri.clone(), String::new())]);
+    let mut pending = vec![(root_uri.clone(), root.clone())];
+    let mut keys = HashMap::new();
+    let mut embedded = Vec::new();
+
+    while let Some((uri, mut document)) = pending.pop() {
+        let prefix = prefixes[&uri].clone();
+        let base = referencing::uri::from_str(&uri).map_err(|e| e.to_string())?;
+        let mut rewrites = Vec::new();
+        walk::for_each_subschema(&document, &mut |subschema, path, _| {
+            if let Some(reference) = subschema.get("$ref").and_then(Value::as_str) {
+                rewrites.push((path.to_string(), reference.to_string()));
+            }
+            true
+        });
+        for (path, reference) in rewrites {
+            let target = referencing::uri::resolve_against(&base.borrow(), &reference)
+                .map_err(|e| format!("Invalid $ref '{}' in '{}': {}", reference, uri, e))?;
+            let (target_uri, fragment) = target
+                .as_str()
+                .split_once('#')
+                .unwrap_or((target.as_str(), ""));
+            if !fragment.is_empty() && !fragment.starts_with('/') {
+                return Err(format!(
+                    "Cannot bundle $ref '{}' in '{}': only JSON Pointer fragments are supported",
+                    reference, uri
+                ));
+            }
+            let target_prefix = match prefixes.get(target_uri) {
+                Some(prefix) => prefix.clone(),
+                None => {
+                    let target_document = resources.get(target_uri).ok_or_else(|| {
+                        format!(
+                            "Resource '{}' referenced from '{}' is not available",
+                            target_uri, uri
+                        )
+                    })?;
+                    let key = unique_key(target_uri, &mut taken);
+                    let target_prefix = walk::join("/$defs", &key);
+                    keys.insert(target_uri.to_string(), key);
+                    prefixes.insert(target_uri.to_string(), target_prefix.clone());
+                    pending.push((target_uri.to_string(), (*target_document).clone()));
+                    target_prefix
+                }
+            };
+            if let Some(Value::Object(subschema)) = document.pointer_mut(&path) {
+                subschema.insert(
+                    "$ref".to_string(),
+                    Value::from(format!("#{}{}", target_prefix, fragment)),
+                );
+            }
+        }
+        if prefix.is_empty() {
+            bundled = document;
+        } else {
+            if let Value::Object(document) = &mut document {
+                document.remove("$id");
+                document.remove("$schema");
+            }
+            embedded.push((keys.remove(&uri).unwrap_or_default(), document));
+        }
+    }
+
+    if !embedded.is_empty() {
+        if !bundled.get("$defs").is_some_and(Value::is_object) {
+            bundled["$defs"] = Value::Object(serde_json::Map::new());
+        }
+        for (key, document) in embedded {
+            bundled["$defs"][key] = document;
+        }
+    }
+    Ok(bundled)
+}
+
+/// A `$defs` key for the document at `uri`: its file name without extension,
+/// suffixed with a number if already taken.
+fn unique_key(uri: &str, taken: &mut HashSet<String>) -> String {
+    let name = uri
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default();
+    let name = name
+        .split('.')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("resource");
+    let mut key = name.to_string();
+    let mut n = 2;
+    while !taken.insert(key.clone()) {
+        key = format!("{}_{}", name, n);
+        n += 1;
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bundle_keeps_cycles_internal() {
+        let root = json!({
+          "$id": "https://example.com/person.json",
+          "properties": {
+            "name": { "type": "string" },
+            "parent": { "$ref": "#" },
+            "home": { "$ref": "address.json" },
+          },
+        });
+        let resources = HashMap::from([(
+            "https://example.com/address.json".to_string(),
+            json!({
+              "$id": "https://example.com/address.json",
+              "properties": {
+                "zip": { "$ref": "#/$defs/zip" },
+                "owner": { "$ref": "person.json" },
+              },
+              "$defs": { "zip": { "type": "string", "pattern": "^[0-9]{5}$" } },
+            }),
+        )]);
+        let bundled = bundle(&root, &resources).unwrap();
+        assert_eq!(bundled["properties"]["parent"]["$ref"], "#");
+        assert_eq!(bundled["properties"]["home"]["$ref"], "#/$defs/address");
+        let address = &bundled["$defs"]["address"];
+        assert_eq!(
+            address["properties"]["zip"]["$ref"],
+            "#/$defs/address/$defs/zip"
+        );
+        assert_eq!(address["properties"]["owner"]["$ref"], "#");
+        assert!(address.get("$id").is_none());
+
+        assert!(bundle(&root, &HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+/// How many compiled schemas the cache keeps before evicting the least recently
+/// used one.
+pub(crate) const CAPACITY: usize = 64;
+
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+}
+
+struct Entry<T> {
+    /// The full key, compared on lookup so a hash collision is a miss.
+    key: String,
+    value: Arc<T>,
+    last_used: u64,
+}
+
+/// A bounded least-recently-used map from a key string, such as a serialized
+/// schema, to a shared value. Entries are found by the key's hash.
+pub(crate) struct SchemaCache<T> {
+    entries: HashMap<u64, Entry<T>>,
+    capacity: usize,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<T> SchemaCache<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        SchemaCache {
+            entries: HashMap::new(),
+            capacity,
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Looks up `key`, counting a hit or a miss.
+    pub(crate) fn get(&mut self, key: &str) -> Option<Arc<T>> {
+        self.clock += 1;
+        match self.entries.get_mut(&hash(key)) {
+            Some(entry) if entry.key == key => {
+                entry.last_used = self.clock;
+                self.hits += 1;
+                Some(entry.value.clone())
+            }
+            _ => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: String, value: Arc<T>) {
+        let hash = hash(&key);
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&hash) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(hash, _)| *hash);
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.clock += 1;
+        self.entries.insert(
+            hash,
+            Entry {
+                key,
+                value,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Drops every entry and resets the counters.
+    pub(crate) fn clear(&mut self) {
+        *self = SchemaCache::new(self.capacity);
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.entries.len(),
+        }
+    }
+}
+
+fn hash(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_and_evicts_least_recently_used() {
+        let mut cache = SchemaCache::new(2);
+        assert!(cache.get("a").is_none());
+        cache.insert("a".to_string(), Arc::new(1));
+        cache.insert("b".to_string(), Arc::new(2));
+        assert_eq!(cache.get("a").as_deref(), Some(&1));
+        cache.insert("c".to_string(), Arc::new(3));
+        assert!(cache.get("b").is_none());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                size: 2
+            }
+        );
+
+        cache.clear();
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.stats().size, 0);
+        assert_eq!(cache.stats().misses, 1);
+    }
+}
@@ -0,0 +1,28 @@
+/// Monotonic time in microseconds since an arbitrary origin, for profiling.
+/// In the browser and Node this is `performance.now()`, falling back to
+/// `Date.now()` where `performance` is unavailable.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_micros() -> f64 {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let performance = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .ok()
+        .filter(|performance| performance.is_object());
+    let now = performance.and_then(|performance| {
+        let now = js_sys::Reflect::get(&performance, &JsValue::from_str("now")).ok()?;
+        now.dyn_into::<js_sys::Function>()
+            .ok()?
+            .call0(&performance)
+            .ok()?
+            .as_f64()
+    });
+    now.unwrap_or_else(js_sys::Date::now) * 1000.0
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_micros() -> f64 {
+    use std::{sync::OnceLock, time::Instant};
+
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    ORIGIN.get_or_init(Instant::now).elapsed().as_secs_f64() * 1_000_000.0
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::walk;
+
+/// An object with properties no applicable subschema declares.
+pub(crate) struct Undeclared {
+    pub instance_path: String,
+    /// Where `additionalProperties: false` would sit in the first subschema that
+    /// describes the object.
+    pub schema_path: String,
+    pub unexpected: Vec<String>,
+}
+
+#[derive(Default)]
+struct ObjectNode {
+    schema_path: Option<String>,
+    declared: Vec<String>,
+    patterns: Vec<String>,
+    /// Whether the schema already closes the object, so it reports extras itself.
+    already_closed: bool,
+}
+
+/// Finds the properties of every instance object that no `properties` or
+/// `patternProperties` among its applicable subschemas declare, as if each of
+/// them had `additionalProperties: false`. Only objects that some subschema
+/// describes with `properties` or `patternProperties` are checked, so free-form
+/// maps stay open, and objects the schema already closes are skipped.
+pub(crate) fn undeclared_properties(schema: &Value, instance: &Value) -> Vec<Undeclared> {
+    let mut order = Vec::new();
+    let mut nodes: HashMap<String, ObjectNode> = HashMap::new();
+    walk::for_each_applied(schema, instance, &mut |applied| {
+        if !applied.instance.is_object() {
+            return true;
+        }
+        let node = nodes
+            .entry(applied.instance_path.clone())
+            .or_insert_with(|| {
+                order.push(applied.instance_path.clone());
+                ObjectNode::default()
+            });
+        let keys = |keyword| {
+            applied
+                .schema
+                .get(keyword)
+                .and_then(Value::as_object)
+                .map(|map| map.keys().cloned().collect::<Vec<_>>())
+        };
+        let declared = keys("properties");
+        let patterns = keys("patternProperties");
+        if (declared.is_some() || patterns.is_some()) && node.schema_path.is_none() {
+            node.schema_path = Some(walk::join(&applied.schema_path, "additionalProperties"));
+        }
+        node.declared.extend(declared.unwrap_or_default());
+        node.patterns.extend(patterns.unwrap_or_default());
+        node.already_closed |= ["additionalProperties", "unevaluatedProperties"]
+            .iter()
+            .any(|keyword| applied.schema.get(*keyword) == Some(&Value::Bool(false)));
+        true
+    });
+
+    let mut undeclared = Vec::new();
+    for instance_path in order {
+        let node = &nodes[&instance_path];
+        let Some(schema_path) = &node.schema_path else {
+            continue;
+        };
+        let Some(Value::Object(object)) = instance.pointer(&instance_path) else {
+            continue;
+        };
+        if node.already_closed {
+            continue;
+        }
+        let patterns: Vec<fancy_regex::Regex> = node
+            .patterns
+            .iter()
+            .filter_map(|pattern| fancy_regex::Regex::new(pattern).ok())
+            .collect();
+        let unexpected: Vec<String> = object
+            .keys()
+            .filter(|key| {
+                !node.declared.contains(key)
+                    && !patterns
+                        .iter()
+                        .any(|pattern| pattern.is_match(key).unwrap_or(false))
+            })
+            .cloned()
+            .collect();
+        if !unexpected.is_empty() {
+            undeclared.push(Undeclared {
+                instance_path,
+                schema_path: schema_path.clone(),
+                unexpected,
+            });
+        }
+    }
+    undeclared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_undeclared_properties_across_applicators() {
+        let schema = json!({
+          "allOf": [
+            { "properties": { "a": {} } },
+            { "properties": { "b": { "properties": { "c": {} } } } },
+          ],
+          "patternProperties": { "^x-": {} },
+          "properties": { "map": { "type": "object" }, "strict": { "properties": {}, "additionalProperties": false } },
+        });
+        let instance = json!({
+          "a": 1,
+          "b": { "c": 1, "d": 2 },
+          "x-trace": 1,
+          "extra": 1,
+          "map": { "anything": 1 },
+          "strict": { "reported": "by the schema" },
+        });
+        let undeclared = undeclared_properties(&schema, &instance);
+        let found: Vec<_> = undeclared
+            .iter()
+            .map(|u| (u.instance_path.as_str(), u.unexpected.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("", vec!["extra".to_string()]),
+                ("/b", vec!["d".to_string()])
+            ]
+        );
+        assert_eq!(undeclared[0].schema_path, "/additionalProperties");
+        assert_eq!(
+            undeclared[1].schema_path,
+            "/allOf/1/properties/b/additionalProperties"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{issue_id, path_depth, ValidationIssue};
+
+/// Splits `path` at its last array index into `(array path, index, rest)`.
+fn split_at_index(path: &str) -> Option<(&str, usize, &str)> {
+    let mut end = path.len();
+    while let Some(start) = path[..end].rfind('/') {
+        if let Ok(index) = path[start + 1..end].parse::<usize>() {
+            return Some((&path[..start], index, &path[end..]));
+        }
+        end = start;
+    }
+    None
+}
+
+/// Replaces issues that differ only in their array index with one summary issue
+/// at the array, carrying `count` and the failing `indices`. Issues are grouped
+/// by array path, path below the item, `schema_path`, `code` and message params
+/// other than the value, so items failing different constraints stay separate.
+/// A constraint only one item fails is left as is. `base_path` is the prefix
+/// the issue paths carry on top of pointers into `instance`.
+pub(crate) fn collapse_arrays(
+    issues: Vec<ValidationIssue>,
+    instance: &Value,
+    base_path: Option<&str>,
+) -> Vec<ValidationIssue> {
+    let group_key = |issue: &ValidationIssue| {
+        let (array, _, rest) = split_at_index(&issue.path)?;
+        let mut params = issue.message_params.clone();
+        params.remove("value");
+        Some((
+            array.to_string(),
+            rest.to_string(),
+            issue.schema_path.clone(),
+            issue.code.clone(),
+            Value::Object(params).to_string(),
+        ))
+    };
+    let mut group_sizes = HashMap::new();
+    for key in issues.iter().filter_map(group_key) {
+        *group_sizes.entry(key).or_insert(0) += 1;
+    }
+    let mut summaries: HashMap<_, usize> = HashMap::new();
+    let mut collapsed: Vec<ValidationIssue> = Vec::with_capacity(issues.len());
+    for issue in issues {
+        let Some(key) = group_key(&issue).filter(|key| group_sizes[key] > 1) else {
+            collapsed.push(issue);
+            continue;
+        };
+        let (array, index, _) = split_at_index(&issue.path).expect("grouped issues have an index");
+        if let Some(&position) = summaries.get(&key) {
+            let summary: &mut ValidationIssue = &mut collapsed[position];
+            summary.count = summary.count.map(|count| count + 1);
+            summary.indices.get_or_insert_with(Vec::new).push(index);
+            continue;
+        }
+        let local = base_path
+            .and_then(|base| array.strip_prefix(base))
+            .unwrap_or(array);
+        let total = instance
+            .pointer(local)
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        let (_, rest, _, code, params) = &key;
+        let summary = ValidationIssue {
+            id: issue_id(
+                &format!("{}/*{}", array, rest),
+                &issue.schema_path,
+                &format!("{}{}", code, params),
+            ),
+            path: array.to_string(),
+            depth: path_depth(array),
+            message: format!(
+                "{} of {} items are invalid: {}",
+                group_sizes[&key], total, issue.message
+            ),
+            count: Some(1),
+            indices: Some(vec![index]),
+            ..issue
+        };
+        summaries.insert(key, collapsed.len());
+        collapsed.push(summary);
+    }
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_at_last_index() {
+        assert_eq!(split_at_index("/tags/3"), Some(("/tags", 3, "")));
+        assert_eq!(
+            split_at_index("/rows/0/cells/12/v"),
+            Some(("/rows/0/cells", 12, "/v"))
+        );
+        assert_eq!(split_at_index("/profile/age"), None);
+    }
+}
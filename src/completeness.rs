@@ -0,0 +1,75 @@
+use serde_json::Value;
+
+use crate::walk;
+
+/// Keywords that each assert one thing about a value that is present.
+const VALUE_KEYWORDS: &[&str] = &[
+    "type",
+    "enum",
+    "const",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "format",
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+    "contains",
+    "minProperties",
+    "maxProperties",
+    "propertyNames",
+    "not",
+    "anyOf",
+    "oneOf",
+];
+
+/// Counts the constraints that apply to `instance`: one per property each
+/// applicable `required` lists, plus one per [`VALUE_KEYWORDS`] entry in every
+/// subschema that applies to a value the instance has. Subschemas of absent
+/// properties don't count, so filling in a field can raise the total.
+pub(crate) fn applicable_constraints(schema: &Value, instance: &Value) -> usize {
+    let mut total = 0;
+    walk::for_each_applied(schema, instance, &mut |applied| {
+        let Value::Object(object) = applied.schema else {
+            return true;
+        };
+        total += object
+            .get("required")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        total += VALUE_KEYWORDS
+            .iter()
+            .filter(|keyword| object.contains_key(**keyword))
+            .count();
+        true
+    });
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_absent_properties_count_only_as_required() {
+        let schema = json!({
+          "type": "object",
+          "required": ["name", "email"],
+          "properties": {
+            "name": { "type": "string", "minLength": 1 },
+            "email": { "type": "string", "format": "email" },
+          },
+        });
+        assert_eq!(applicable_constraints(&schema, &json!({})), 3);
+        assert_eq!(
+            applicable_constraints(&schema, &json!({ "name": "Ada" })),
+            5
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::walk;
+
+/// A rough measure of how expensive a schema may be to validate against.
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub(crate) struct SchemaCost {
+    /// Members of every subschema object, `$defs` included.
+    pub keyword_count: usize,
+    /// `pattern` keywords plus `patternProperties` entries.
+    pub regex_count: usize,
+    /// The longest chain of `$ref`s that resolve into subschemas holding further
+    /// `$ref`s. A cycle is counted once around; a remote `$ref` ends a chain.
+    pub max_ref_depth: usize,
+    /// The product of the branch counts of every `oneOf` and `anyOf`, an upper
+    /// bound on the combinations validation may try. Saturates at `u64::MAX`.
+    pub branch_product: u64,
+}
+
+/// Computes the [`SchemaCost`] of `schema` in one walk, without compiling it.
+pub(crate) fn schema_cost(schema: &Value) -> SchemaCost {
+    let mut cost = SchemaCost {
+        branch_product: 1,
+        ..SchemaCost::default()
+    };
+    // Each `$ref` by the path of the subschema holding it.
+    let mut refs: Vec<(String, String)> = Vec::new();
+    walk::for_each_subschema(schema, &mut |subschema, path, _| {
+        let Value::Object(object) = subschema else {
+            return true;
+        };
+        cost.keyword_count += object.len();
+        cost.regex_count += usize::from(object.get("pattern").is_some_and(Value::is_string));
+        if let Some(Value::Object(patterns)) = object.get("patternProperties") {
+            cost.regex_count += patterns.len();
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(branches)) = object.get(keyword) {
+                cost.branch_product = cost.branch_product.saturating_mul(branches.len() as u64);
+            }
+        }
+        if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+            refs.push((path.to_string(), reference.to_string()));
+        }
+        true
+    });
+    let mut depths = HashMap::new();
+    cost.max_ref_depth = refs
+        .iter()
+        .map(|(_, reference)| ref_depth(reference, &refs, &mut depths, &mut Vec::new()))
+        .max()
+        .unwrap_or(0);
+    cost
+}
+
+/// The length of the longest `$ref` chain starting with `reference`.
+fn ref_depth<'a>(
+    reference: &'a str,
+    refs: &'a [(String, String)],
+    depths: &mut HashMap<&'a str, usize>,
+    following: &mut Vec<&'a str>,
+) -> usize {
+    let Some(target) = reference.strip_prefix('#') else {
+        return 1;
+    };
+    if let Some(depth) = depths.get(target) {
+        return *depth;
+    }
+    if following.contains(&target) {
+        return 0;
+    }
+    following.push(target);
+    let nested = refs
+        .iter()
+        .filter(|(path, _)| {
+            path.strip_prefix(target)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .map(|(_, nested)| ref_depth(nested, refs, depths, following))
+        .max()
+        .unwrap_or(0);
+    following.pop();
+    depths.insert(target, 1 + nested);
+    1 + nested
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_complex_schema_costs_more() {
+        let simple = json!({ "type": "string", "minLength": 1 });
+        assert_eq!(
+            schema_cost(&simple),
+            SchemaCost {
+                keyword_count: 2,
+                regex_count: 0,
+                max_ref_depth: 0,
+                branch_product: 1,
+            }
+        );
+
+        let complex = json!({
+          "oneOf": [{ "$ref": "#/$defs/a" }, { "$ref": "#/$defs/b" }, { "type": "null" }],
+          "properties": {
+            "id": { "type": "string", "pattern": "^[a-z]+$" },
+            "meta": { "patternProperties": { "^x-": {}, "^y-": {} } },
+            "kind": { "anyOf": [{ "const": 1 }, { "const": 2 }] },
+          },
+          "$defs": {
+            "a": { "properties": { "next": { "$ref": "#/$defs/b" } } },
+            "b": { "items": { "$ref": "#/$defs/a" } },
+          },
+        });
+        let cost = schema_cost(&complex);
+        assert_eq!(cost.regex_count, 3);
+        assert_eq!(cost.branch_product, 6);
+        assert_eq!(cost.max_ref_depth, 2);
+        assert!(cost.keyword_count > schema_cost(&simple).keyword_count);
+    }
+}
@@ -0,0 +1,201 @@
+use serde_json::{Map, Value};
+
+/// One parsed CSV record and the 1-based line it starts on.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Record {
+    pub line: usize,
+    pub cells: Vec<String>,
+}
+
+/// Splits RFC 4180 style CSV text into records. Quoted cells may contain commas,
+/// line breaks and `""` escapes; `\r\n` and `\n` both end a record, and blank
+/// lines are skipped.
+pub(crate) fn parse(text: &str) -> Result<Vec<Record>, String> {
+    let mut records = Vec::new();
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut line = 1;
+    let mut start_line = 1;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    cell.push(c);
+                }
+                _ => cell.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if cell.is_empty() => quoted = true,
+            ',' => cells.push(std::mem::take(&mut cell)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                cells.push(std::mem::take(&mut cell));
+                if cells.len() > 1 || !cells[0].is_empty() {
+                    records.push(Record {
+                        line: start_line,
+                        cells: std::mem::take(&mut cells),
+                    });
+                }
+                cells.clear();
+                line += 1;
+                start_line = line;
+            }
+            _ => cell.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!(
+            "unterminated quoted cell starting on line {start_line}"
+        ));
+    }
+    if !cells.is_empty() || !cell.is_empty() {
+        cells.push(cell);
+        records.push(Record {
+            line: start_line,
+            cells,
+        });
+    }
+    Ok(records)
+}
+
+/// Appends `cells` to `out` as one RFC 4180 record ending in `\r\n`, quoting
+/// the cells that contain a comma, a quote or a line break, so [`parse`] reads
+/// them back unchanged.
+pub(crate) fn write_record(out: &mut String, cells: &[&str]) {
+    for (index, cell) in cells.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        if cell.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&cell.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(cell);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// Builds the object for one row, keyed by `headers`. Each cell is coerced to the
+/// first of `integer`, `number`, `boolean` its property's `type` allows and it
+/// parses as; cells of properties that allow `string`, or declare no type, stay
+/// strings. Empty cells become `null` where allowed and are left out otherwise.
+pub(crate) fn row_object(schema: &Value, headers: &[String], cells: &[String]) -> Value {
+    let mut object = Map::new();
+    for (header, cell) in headers.iter().zip(cells) {
+        let types = declared_types(schema.pointer(&format!(
+            "/properties/{}",
+            header.replace('~', "~0").replace('/', "~1")
+        )));
+        let allows = |name: &str| types.iter().any(|t| t == name);
+        if cell.is_empty() {
+            if allows("null") {
+                object.insert(header.clone(), Value::Null);
+            }
+            continue;
+        }
+        let value = if types.is_empty() || allows("string") {
+            None
+        } else {
+            coerce(cell.trim(), allows)
+        };
+        object.insert(
+            header.clone(),
+            value.unwrap_or_else(|| Value::String(cell.clone())),
+        );
+    }
+    Value::Object(object)
+}
+
+fn declared_types(subschema: Option<&Value>) -> Vec<String> {
+    match subschema.and_then(|s| s.get("type")) {
+        Some(Value::String(name)) => vec![name.clone()],
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(|n| n.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn coerce(text: &str, allows: impl Fn(&str) -> bool) -> Option<Value> {
+    if allows("integer") || allows("number") {
+        if let Ok(integer) = text.parse::<i64>() {
+            return Some(Value::from(integer));
+        }
+    }
+    if allows("number") {
+        if let Some(number) = text.parse().ok().and_then(serde_json::Number::from_f64) {
+            return Some(Value::Number(number));
+        }
+    }
+    if allows("boolean") {
+        if let Ok(boolean) = text.parse::<bool>() {
+            return Some(Value::Bool(boolean));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_quoted_cells() {
+        let records = parse("a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\n\n1,2").unwrap();
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    line: 1,
+                    cells: vec!["a".into(), "b".into()]
+                },
+                Record {
+                    line: 2,
+                    cells: vec!["x, \"y\"".into(), "multi\nline".into()]
+                },
+                Record {
+                    line: 5,
+                    cells: vec!["1".into(), "2".into()]
+                },
+            ]
+        );
+        assert!(parse("a\n\"open").is_err());
+    }
+
+    #[test]
+    fn test_row_object_coerces_by_declared_type() {
+        let schema = json!({
+          "properties": {
+            "id": { "type": "integer" },
+            "zip": { "type": ["string", "integer"] },
+            "active": { "type": "boolean" },
+            "note": { "type": ["string", "null"] },
+            "score": { "type": "number" },
+          },
+        });
+        let headers: Vec<String> = ["id", "zip", "active", "note", "score", "extra"]
+            .map(String::from)
+            .to_vec();
+        let cells: Vec<String> = ["7", "01234", "true", "", "", "x"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            row_object(&schema, &headers, &cells),
+            json!({ "id": 7, "zip": "01234", "active": true, "note": null, "extra": "x" })
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::{fmt, sync::Arc};
+
+use jsonschema::{paths::LazyLocation, Keyword, ValidationError, ValidationOptions};
+use serde_json::{Map, Value};
+
+/// Checks an instance against a keyword's schema value. `Err(None)` rejects it
+/// with a default message.
+pub(crate) type KeywordCheck =
+    Arc<dyn Fn(&Value, &Value) -> Result<(), Option<String>> + Send + Sync>;
+
+/// A user-defined keyword, reported as `custom_error` when its check fails.
+#[derive(Clone)]
+pub(crate) struct CustomKeyword {
+    pub name: String,
+    pub check: KeywordCheck,
+}
+
+impl fmt::Debug for CustomKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomKeyword")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+struct CustomKeywordValidator {
+    name: String,
+    value: Value,
+    check: KeywordCheck,
+    location: jsonschema::paths::Location,
+    mask_values: bool,
+}
+
+impl Keyword for CustomKeywordValidator {
+    fn validate<'i>(
+        &self,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> Result<(), ValidationError<'i>> {
+        (self.check)(instance, &self.value).map_err(|message| {
+            let message = message.unwrap_or_else(|| {
+                if self.mask_values {
+                    format!("value is not valid under the `{}` keyword", self.name)
+                } else {
+                    format!(
+                        "{} is not valid under the `{}` keyword",
+                        instance, self.name
+                    )
+                }
+            });
+            ValidationError::custom(self.location.clone(), location.into(), instance, message)
+        })
+    }
+
+    fn is_valid(&self, instance: &Value) -> bool {
+        (self.check)(instance, &self.value).is_ok()
+    }
+}
+
+/// Registers every keyword with the builder. Custom keywords take precedence
+/// over built-in ones with the same name. With `mask_values`, the default
+/// message for a failure leaves the instance out.
+// The factory's error type is dictated by `with_keyword`.
+#[allow(clippy::result_large_err)]
+pub(crate) fn register(
+    keywords: &[CustomKeyword],
+    builder: ValidationOptions,
+    mask_values: bool,
+) -> ValidationOptions {
+    keywords.iter().fold(builder, |builder, keyword| {
+        let name = keyword.name.clone();
+        let check = keyword.check.clone();
+        builder.with_keyword(
+            keyword.name.clone(),
+            move |_: &Map<String, Value>, value: &Value, location| {
+                Ok(Box::new(CustomKeywordValidator {
+                    name: name.clone(),
+                    value: value.clone(),
+                    check: check.clone(),
+                    location,
+                    mask_values,
+                }) as Box<dyn Keyword>)
+            },
+        )
+    })
+}
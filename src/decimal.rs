@@ -0,0 +1,86 @@
+/// A decimal number as `mantissa * 10^exponent`.
+#[derive(Debug, PartialEq)]
+struct Decimal {
+    mantissa: i128,
+    exponent: i32,
+}
+
+/// Parses the textual form of a JSON number, e.g. `-12.5` or `1e-7`.
+fn parse(text: &str) -> Option<Decimal> {
+    let (significand, exponent) = match text.find(['e', 'E']) {
+        Some(index) => (&text[..index], text[index + 1..].parse::<i32>().ok()?),
+        None => (text, 0),
+    };
+    let (integer, fraction) = significand.split_once('.').unwrap_or((significand, ""));
+    let digits = format!("{}{}", integer, fraction);
+    Some(Decimal {
+        mantissa: digits.parse().ok()?,
+        exponent: exponent.checked_sub(i32::try_from(fraction.len()).ok()?)?,
+    })
+}
+
+/// Scales `value` down to `exponent`, which must not exceed its own.
+fn rescale(value: &Decimal, exponent: i32) -> Option<i128> {
+    let shift = u32::try_from(value.exponent - exponent).ok()?;
+    10i128.checked_pow(shift)?.checked_mul(value.mantissa)
+}
+
+/// Whether `value` is an exact multiple of `multiple`, comparing the decimal
+/// digits as written instead of their binary floating point approximations.
+/// Returns `None` when the numbers can't be compared exactly (too many digits).
+pub(crate) fn is_decimal_multiple(value: &str, multiple: &str) -> Option<bool> {
+    let value = parse(value)?;
+    let multiple = parse(multiple)?;
+    if multiple.mantissa == 0 {
+        return None;
+    }
+    let exponent = value.exponent.min(multiple.exponent);
+    Some(rescale(&value, exponent)? % rescale(&multiple, exponent)? == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decimal_forms() {
+        assert_eq!(
+            parse("0.3"),
+            Some(Decimal {
+                mantissa: 3,
+                exponent: -1
+            })
+        );
+        assert_eq!(
+            parse("-12.50"),
+            Some(Decimal {
+                mantissa: -1250,
+                exponent: -2
+            })
+        );
+        assert_eq!(
+            parse("1e-7"),
+            Some(Decimal {
+                mantissa: 1,
+                exponent: -7
+            })
+        );
+        assert_eq!(
+            parse("2.5E3"),
+            Some(Decimal {
+                mantissa: 25,
+                exponent: 2
+            })
+        );
+        assert_eq!(parse("abc"), None);
+    }
+
+    #[test]
+    fn test_decimal_multiples() {
+        assert_eq!(is_decimal_multiple("0.3", "0.1"), Some(true));
+        assert_eq!(is_decimal_multiple("19.99", "0.01"), Some(true));
+        assert_eq!(is_decimal_multiple("0.35", "0.1"), Some(false));
+        assert_eq!(is_decimal_multiple("300", "0.1"), Some(true));
+        assert_eq!(is_decimal_multiple("1", "0"), None);
+    }
+}
@@ -0,0 +1,74 @@
+use serde_json::Value;
+
+use crate::walk;
+
+/// Bounds how many levels of defaults that themselves gain defaults are filled,
+/// since a recursive schema could keep producing new ones.
+const MAX_ROUNDS: usize = 32;
+
+/// Fills absent object properties with the `default` of their subschema in
+/// `properties`, in place. Present values, including `null`, are never replaced.
+/// Defaults inserted in one pass are themselves filled in by the next.
+pub(crate) fn apply_defaults(schema: &Value, instance: &mut Value) {
+    for _ in 0..MAX_ROUNDS {
+        let mut edits = Vec::new();
+        walk::for_each_applied(schema, instance, &mut |node| {
+            let (Value::Object(object), Some(properties)) = (
+                node.instance,
+                node.schema.get("properties").and_then(Value::as_object),
+            ) else {
+                return true;
+            };
+            for (key, property) in properties {
+                if let Some(default) = property.get("default") {
+                    if !object.contains_key(key) {
+                        edits.push((node.instance_path.clone(), key.clone(), default.clone()));
+                    }
+                }
+            }
+            true
+        });
+        if edits.is_empty() {
+            return;
+        }
+        for (path, key, default) in edits {
+            if let Some(Value::Object(object)) = instance.pointer_mut(&path) {
+                object.entry(key).or_insert(default);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fill_nested_defaults_without_overwriting() {
+        let schema = json!({
+          "properties": {
+            "name": { "type": "string", "default": "anonymous" },
+            "settings": {
+              "type": "object",
+              "default": {},
+              "properties": {
+                "theme": { "default": "dark" },
+                "font": { "properties": { "size": { "default": 12 } }, "default": {} },
+              },
+            },
+            "items": { "items": { "properties": { "qty": { "default": 1 } } } },
+          },
+        });
+        let mut instance = json!({ "name": null, "items": [{}, { "qty": 5 }] });
+        apply_defaults(&schema, &mut instance);
+        assert_eq!(
+            instance,
+            json!({
+              "name": null,
+              "items": [{ "qty": 1 }, { "qty": 5 }],
+              "settings": { "theme": "dark", "font": { "size": 12 } },
+            })
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use jsonschema::Draft;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::walk;
+
+/// A JSON Schema draft, as named in options.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SchemaDraft {
+    #[serde(rename = "draft4")]
+    Draft4,
+    #[serde(rename = "draft6")]
+    Draft6,
+    #[serde(rename = "draft7")]
+    Draft7,
+    #[serde(rename = "draft2019-09")]
+    Draft201909,
+    #[serde(rename = "draft2020-12")]
+    Draft202012,
+}
+
+impl From<SchemaDraft> for Draft {
+    fn from(draft: SchemaDraft) -> Self {
+        match draft {
+            SchemaDraft::Draft4 => Draft::Draft4,
+            SchemaDraft::Draft6 => Draft::Draft6,
+            SchemaDraft::Draft7 => Draft::Draft7,
+            SchemaDraft::Draft201909 => Draft::Draft201909,
+            SchemaDraft::Draft202012 => Draft::Draft202012,
+        }
+    }
+}
+
+/// Keywords that only exist in some drafts, with the first and last draft
+/// that define them.
+const DRAFT_SPECIFIC_KEYWORDS: &[(&str, Draft, Draft)] = &[
+    ("id", Draft::Draft4, Draft::Draft4),
+    ("$id", Draft::Draft6, Draft::Draft202012),
+    ("const", Draft::Draft6, Draft::Draft202012),
+    ("contains", Draft::Draft6, Draft::Draft202012),
+    ("propertyNames", Draft::Draft6, Draft::Draft202012),
+    ("examples", Draft::Draft6, Draft::Draft202012),
+    ("if", Draft::Draft7, Draft::Draft202012),
+    ("then", Draft::Draft7, Draft::Draft202012),
+    ("else", Draft::Draft7, Draft::Draft202012),
+    ("$comment", Draft::Draft7, Draft::Draft202012),
+    ("dependencies", Draft::Draft4, Draft::Draft7),
+    ("$defs", Draft::Draft201909, Draft::Draft202012),
+    ("$anchor", Draft::Draft201909, Draft::Draft202012),
+    ("dependentRequired", Draft::Draft201909, Draft::Draft202012),
+    ("dependentSchemas", Draft::Draft201909, Draft::Draft202012),
+    ("maxContains", Draft::Draft201909, Draft::Draft202012),
+    ("minContains", Draft::Draft201909, Draft::Draft202012),
+    ("unevaluatedItems", Draft::Draft201909, Draft::Draft202012),
+    (
+        "unevaluatedProperties",
+        Draft::Draft201909,
+        Draft::Draft202012,
+    ),
+    ("$recursiveRef", Draft::Draft201909, Draft::Draft201909),
+    ("$recursiveAnchor", Draft::Draft201909, Draft::Draft201909),
+    ("additionalItems", Draft::Draft4, Draft::Draft201909),
+    ("prefixItems", Draft::Draft202012, Draft::Draft202012),
+    ("$dynamicRef", Draft::Draft202012, Draft::Draft202012),
+    ("$dynamicAnchor", Draft::Draft202012, Draft::Draft202012),
+];
+
+/// A keyword that the selected draft doesn't define and will silently ignore.
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct CompileWarning {
+    pub keyword: String,
+    pub schema_path: String,
+    pub message: String,
+}
+
+fn draft_name(draft: Draft) -> &'static str {
+    match draft {
+        Draft::Draft4 => "Draft 4",
+        Draft::Draft6 => "Draft 6",
+        Draft::Draft7 => "Draft 7",
+        Draft::Draft201909 => "Draft 2019-09",
+        _ => "Draft 2020-12",
+    }
+}
+
+/// Finds keywords in `schema` that `draft` doesn't support.
+pub(crate) fn incompatible_keywords(schema: &Value, draft: Draft) -> Vec<CompileWarning> {
+    let mut warnings = Vec::new();
+    walk::for_each_subschema(schema, &mut |subschema, path, _| {
+        let Value::Object(object) = subschema else {
+            return true;
+        };
+        for (keyword, value) in object {
+            let unsupported = DRAFT_SPECIFIC_KEYWORDS
+                .iter()
+                .find(|(name, since, until)| name == keyword && (draft < *since || draft > *until));
+            let message = if let Some((_, since, until)) = unsupported {
+                let range = if since == until {
+                    format!("only defined in {}", draft_name(*since))
+                } else {
+                    format!(
+                        "defined from {} to {}",
+                        draft_name(*since),
+                        draft_name(*until)
+                    )
+                };
+                format!(
+                    "`{}` is {} and is ignored under {}",
+                    keyword,
+                    range,
+                    draft_name(draft)
+                )
+            } else if keyword == "items" && value.is_array() && draft == Draft::Draft202012 {
+                "The array form of `items` was replaced by `prefixItems` in Draft 2020-12"
+                    .to_string()
+            } else {
+                continue;
+            };
+            warnings.push(CompileWarning {
+                keyword: keyword.clone(),
+                schema_path: walk::join(path, keyword),
+                message,
+            });
+        }
+        true
+    });
+    warnings
+}
+
+/// Finds subschemas below the root that declare their own `$schema`. Separate
+/// resources, loaded by URI, are compiled under the draft they declare, but an
+/// embedded subschema is checked against the root's meta-schema and its
+/// `$schema` isn't honored.
+pub(crate) fn embedded_dialects(schema: &Value) -> Vec<CompileWarning> {
+    let mut warnings = Vec::new();
+    walk::for_each_subschema(schema, &mut |subschema, path, _| {
+        if !path.is_empty() && subschema.get("$schema").is_some_and(Value::is_string) {
+            warnings.push(CompileWarning {
+                keyword: "$schema".to_string(),
+                schema_path: walk::join(path, "$schema"),
+                message: "`$schema` is not honored in an embedded subschema, which follows the \
+                          root's draft; load it as a separate resource instead"
+                    .to_string(),
+            });
+        }
+        true
+    });
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_warn_about_prefix_items_on_draft7() {
+        let schema = json!({
+          "type": "object",
+          "properties": { "pair": { "type": "array", "prefixItems": [{ "type": "string" }] } },
+        });
+        let warnings = incompatible_keywords(&schema, Draft::Draft7);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].keyword, "prefixItems");
+        assert_eq!(warnings[0].schema_path, "/properties/pair/prefixItems");
+        assert!(incompatible_keywords(&schema, Draft::Draft202012).is_empty());
+    }
+
+    #[test]
+    fn test_warn_about_defs_on_draft4_and_items_array_on_2020() {
+        let schema = json!({ "$defs": { "a": { "items": [true] } } });
+        let warnings = incompatible_keywords(&schema, Draft::Draft4);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].keyword, "$defs");
+        let warnings = incompatible_keywords(&schema, Draft::Draft202012);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].schema_path, "/$defs/a/items");
+    }
+
+    #[test]
+    fn test_warn_about_embedded_dialect() {
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "$defs": { "legacy": { "$schema": "http://json-schema.org/draft-07/schema#" } },
+        });
+        let warnings = embedded_dialects(&schema);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].schema_path, "/$defs/legacy/$schema");
+    }
+}
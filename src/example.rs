@@ -0,0 +1,198 @@
+use serde_json::{Map, Value};
+
+use crate::walk;
+
+/// Bounds how deep examples nest, since a recursive schema could require an
+/// ever deeper value.
+const MAX_DEPTH: usize = 32;
+
+/// Synthesizes a small value `schema` accepts, best-effort: the `const`, the
+/// first `enum` value, or else a value of the first declared (or implied) type
+/// sized to its lower bounds. Objects get their required properties, arrays
+/// their `minItems` items, and the first branch of a `oneOf`/`anyOf` is used.
+/// `pattern`, `multipleOf` and `not` aren't considered. `None` for `false` and
+/// for schemas too deeply nested to follow.
+///
+/// With a `seed`, the `enum` value and the `oneOf`/`anyOf` branch are picked
+/// pseudo-randomly instead, the same way for the same seed.
+pub(crate) fn example_for(root: &Value, schema: &Value, seed: Option<u64>) -> Option<Value> {
+    example(root, schema, 0, &mut Choices { state: seed })
+}
+
+/// Picks among alternatives: always the first without a seed, otherwise from a
+/// SplitMix64 sequence, which is tiny and reproducible across platforms.
+struct Choices {
+    state: Option<u64>,
+}
+
+impl Choices {
+    fn pick(&mut self, len: usize) -> usize {
+        let Some(state) = &mut self.state else {
+            return 0;
+        };
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((z ^ (z >> 31)) % len.max(1) as u64) as usize
+    }
+}
+
+fn example(root: &Value, schema: &Value, depth: usize, choices: &mut Choices) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let object = match schema {
+        Value::Bool(true) => return Some(Value::Null),
+        Value::Object(object) => object,
+        _ => return None,
+    };
+    if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+        let target = walk::resolve_local_ref(root, reference)?;
+        return example(root, target, depth + 1, choices);
+    }
+    if let Some(value) = object.get("const") {
+        return Some(value.clone());
+    }
+    if let Some(Value::Array(options)) = object.get("enum") {
+        return options.get(choices.pick(options.len())).cloned();
+    }
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(branches) = object.get(keyword).and_then(Value::as_array) {
+            if let Some(branch) = branches.get(choices.pick(branches.len())) {
+                return example(root, branch, depth + 1, choices);
+            }
+        }
+    }
+    let number = |name| object.get(name).and_then(Value::as_f64);
+    let count = |name| object.get(name).and_then(Value::as_u64).unwrap_or(0);
+    Some(match declared_type(object) {
+        "string" => Value::from(string_example(object, count("minLength"))),
+        "integer" => {
+            let low = number("minimum")
+                .map(f64::ceil)
+                .or(number("exclusiveMinimum").map(|limit| limit.floor() + 1.0));
+            let high = number("maximum")
+                .map(f64::floor)
+                .or(number("exclusiveMaximum").map(|limit| limit.ceil() - 1.0));
+            Value::from(low.or(high.filter(|high| *high < 0.0)).unwrap_or(0.0) as i64)
+        }
+        "number" => {
+            let low = number("minimum").or(number("exclusiveMinimum").map(|limit| limit + 1.0));
+            let high = number("maximum").or(number("exclusiveMaximum").map(|limit| limit - 1.0));
+            match low.or(high.filter(|high| *high < 0.0)) {
+                Some(value) if value.fract() == 0.0 => Value::from(value as i64),
+                Some(value) => Value::from(value),
+                None => Value::from(0),
+            }
+        }
+        "boolean" => Value::Bool(false),
+        "array" => {
+            let prefix = object.get("prefixItems").and_then(Value::as_array);
+            let items = object.get("items").unwrap_or(&Value::Bool(true));
+            let items = (0..count("minItems") as usize)
+                .map(|index| {
+                    let item = prefix.and_then(|prefix| prefix.get(index)).unwrap_or(items);
+                    example(root, item, depth + 1, choices)
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Value::Array(items)
+        }
+        "object" => {
+            let properties = object.get("properties");
+            let mut example_object = Map::new();
+            for name in object
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                let property = properties
+                    .and_then(|properties| properties.get(name))
+                    .unwrap_or(&Value::Bool(true));
+                example_object.insert(
+                    name.to_string(),
+                    example(root, property, depth + 1, choices)?,
+                );
+            }
+            Value::Object(example_object)
+        }
+        _ => Value::Null,
+    })
+}
+
+/// The first type `type` lists, or the one its other keywords imply.
+fn declared_type(object: &Map<String, Value>) -> &str {
+    match object.get("type") {
+        Some(Value::String(name)) => return name,
+        Some(Value::Array(names)) => {
+            if let Some(name) = names.iter().find_map(Value::as_str) {
+                return name;
+            }
+        }
+        _ => {}
+    }
+    let implies = |keywords: &[&str]| keywords.iter().any(|k| object.contains_key(*k));
+    if implies(&[
+        "properties",
+        "required",
+        "additionalProperties",
+        "minProperties",
+    ]) {
+        "object"
+    } else if implies(&["items", "prefixItems", "minItems", "contains"]) {
+        "array"
+    } else if implies(&["minLength", "maxLength", "pattern", "format"]) {
+        "string"
+    } else if implies(&["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum"]) {
+        "number"
+    } else {
+        "null"
+    }
+}
+
+/// A string of the common `format`s, or else `minLength` letters.
+fn string_example(object: &Map<String, Value>, min_length: u64) -> String {
+    let formatted = match object.get("format").and_then(Value::as_str) {
+        Some("date") => "1970-01-01",
+        Some("date-time") => "1970-01-01T00:00:00Z",
+        Some("time") => "00:00:00Z",
+        Some("email") => "user@example.com",
+        Some("uri") => "https://example.com",
+        Some("uuid") => "00000000-0000-0000-0000-000000000000",
+        Some("ipv4") => "127.0.0.1",
+        _ => "",
+    };
+    if formatted.is_empty() {
+        "a".repeat(min_length as usize)
+    } else {
+        formatted.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_example_for_common_keywords() {
+        let schema = json!({
+          "type": "object",
+          "required": ["id", "tags", "ref"],
+          "properties": {
+            "id": { "type": "integer", "exclusiveMinimum": 0 },
+            "tags": { "type": "array", "minItems": 1, "items": { "format": "date" } },
+            "ref": { "$ref": "#/$defs/price" },
+            "note": { "type": "string" },
+          },
+          "$defs": { "price": { "type": "number", "minimum": 0.5 } },
+        });
+        assert_eq!(
+            example_for(&schema, &schema, None),
+            Some(json!({ "id": 1, "tags": ["1970-01-01"], "ref": 0.5 }))
+        );
+        assert_eq!(example_for(&schema, &json!(false), None), None);
+    }
+}
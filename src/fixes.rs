@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use jsonschema::{
+    error::{TypeKind, ValidationErrorKind},
+    JsonType, ValidationError, Validator,
+};
+use serde_json::{json, Value};
+
+use crate::walk;
+
+/// JSON Patch operations that repair the trivially fixable errors `instance`
+/// has: a missing required property whose schema has a `default` is added, a
+/// value of the wrong type that converts losslessly (`"42"` to `42`, `true` to
+/// `"true"`) is replaced, and a number outside `minimum`/`maximum` is clamped.
+/// Other errors are left alone, and each path is patched at most once.
+pub(crate) fn suggest_fixes(schema: &Value, validator: &Validator, instance: &Value) -> Vec<Value> {
+    let mut patched = HashSet::new();
+    let mut patch = Vec::new();
+    for error in validator.iter_errors(instance) {
+        let path = error.instance_path.as_str();
+        let fix = match &error.kind {
+            ValidationErrorKind::Required { property } => {
+                required_default(schema, &error, property).map(|(property, value)| {
+                    json!({ "op": "add", "path": walk::join(path, property), "value": value })
+                })
+            }
+            ValidationErrorKind::Type { kind } => {
+                let types: Vec<JsonType> = match kind {
+                    TypeKind::Single(single) => vec![*single],
+                    TypeKind::Multiple(set) => set.iter().collect(),
+                };
+                types
+                    .into_iter()
+                    .find_map(|target| convert(&error.instance, target))
+                    .map(|value| json!({ "op": "replace", "path": path, "value": value }))
+            }
+            ValidationErrorKind::Minimum { limit } | ValidationErrorKind::Maximum { limit } => {
+                Some(json!({ "op": "replace", "path": path, "value": limit }))
+            }
+            _ => None,
+        };
+        if let Some(fix) = fix {
+            if patched.insert(fix["path"].as_str().unwrap_or_default().to_string()) {
+                patch.push(fix);
+            }
+        }
+    }
+    patch
+}
+
+/// The `default` of the missing `property`, read from the `properties` next to
+/// the failed `required` keyword.
+fn required_default<'a>(
+    schema: &'a Value,
+    error: &ValidationError,
+    property: &'a Value,
+) -> Option<(&'a str, &'a Value)> {
+    let property = property.as_str()?;
+    let parent = error.schema_path.as_str().strip_suffix("/required")?;
+    let default = schema
+        .pointer(parent)?
+        .get("properties")?
+        .get(property)?
+        .get("default")?;
+    Some((property, default))
+}
+
+/// `value` as `target`, if the conversion loses nothing.
+fn convert(value: &Value, target: JsonType) -> Option<Value> {
+    match (value, target) {
+        (Value::String(text), JsonType::Integer) => {
+            text.trim().parse::<i64>().ok().map(Value::from)
+        }
+        (Value::String(text), JsonType::Number) => {
+            let text = text.trim();
+            text.parse::<i64>()
+                .ok()
+                .map(Value::from)
+                .or_else(|| serde_json::Number::from_f64(text.parse().ok()?).map(Value::Number))
+        }
+        (Value::String(text), JsonType::Boolean) => {
+            text.trim().parse::<bool>().ok().map(Value::Bool)
+        }
+        (Value::Number(_) | Value::Bool(_), JsonType::String) => {
+            Some(Value::from(value.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_is_lossless() {
+        assert_eq!(convert(&json!(" 42 "), JsonType::Integer), Some(json!(42)));
+        assert_eq!(convert(&json!("4.5"), JsonType::Integer), None);
+        assert_eq!(convert(&json!("4.5"), JsonType::Number), Some(json!(4.5)));
+        assert_eq!(convert(&json!("yes"), JsonType::Boolean), None);
+        assert_eq!(convert(&json!(true), JsonType::String), Some(json!("true")));
+        assert_eq!(convert(&json!([1]), JsonType::String), None);
+    }
+}
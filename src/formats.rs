@@ -0,0 +1,113 @@
+use jsonschema::{Draft, ValidationOptions};
+
+/// Built-in formats other than `date`, `time` and `date-time`.
+const NON_DATE_FORMATS: &[&str] = &[
+    "duration",
+    "email",
+    "hostname",
+    "idn-email",
+    "idn-hostname",
+    "ipv4",
+    "ipv6",
+    "iri",
+    "iri-reference",
+    "json-pointer",
+    "regex",
+    "relative-json-pointer",
+    "uri",
+    "uri-reference",
+    "uri-template",
+    "uuid",
+];
+
+/// Asserts the `date`, `time` and `date-time` formats even on drafts that treat
+/// `format` as an annotation. Every other built-in format is overridden with an
+/// always-valid check, so enabling assertion doesn't turn them on as well.
+/// `draft` is the one the schema is compiled under.
+pub(crate) fn enforce_date_formats(builder: ValidationOptions, draft: Draft) -> ValidationOptions {
+    if matches!(draft, Draft::Draft4 | Draft::Draft6 | Draft::Draft7) {
+        // These drafts already assert every format.
+        return builder;
+    }
+    NON_DATE_FORMATS
+        .iter()
+        .fold(builder.should_validate_formats(true), |builder, name| {
+            builder.with_format(*name, |_: &str| true)
+        })
+}
+
+/// Accepts any `local@domain` address without whitespace whose domain is a
+/// hostname per [`is_lax_hostname`]. Quoted local parts, comments and other rarely
+/// used RFC 5322 forms are rejected.
+fn is_lax_email(value: &str) -> bool {
+    match value.rsplit_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !local.chars().any(|c| c.is_whitespace() || c == '@')
+                && domain.contains('.')
+                && is_lax_hostname(domain)
+        }
+        None => false,
+    }
+}
+
+/// Accepts dot-separated labels of ASCII letters, digits, `-` and `_`, up to 253
+/// characters, with an optional trailing dot. Unlike RFC 1123, labels may contain
+/// underscores and start or end with a hyphen.
+fn is_lax_hostname(value: &str) -> bool {
+    let value = value.strip_suffix('.').unwrap_or(value);
+    !value.is_empty()
+        && value.len() <= 253
+        && value.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// Replaces the built-in `email` and `hostname` checks with pragmatic ones that
+/// accept addresses real users have. Whether `format` is asserted still follows
+/// the draft.
+pub(crate) fn lax_email(builder: ValidationOptions) -> ValidationOptions {
+    builder
+        .with_format("email", is_lax_email)
+        .with_format("hostname", is_lax_hostname)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lax_formats_accept_what_strict_rejects() {
+        let schema = json!({
+          "$schema": "http://json-schema.org/draft-07/schema#",
+          "properties": {
+            "email": { "format": "email" },
+            "host": { "format": "hostname" },
+          },
+        });
+        let strict = jsonschema::validator_for(&schema).unwrap();
+        let lax = lax_email(jsonschema::options()).build(&schema).unwrap();
+        for (field, value) in [
+            ("email", "first..last@example.com"),
+            ("email", "user@my_host.example.com"),
+            ("host", "_dmarc.example.com"),
+            ("host", "-edge.example.com"),
+        ] {
+            let instance = json!({ field: value });
+            assert!(!strict.is_valid(&instance), "{value}");
+            assert!(lax.is_valid(&instance), "{value}");
+        }
+        for (field, value) in [
+            ("email", "no-at-sign"),
+            ("email", "a b@example.com"),
+            ("email", "user@localhost"),
+            ("host", "bad..example.com"),
+        ] {
+            assert!(!lax.is_valid(&json!({ field: value })), "{value}");
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+
+/// Parses JSON text like `serde_json::from_str`, but fails on an object that
+/// repeats a key instead of silently keeping the last value.
+pub(crate) fn from_str_unique_keys(text: &str) -> Result<Value, serde_json::Error> {
+    serde_json::from_str::<UniqueKeys>(text).map(|parsed| parsed.0)
+}
+
+/// Parses JSON5 text: comments, trailing commas, unquoted keys, single-quoted
+/// strings and hexadecimal numbers are accepted. Errors read like serde_json's,
+/// ending in `at line L column C`.
+pub(crate) fn from_json5_str(text: &str) -> Result<Value, String> {
+    json5::from_str(text).map_err(|json5::Error::Message { msg, location }| {
+        // Syntax errors come as a multi-line excerpt ending in "= expected ...".
+        let msg = msg
+            .lines()
+            .last()
+            .map(|line| line.trim().trim_start_matches("= "))
+            .unwrap_or_default();
+        match location {
+            Some(location) => format!(
+                "{} at line {} column {}",
+                msg, location.line, location.column
+            ),
+            None => msg.to_string(),
+        }
+    })
+}
+
+struct UniqueKeys(Value);
+
+impl<'de> Deserialize<'de> for UniqueKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(UniqueKeysVisitor)
+            .map(UniqueKeys)
+    }
+}
+
+struct UniqueKeysVisitor;
+
+impl<'de> Visitor<'de> for UniqueKeysVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(value).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(UniqueKeys(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if object.contains_key(&key) {
+                return Err(de::Error::custom(format_args!("duplicate key `{}`", key)));
+            }
+            let UniqueKeys(value) = map.next_value()?;
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_matches_serde_json_without_duplicates() {
+        let text = r#"{"a": [1, -2, 3.5, "x", null, true], "b": {"c": {}}}"#;
+        assert_eq!(
+            from_str_unique_keys(text).unwrap(),
+            serde_json::from_str::<Value>(text).unwrap()
+        );
+        assert_eq!(from_str_unique_keys("[]").unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_reject_nested_duplicate_key() {
+        let error = from_str_unique_keys(r#"{"a": {"id": 1, "id": 2}}"#).unwrap_err();
+        assert!(error.to_string().contains("duplicate key `id`"), "{error}");
+        assert!(from_str_unique_keys(r#"{"a": 1} x"#).is_err());
+    }
+}
@@ -1,35 +1,823 @@
-use serde::{Deserialize, Serialize};
-use jsonschema::{error::ValidationErrorKind, Validator};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use jsonschema::{error::ValidationErrorKind, ValidationError, Validator};
 use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex};
+use unicode_segmentation::UnicodeSegmentation;
 use wasm_bindgen::prelude::*;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+mod access;
+mod bundle;
+mod cache;
+mod clock;
+mod closed;
+mod collapse;
+mod completeness;
+mod cost;
+mod csv;
+mod custom;
+mod decimal;
+mod defaults;
+mod drafts;
+mod example;
+mod fixes;
+mod formats;
+mod json;
+mod limits;
+mod normalize;
+mod patch;
+mod paths;
+mod properties;
+mod resources;
+mod suggest;
+mod templates;
+mod walk;
+
+pub use access::AccessContext;
+pub use drafts::SchemaDraft;
+pub use paths::PathFormat;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct ValidationIssue {
+    /// Stable key derived from `path`, `schema_path` and `code` only, so the same
+    /// logical error keeps its id while the offending value changes. For
+    /// `missing_property` and the dependency codes, which share their location
+    /// with every other missing property, the property name is included too.
+    pub id: String,
     pub path: String,
     pub message: String,
     pub code: String,
+    /// JSON Pointer to the schema keyword that produced the error.
+    pub schema_path: String,
+    /// User-perceived length (extended grapheme clusters) of the string for
+    /// `minLength`/`maxLength` violations, which count code points instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grapheme_count: Option<usize>,
+    /// Compact numeric form of `code`, see [`CODE_IDS`].
+    pub code_id: u16,
+    /// For `enum_mismatch`/`const_mismatch` on a string, the closest allowed string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// `message` with `{name}` placeholders, for clients that localize or restyle it.
+    pub message_template: String,
+    /// Values for the placeholders in `message_template`. `value` is omitted when
+    /// values are masked.
+    pub message_params: serde_json::Map<String, Value>,
+    /// For a summary produced by `collapse_arrays`, how many items failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// For a summary produced by `collapse_arrays`, the indices of the failing items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indices: Option<Vec<usize>>,
+    /// For issues raised through a `$ref`, the keyword's location as a URI with a
+    /// JSON Pointer fragment into the referenced document. Just the document's
+    /// URI when the pointer can't be determined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_keyword_location: Option<String>,
+    /// For `too_few_items`, `too_few_properties` and `too_short`, how many more
+    /// items, properties or code points are needed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortfall: Option<u64>,
+    /// For `too_many_items`, `too_many_properties` and `too_long`, how many must
+    /// be removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excess: Option<u64>,
+    /// For `one_of_multiple_matches`, the indices of the `oneOf` branches the value
+    /// matched. Not determined for a `oneOf` reached through `$ref`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_branches: Option<Vec<usize>>,
+    /// For `pattern_mismatch`, and `invalid_property_name` caused by a pattern,
+    /// the regular expression from the schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Number of segments in `path` as a JSON Pointer: 0 for the root, 2 for
+    /// `/profile/age`.
+    pub depth: usize,
+    /// With `path_format: "dot"`, `path` spelled as `tags[0]`. `path` itself
+    /// stays a JSON Pointer, so it can still be resolved against the instance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_path: Option<String>,
+    /// With the `doc_base_url` option, a link to the docs for the failed keyword:
+    /// `{doc_base_url}#{keyword}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_url: Option<String>,
+    /// With the `include_both_messages` option, `message` with the instance
+    /// values redacted, while `message` itself keeps them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub masked_message: Option<String>,
+    /// From `validate_with_context`, the part of the instance around the failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_value: Option<Value>,
+    /// For `negated_schema_match`, the `not` subschema the value wrongly matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negated_schema: Option<Value>,
+    /// The JSON type of the failing value, e.g. `string`. Kept when values are
+    /// masked, since a type reveals nothing sensitive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_type: Option<String>,
+    /// For `missing_property` and `additional_properties`, the keys the object
+    /// does have, with the `include_sibling_keys` option. Values are never included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sibling_keys: Option<Vec<String>>,
+    /// Whether this crate wrote `message`, rather than `jsonschema` or the schema
+    /// author. Only such messages are rewritten by `normalize_messages`.
+    #[serde(skip)]
+    generated_message: bool,
+}
+
+impl ValidationIssue {
+    /// Issues that don't come from a schema keyword template their whole message.
+    fn new(path: String, message: String, code: &str) -> Self {
+        let mut message_params = serde_json::Map::new();
+        message_params.insert("message".to_string(), Value::from(message.as_str()));
+        ValidationIssue {
+            id: issue_id(&path, "", code),
+            depth: path_depth(&path),
+            path,
+            message,
+            code: code.to_string(),
+            code_id: code_id(code),
+            message_template: "{message}".to_string(),
+            message_params,
+            generated_message: true,
+            ..ValidationIssue::default()
+        }
+    }
+
+    fn with_path(self, path: String) -> Self {
+        ValidationIssue {
+            id: issue_id(&path, &self.schema_path, &self.code),
+            depth: path_depth(&path),
+            path,
+            ..self
+        }
+    }
+
+    fn with_schema_path(self, schema_path: String) -> Self {
+        ValidationIssue {
+            id: issue_id(&self.path, &schema_path, &self.code),
+            schema_path,
+            ..self
+        }
+    }
+}
+
+/// The number of non-empty segments in a JSON Pointer, so that the document-level
+/// `/` counts as the root too.
+fn path_depth(path: &str) -> usize {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .count()
+}
+
+/// 64-bit FNV-1a of the NUL-separated fields, as 16 hex digits. Spelled out
+/// rather than using `DefaultHasher`, whose output may change between Rust releases.
+fn issue_id(path: &str, schema_path: &str, code: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let fields = path.bytes().chain([0]).chain(schema_path.bytes());
+    for byte in fields.chain([0]).chain(code.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Codes about the shape of an instance (its types and which properties or items
+/// it has) as opposed to its values, kept by the `structural_only` option.
+const STRUCTURAL_CODES: &[&str] = &[
+    "invalid_type",
+    "missing_property",
+    "dependent_required",
+    "additional_properties",
+    "unevaluated_properties",
+    "additional_items",
+    "unevaluated_items",
+];
+
+/// Numeric ids for every issue code. This table is append-only: an id is never
+/// reassigned or reused, so clients can rely on it across versions.
+const CODE_IDS: &[(&str, u16)] = &[
+    ("too_small", 1),
+    ("invalid_type", 2),
+    ("missing_property", 3),
+    ("too_large", 4),
+    ("too_short", 5),
+    ("too_long", 6),
+    ("pattern_mismatch", 7),
+    ("enum_mismatch", 8),
+    ("const_mismatch", 9),
+    ("format_mismatch", 10),
+    ("additional_properties", 11),
+    ("additional_items", 12),
+    ("too_few_items", 13),
+    ("too_many_items", 14),
+    ("duplicate_items", 15),
+    ("too_few_properties", 16),
+    ("too_many_properties", 17),
+    ("exclusive_min", 18),
+    ("exclusive_max", 19),
+    ("not_a_multiple", 20),
+    ("any_of_mismatch", 21),
+    ("one_of_no_match", 22),
+    ("one_of_multiple_matches", 23),
+    ("negated_schema_match", 24),
+    ("disallowed_value", 25),
+    ("no_match_in_contains", 26),
+    ("invalid_property_name", 27),
+    ("unevaluated_items", 28),
+    ("unevaluated_properties", 29),
+    ("invalid_content_encoding", 30),
+    ("invalid_media_type", 31),
+    ("invalid_utf8", 32),
+    ("regex_backtrack_limit", 33),
+    ("custom_error", 34),
+    ("schema_reference_error", 35),
+    ("invalid_schema", 36),
+    ("instance_too_large", 37),
+    ("invalid_json", 38),
+    ("readonly_write", 39),
+    ("writeonly_read", 40),
+    ("operation_limit_exceeded", 41),
+    ("schema_too_large", 42),
+    ("invalid_csv", 43),
+    ("dependent_required", 44),
+    ("dependent_schema", 45),
+];
+
+/// Returns the numeric id of `code`, or 0 for codes missing from [`CODE_IDS`].
+fn code_id(code: &str) -> u16 {
+    CODE_IDS
+        .iter()
+        .find(|(name, _)| *name == code)
+        .map_or(0, |(_, id)| *id)
+}
+
+/// Options accepted by every validation entry point.
+///
+/// On the JS side this is either a plain boolean (the legacy `mask_values` flag)
+/// or an object with any subset of these fields.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ValidatorOptions {
+    /// Redact instance values from error messages.
+    pub mask_values: bool,
+    /// JSON Pointer prepended to every issue path, e.g. `/order`. A trailing `/`
+    /// is dropped.
+    #[serde(deserialize_with = "deserialize_base_path")]
+    pub base_path: Option<String>,
+    /// Reject instances nested deeper than this many levels before validating.
+    pub max_depth: Option<usize>,
+    /// Reject instances with more than this many values before validating.
+    pub max_nodes: Option<usize>,
+    /// A custom meta-schema, identified by its `$id`. Schemas declaring it as their
+    /// `$schema` compile against it and must conform to it.
+    pub meta_schema: Option<Value>,
+    /// Assert the `date`, `time` and `date-time` formats even on drafts where
+    /// `format` is only an annotation, without asserting any other format.
+    pub enforce_date_formats: bool,
+    /// Use pragmatic `email` and `hostname` checks instead of strict RFC ones.
+    pub lax_email: bool,
+    /// Reject `readOnly` properties when writing, or `writeOnly` ones when reading.
+    pub context: Option<AccessContext>,
+    /// Re-check reported `multipleOf` failures with exact decimal arithmetic, so
+    /// that e.g. `0.3` is accepted as a multiple of `0.1`.
+    pub decimal_multiple_of: bool,
+    /// Apply `x-trim`/`x-lowercase` schema annotations to string values before
+    /// validating, and return the normalized instance from `validate`.
+    pub normalize: bool,
+    /// Reject instance strings that repeat a key within an object, in the
+    /// functions that parse JSON text.
+    pub reject_duplicate_keys: bool,
+    /// Report compilation and validation time in `validate_result`.
+    pub profile: bool,
+    /// Replace issues that repeat across the items of an array with one summary
+    /// issue per array and constraint.
+    pub collapse_arrays: bool,
+    /// Stop after this many errors have been produced by the validator, keeping
+    /// the issues gathered so far and adding an `operation_limit_exceeded` issue,
+    /// which the code filters never remove. Errors are counted before filtering.
+    pub operation_limit: Option<u64>,
+    /// Compile with this draft instead of detecting it from `$schema`.
+    pub draft: Option<SchemaDraft>,
+    /// Compile schemas that don't declare `$schema` as Draft 4, so legacy schemas
+    /// using `id`, `definitions` refs and boolean `exclusiveMinimum` compile.
+    /// `draft` takes precedence.
+    pub legacy_definitions: bool,
+    /// Take an issue's message from the `errorMessage` object of the subschema
+    /// that failed, keyed by keyword as in `ajv-errors`, when it has an entry.
+    pub use_schema_messages: bool,
+    /// Fill in each issue's `doc_url` as this URL plus `#` and the failed keyword.
+    pub doc_base_url: Option<String>,
+    /// On a `WasmValidator`, remember the result for each distinct instance (up to
+    /// a bounded number) and return it for identical instances without revalidating.
+    pub memoize: bool,
+    /// Fill in `masked_message` alongside an unmasked `message`, overriding
+    /// `mask_values` for `message`.
+    pub include_both_messages: bool,
+    /// Validate object properties whose value is `null` as if they were absent,
+    /// so `required` reports them and their subschemas don't apply.
+    pub treat_null_as_missing: bool,
+    /// Keep only issues about the instance's shape, see [`STRUCTURAL_CODES`].
+    pub structural_only: bool,
+    /// Lowercase the first letter of the messages this crate writes itself and
+    /// drop their trailing period, so they slot into a sentence.
+    pub normalize_messages: bool,
+    /// On a `WasmValidator`, keep the URIs of the external documents compilation
+    /// resolved `$ref`s to, for `WasmValidator.ref_report()`.
+    pub record_refs: bool,
+    /// Fill in `sibling_keys` on `missing_property` and `additional_properties`
+    /// issues, to help spot misspelled keys.
+    pub include_sibling_keys: bool,
+    /// Cut longer messages down to this many characters, the last one being `…`.
+    /// `0` empties every message.
+    pub max_message_len: Option<usize>,
+    /// Take an issue's code from a `$comment` of `code:SOME_CODE` on the
+    /// subschema that failed, overriding the built-in code.
+    pub codes_from_comment: bool,
+    /// Drop issues with these codes before returning.
+    pub ignore_codes: Vec<String>,
+    /// Keep only issues with these codes.
+    pub only_codes: Option<Vec<String>>,
+    /// Report properties no `properties`/`patternProperties` declare as
+    /// `additional_properties`, as if every object had `additionalProperties: false`.
+    pub closed: bool,
+    /// Accept strings that parse as numbers where a `number`/`integer` is
+    /// expected, validating the parsed number. The instance isn't modified.
+    pub numeric_strings: bool,
+    /// Extra spelling of issue paths, in `display_path`. `path` and `id` always
+    /// use the JSON Pointer.
+    pub path_format: PathFormat,
+    /// Custom keywords. Set from the functions in the JS `keywords` option.
+    #[serde(skip)]
+    pub(crate) keywords: Vec<custom::CustomKeyword>,
+}
+
+fn parse_options(options_js: JsValue) -> Result<ValidatorOptions, JsValue> {
+    if options_js.is_undefined() || options_js.is_null() {
+        return Ok(ValidatorOptions::default());
+    }
+    if let Some(mask_values) = options_js.as_bool() {
+        return Ok(ValidatorOptions {
+            mask_values,
+            ..ValidatorOptions::default()
+        });
+    }
+    let keywords = js_sys::Reflect::get(&options_js, &JsValue::from_str("keywords"))
+        .ok()
+        .filter(|keywords| keywords.is_object());
+    let Some(keywords) = keywords else {
+        return serde_wasm_bindgen::from_value(options_js)
+            .map_err(|e| JsValue::from_str(&format!("Options deserialization error: {}", e)));
+    };
+    // Functions don't deserialize, so read the rest from a copy without `keywords`.
+    let rest = js_sys::Object::assign(&js_sys::Object::new(), options_js.unchecked_ref());
+    js_sys::Reflect::delete_property(&rest, &JsValue::from_str("keywords"))?;
+    let mut options: ValidatorOptions = serde_wasm_bindgen::from_value(rest.into())
+        .map_err(|e| JsValue::from_str(&format!("Options deserialization error: {}", e)))?;
+    for name in js_sys::Object::keys(keywords.unchecked_ref()) {
+        let callback: js_sys::Function = js_sys::Reflect::get(&keywords, &name)?
+            .dyn_into()
+            .map_err(|_| {
+                JsValue::from_str("Options deserialization error: keywords must be functions")
+            })?;
+        options.keywords.push(custom::CustomKeyword {
+            name: name.as_string().unwrap_or_default(),
+            check: js_keyword_check(callback),
+        });
+    }
+    Ok(options)
+}
+
+/// A JS keyword callback, called as `callback(instance, keywordValue)`. It
+/// returns a boolean or `{ valid, message }`; throwing counts as invalid, with
+/// the thrown message.
+struct JsKeywordCallback(js_sys::Function);
+
+// SAFETY: a `js_sys::Function` is only obtained from the JS `keywords` option,
+// so it exists only on wasm32-unknown-unknown, which runs this module on a
+// single thread without shared memory. The function is therefore never sent
+// to or called from another thread, even though `KeywordCheck` requires
+// `Send + Sync` to satisfy `jsonschema`'s `Keyword` bounds.
+unsafe impl Send for JsKeywordCallback {}
+unsafe impl Sync for JsKeywordCallback {}
+
+impl JsKeywordCallback {
+    fn call(&self, instance: &Value, value: &Value) -> Result<JsValue, JsValue> {
+        self.0
+            .call2(&JsValue::NULL, &to_js(instance), &to_js(value))
+    }
+}
+
+fn js_keyword_check(callback: js_sys::Function) -> custom::KeywordCheck {
+    let callback = JsKeywordCallback(callback);
+    std::sync::Arc::new(move |instance: &Value, value: &Value| {
+        let result = callback
+            .call(instance, value)
+            .map_err(|e| Some(e.as_string().unwrap_or_else(|| format!("{:?}", e))))?;
+        let message = js_sys::Reflect::get(&result, &JsValue::from_str("message"))
+            .ok()
+            .and_then(|message| message.as_string());
+        let valid = match result.as_bool() {
+            Some(valid) => valid,
+            None => js_sys::Reflect::get(&result, &JsValue::from_str("valid"))
+                .is_ok_and(|valid| valid.is_truthy()),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(message)
+        }
+    })
+}
+
+/// Prefixes an instance path with `base_path`. Instance paths are either empty
+/// (the root) or start with `/`, so concatenation yields a valid pointer once a
+/// trailing `/` is dropped from `base_path`.
+fn prefix_path(base_path: Option<&str>, path: &str) -> String {
+    match base_path {
+        Some(base) => format!("{}{}", base.strip_suffix('/').unwrap_or(base), path),
+        None => path.to_string(),
+    }
+}
+
+/// Reads `base_path` without its trailing `/`, so that code stripping it from
+/// issue paths agrees with [`prefix_path`].
+fn deserialize_base_path<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let base_path: Option<String> = Option::deserialize(deserializer)?;
+    Ok(base_path.map(|base| match base.strip_suffix('/') {
+        Some(trimmed) => trimmed.to_string(),
+        None => base,
+    }))
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue, what: &str) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| JsValue::from_str(&format!("{} deserialization error: {}", what, e)))
+}
+
+/// Serializes to plain JS objects (rather than `Map`s) so results match the
+/// JSON shape documented in the typings.
+fn to_js<T: Serialize + ?Sized>(value: &T) -> JsValue {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap()
+}
+
+fn issues_to_js(issues: &[ValidationIssue]) -> JsValue {
+    to_js(issues)
+}
+
+fn schema_issue(message: String, schema_path: String) -> ValidationIssue {
+    ValidationIssue::new("/".to_string(), message, "invalid_schema").with_schema_path(schema_path)
+}
+
+fn compile_schema(
+    schema: &Value,
+    options: &ValidatorOptions,
+) -> Result<Validator, Vec<ValidationIssue>> {
+    compile_schema_with(
+        jsonschema::options(),
+        resources::MapRetriever::default(),
+        schema,
+        options,
+    )
+}
+
+/// Like [`compile_schema`], starting from a preconfigured `builder` and resolving
+/// external `$ref`s from `retriever`.
+fn compile_schema_with(
+    mut builder: jsonschema::ValidationOptions,
+    mut retriever: resources::MapRetriever,
+    schema: &Value,
+    options: &ValidatorOptions,
+) -> Result<Validator, Vec<ValidationIssue>> {
+    let draft = options
+        .draft
+        .or(options.legacy_definitions.then_some(SchemaDraft::Draft4));
+    if let Some(draft) = draft {
+        builder = builder.with_draft(draft.into());
+    }
+    if let Some(meta_schema) = &options.meta_schema {
+        builder = register_meta_schema(builder, &mut retriever, meta_schema, schema)?;
+    }
+    if !retriever.is_empty() {
+        builder = builder.with_retriever(retriever);
+    }
+    if options.lax_email {
+        builder = formats::lax_email(builder);
+    }
+    // Masked for `include_both_messages` too, whose `masked_message` must not
+    // carry the instance.
+    let mask_values = options.mask_values || options.include_both_messages;
+    builder = custom::register(&options.keywords, builder, mask_values);
+    // Applied last so that its always-valid overrides win on 2019-09 and later.
+    if options.enforce_date_formats {
+        let draft = match draft {
+            Some(draft) => draft.into(),
+            None => jsonschema::Draft::default()
+                .detect(schema)
+                .unwrap_or_default(),
+        };
+        builder = formats::enforce_date_formats(builder, draft);
+    }
+    builder.build(schema).map_err(|e| {
+        // A `$ref`, `$dynamicRef` or `$recursiveRef` that doesn't resolve, as
+        // opposed to an unknown `$schema`.
+        let code = match e.kind {
+            ValidationErrorKind::Referencing(referencing::Error::UnknownSpecification {
+                ..
+            }) => "invalid_schema",
+            ValidationErrorKind::Referencing(_) => "schema_reference_error",
+            _ => "invalid_schema",
+        };
+        vec![ValidationIssue::new(
+            "/".to_string(),
+            format!("Schema compilation error: {}", e),
+            code,
+        )
+        .with_schema_path(e.instance_path.to_string())]
+    })
+}
+
+/// Makes a custom meta-schema resolvable by its `$id` and checks `schema`
+/// against it, since `jsonschema` itself only enforces the standard drafts.
+fn register_meta_schema(
+    builder: jsonschema::ValidationOptions,
+    retriever: &mut resources::MapRetriever,
+    meta_schema: &Value,
+    schema: &Value,
+) -> Result<jsonschema::ValidationOptions, Vec<ValidationIssue>> {
+    let registration_error = |reason: String| {
+        vec![schema_issue(
+            format!("Meta-schema registration error: {}", reason),
+            String::new(),
+        )]
+    };
+    let uri = meta_schema
+        .get("$id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| registration_error("the meta-schema must declare an `$id`".to_string()))?;
+    let resource = jsonschema::Resource::from_contents(meta_schema.clone())
+        .map_err(|e| registration_error(e.to_string()))?;
+    let meta_validator =
+        jsonschema::validator_for(meta_schema).map_err(|e| registration_error(e.to_string()))?;
+    let violations: Vec<ValidationIssue> = meta_validator
+        .iter_errors(schema)
+        .map(|e| {
+            schema_issue(
+                format!("Schema does not conform to its meta-schema: {}", e),
+                e.instance_path.to_string(),
+            )
+        })
+        .collect();
+    if !violations.is_empty() {
+        return Err(violations);
+    }
+    retriever.insert(uri, meta_schema.clone());
+    Ok(builder.with_resource(uri, resource))
+}
+
+fn check_instance_size(
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    if options.max_depth.is_none() && options.max_nodes.is_none() {
+        return Ok(());
+    }
+    limits::check_value_size(instance, options.max_depth, options.max_nodes).map_err(|exceeded| {
+        let message = match exceeded {
+            limits::LimitExceeded::Depth(limit) => {
+                format!(
+                    "Instance is nested deeper than the limit of {} levels",
+                    limit
+                )
+            }
+            limits::LimitExceeded::Nodes(limit) => {
+                format!("Instance has more than the limit of {} nodes", limit)
+            }
+        };
+        vec![ValidationIssue::new(
+            prefix_path(options.base_path.as_deref(), ""),
+            message,
+            "instance_too_large",
+        )]
+    })
+}
+
+/// Whether the JSON Pointer `path` is `prefix` itself or points below it.
+fn is_within(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Builds the issue for `error`. With a `scratch` buffer reused across calls,
+/// messages are rendered into it and copied out, so each costs one exactly
+/// sized allocation instead of a series of regrowths. Without one they're
+/// formatted directly, which is cheaper for a one-off validation.
+fn issue_from_error(
+    error: &ValidationError,
+    options: &ValidatorOptions,
+    mut scratch: Option<&mut String>,
+) -> ValidationIssue {
+    let mut render = |message: &dyn std::fmt::Display| match scratch.as_deref_mut() {
+        Some(scratch) => {
+            use std::fmt::Write;
+            scratch.clear();
+            write!(scratch, "{}", message).expect("writing to a String cannot fail");
+            scratch.as_str().to_owned()
+        }
+        None => message.to_string(),
+    };
+    let masked_message = options
+        .include_both_messages
+        .then(|| render(&error.masked()));
+    let message = if options.mask_values && !options.include_both_messages {
+        render(&error.masked())
+    } else {
+        render(error)
+    };
+    let grapheme_count = match (&error.kind, error.instance.as_ref()) {
+        (
+            ValidationErrorKind::MinLength { .. } | ValidationErrorKind::MaxLength { .. },
+            Value::String(value),
+        ) => Some(value.graphemes(true).count()),
+        _ => None,
+    };
+    let suggestion = match (&error.kind, error.instance.as_ref()) {
+        (ValidationErrorKind::Enum { options }, Value::String(value)) => options
+            .as_array()
+            .and_then(|options| suggest::closest_option(value, options)),
+        (ValidationErrorKind::Constant { expected_value }, Value::String(value)) => {
+            suggest::closest_option(value, [expected_value])
+        }
+        _ => None,
+    };
+    let (shortfall, excess) = size_gap(error);
+    let pattern = match &error.kind {
+        ValidationErrorKind::Pattern { pattern } => Some(pattern.clone()),
+        ValidationErrorKind::PropertyNames { error } => match &error.kind {
+            ValidationErrorKind::Pattern { pattern } => Some(pattern.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let negated_schema = match &error.kind {
+        ValidationErrorKind::Not { schema } => Some(schema.clone()),
+        _ => None,
+    };
+    let sibling_keys = match (&error.kind, error.instance.as_ref()) {
+        (
+            ValidationErrorKind::Required { .. } | ValidationErrorKind::AdditionalProperties { .. },
+            Value::Object(object),
+        ) if options.include_sibling_keys => Some(object.keys().cloned().collect()),
+        _ => None,
+    };
+    let template = templates::message_template(error, options.mask_values);
+    let path = prefix_path(options.base_path.as_deref(), error.instance_path.as_str());
+    let schema_path = error.schema_path.to_string();
+    let code = map_error_kind_to_code(&error.kind);
+    let id = match &error.kind {
+        ValidationErrorKind::Required { property } => {
+            let property = property.as_str().unwrap_or_default();
+            issue_id(&walk::join(&path, property), &schema_path, code)
+        }
+        _ => issue_id(&path, &schema_path, code),
+    };
+    ValidationIssue {
+        id,
+        depth: path_depth(&path),
+        path,
+        message,
+        code: code.to_string(),
+        code_id: code_id(code),
+        schema_path,
+        message_template: template.template,
+        message_params: template.params,
+        grapheme_count,
+        suggestion: suggestion.map(str::to_string),
+        shortfall,
+        excess,
+        pattern,
+        masked_message,
+        negated_schema,
+        instance_type: Some(json_type_name(&error.instance).to_string()),
+        sibling_keys,
+        ..ValidationIssue::default()
+    }
+}
+
+/// The JSON type of `value`, as a schema's `type` spells it. Integers are
+/// `number`, since that's all the value itself tells.
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// The `(shortfall, excess)` of a size keyword violation, measured the way the
+/// keyword counts: items, properties, or code points.
+fn size_gap(error: &ValidationError) -> (Option<u64>, Option<u64>) {
+    let size = match error.instance.as_ref() {
+        Value::Array(items) => items.len(),
+        Value::Object(properties) => properties.len(),
+        Value::String(value) => value.chars().count(),
+        _ => return (None, None),
+    } as u64;
+    match error.kind {
+        ValidationErrorKind::MinItems { limit }
+        | ValidationErrorKind::MinProperties { limit }
+        | ValidationErrorKind::MinLength { limit } => (Some(limit.saturating_sub(size)), None),
+        ValidationErrorKind::MaxItems { limit }
+        | ValidationErrorKind::MaxProperties { limit }
+        | ValidationErrorKind::MaxLength { limit } => (None, Some(size.saturating_sub(limit))),
+        _ => (None, None),
+    }
+}
+
+/// Whether a `multipleOf` error is a binary rounding artifact. Only reported
+/// errors are re-checked: a `multipleOf` nested in `anyOf`/`oneOf`/`not` still
+/// decides its branch with floating point arithmetic.
+fn is_decimal_multiple_of(error: &ValidationError) -> bool {
+    match (&error.kind, error.instance.as_ref()) {
+        (ValidationErrorKind::MultipleOf { multiple_of }, Value::Number(value)) => {
+            decimal::is_decimal_multiple(&value.to_string(), &multiple_of.to_string())
+                .unwrap_or(false)
+        }
+        _ => false,
+    }
 }
 
 fn perform_validation(
     validator: &Validator,
     instance: &Value,
-    mask_values: bool,
+    options: &ValidatorOptions,
 ) -> Result<(), Vec<ValidationIssue>> {
-    let errors: Vec<ValidationIssue> = validator
-        .iter_errors(instance)
-        .map(|error| {
-            let message = if mask_values {
-                error.masked().to_string()
-            } else {
-                error.to_string()
-            };
-            ValidationIssue {
-                path: error.instance_path.to_string(),
-                message,
-                code: map_error_kind_to_code(&error.kind),
+    perform_validation_with(validator, instance, options, None)
+}
+
+/// [`perform_validation`] rendering messages through a caller-owned `scratch`
+/// buffer when given one, see [`issue_from_error`].
+fn perform_validation_with(
+    validator: &Validator,
+    instance: &Value,
+    options: &ValidatorOptions,
+    mut scratch: Option<&mut String>,
+) -> Result<(), Vec<ValidationIssue>> {
+    let limit = options.operation_limit.map_or(usize::MAX, |limit| {
+        usize::try_from(limit).unwrap_or(usize::MAX)
+    });
+    let mut raw_errors = validator.iter_errors(instance);
+    let mut errors: Vec<ValidationIssue> = raw_errors
+        .by_ref()
+        .take(limit)
+        .filter(|error| !(options.decimal_multiple_of && is_decimal_multiple_of(error)))
+        .flat_map(|error| {
+            let issue = issue_from_error(&error, options, scratch.as_deref_mut());
+            match &error.kind {
+                // Reported once at the object; split so each path names the offending key.
+                ValidationErrorKind::UnevaluatedProperties { unexpected } => unexpected
+                    .iter()
+                    .map(|property| {
+                        let message = format!(
+                            "Unevaluated properties are not allowed ('{property}' was unexpected)"
+                        );
+                        ValidationIssue {
+                            // A key isn't a value, so the masked message names it too.
+                            masked_message: issue.masked_message.as_ref().map(|_| message.clone()),
+                            message,
+                            message_template:
+                                "Unevaluated properties are not allowed ({property} was unexpected)"
+                                    .to_string(),
+                            message_params: serde_json::Map::from_iter([(
+                                "property".to_string(),
+                                Value::from(property.as_str()),
+                            )]),
+                            generated_message: true,
+                            ..issue.clone()
+                        }
+                        .with_path(walk::join(&issue.path, property))
+                    })
+                    .collect(),
+                _ => vec![issue],
             }
         })
         .collect();
+    sort_by_document_order(instance, options.base_path.as_deref(), &mut errors);
+    if raw_errors.next().is_some() {
+        errors.push(ValidationIssue::new(
+            prefix_path(options.base_path.as_deref(), ""),
+            format!("Validation stopped after the limit of {} errors", limit),
+            "operation_limit_exceeded",
+        ));
+    }
 
     if errors.is_empty() {
         Ok(())
@@ -38,185 +826,6059 @@ fn perform_validation(
     }
 }
 
-#[wasm_bindgen]
-pub struct WasmValidator {
-    validator: Validator,
-    mask_values: bool,
+/// Reorders `issues` so that those under different keys of an object follow
+/// the order the document lists the keys, as they would by array index.
+/// `jsonschema` follows the schema's order instead, e.g. of `properties`. An
+/// issue at a value keeps its place before or after the issues inside it.
+fn sort_by_document_order(
+    instance: &Value,
+    base_path: Option<&str>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if issues.len() < 2 {
+        return;
+    }
+    // Key positions per object, by address, so each object is indexed once.
+    let mut key_positions: HashMap<*const serde_json::Map<String, Value>, HashMap<&str, usize>> =
+        HashMap::new();
+    let positioned = std::mem::take(issues)
+        .into_iter()
+        .map(|issue| {
+            let local = base_path
+                .and_then(|base| issue.path.strip_prefix(base))
+                .unwrap_or(&issue.path);
+            let mut current = instance;
+            let mut position = Vec::new();
+            for segment in local.split('/').skip(1) {
+                let segment = segment.replace("~1", "/").replace("~0", "~");
+                let next = match current {
+                    Value::Object(object) => key_positions
+                        .entry(object as *const _)
+                        .or_insert_with(|| {
+                            object
+                                .keys()
+                                .enumerate()
+                                .map(|(index, key)| (key.as_str(), index))
+                                .collect()
+                        })
+                        .get(segment.as_str())
+                        .map(|index| (*index, &object[&segment])),
+                    Value::Array(items) => segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| Some((index, items.get(index)?))),
+                    _ => None,
+                };
+                let Some((index, value)) = next else {
+                    break;
+                };
+                position.push(index);
+                current = value;
+            }
+            (position, issue)
+        })
+        .collect();
+    *issues = arrange(positioned, 0)
+        .into_iter()
+        .map(|(_, issue)| issue)
+        .collect();
 }
 
-#[wasm_bindgen]
-impl WasmValidator {
-    #[wasm_bindgen(constructor)]
-    pub fn new(schema_js: JsValue, mask_values_js: Option<bool>) -> Result<WasmValidator, JsValue> {
-        let schema: Value = serde_wasm_bindgen::from_value(schema_js)
-            .map_err(|e| JsValue::from_str(&format!("Schema deserialization error: {}", e)))?;
-
-        let validator = Validator::new(&schema).map_err(|e| {
-            let issue = ValidationIssue {
-                path: "/".to_string(),
-                message: format!("Schema compilation error: {}", e),
-                code: "invalid_schema".to_string(),
-            };
-            serde_wasm_bindgen::to_value(&vec![issue]).unwrap()
-        })?;
+/// Orders the issues below one value, each with its document position: those at
+/// the value itself stay before or after the rest, which are grouped by child
+/// in document order.
+fn arrange(
+    issues: Vec<(Vec<usize>, ValidationIssue)>,
+    depth: usize,
+) -> Vec<(Vec<usize>, ValidationIssue)> {
+    if issues.len() < 2 {
+        return issues;
+    }
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let mut children: BTreeMap<usize, Vec<_>> = BTreeMap::new();
+    for (position, issue) in issues {
+        match position.get(depth) {
+            Some(child) => children.entry(*child).or_default().push((position, issue)),
+            None if children.is_empty() => before.push((position, issue)),
+            None => after.push((position, issue)),
+        }
+    }
+    before.extend(
+        children
+            .into_values()
+            .flat_map(|child| arrange(child, depth + 1)),
+    );
+    before.extend(after);
+    before
+}
 
-        Ok(WasmValidator {
-            validator,
-            mask_values: mask_values_js.unwrap_or(false),
+/// Fills in `absolute_keyword_location` for issues whose `schema_path` crosses a
+/// `$ref`, re-running validation in the basic output format only if there are any.
+///
+/// The output format names the right document but keeps the whole keyword path
+/// as its fragment, so the fragment is rebuilt from the part of `schema_path`
+/// after the `$ref`, prefixed by the reference's own fragment. Past a second
+/// `$ref` the referring document isn't at hand, so only the URI is reported.
+fn add_absolute_locations(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+    issues: &mut [ValidationIssue],
+) {
+    if !issues
+        .iter()
+        .any(|issue| issue.schema_path.contains("/$ref"))
+    {
+        return;
+    }
+    let jsonschema::BasicOutput::Invalid(units) = validator.apply(instance).basic() else {
+        return;
+    };
+    let documents: HashMap<(String, String), String> = units
+        .iter()
+        .filter_map(|unit| {
+            let uri = unit.absolute_keyword_location()?;
+            let document = uri.as_str().split('#').next()?.to_string();
+            Some((
+                (
+                    unit.instance_location().to_string(),
+                    unit.keyword_location().to_string(),
+                ),
+                document,
+            ))
         })
+        .collect();
+    for issue in issues.iter_mut() {
+        let Some((before, after)) = issue.schema_path.rsplit_once("/$ref") else {
+            continue;
+        };
+        let instance_path = options
+            .base_path
+            .as_deref()
+            .and_then(|base| issue.path.strip_prefix(base))
+            .unwrap_or(&issue.path);
+        let Some(document) = documents.get(&(instance_path.to_string(), issue.schema_path.clone()))
+        else {
+            continue;
+        };
+        issue.absolute_keyword_location = Some(if before.contains("/$ref") {
+            document.clone()
+        } else {
+            let fragment = schema
+                .pointer(before)
+                .and_then(|subschema| subschema.get("$ref"))
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.split_once('#'))
+                .map_or("", |(_, fragment)| fragment);
+            format!("{}#{}{}", document, fragment, after)
+        });
     }
+}
 
-    #[wasm_bindgen]
-    pub fn validate(&self, instance_js: JsValue) -> Result<(), JsValue> {
-        let instance: Value = serde_wasm_bindgen::from_value(instance_js)
-            .map_err(|e| JsValue::from_str(&format!("Instance deserialization error: {}", e)))?;
+/// Fills in `matched_branches` by checking the value against each branch of the
+/// `oneOf` on its own.
+fn add_matched_branches(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+    issues: &mut [ValidationIssue],
+) {
+    for issue in issues
+        .iter_mut()
+        .filter(|issue| issue.code == "one_of_multiple_matches")
+    {
+        let Some(Value::Array(branches)) = schema.pointer(&issue.schema_path) else {
+            continue;
+        };
+        let instance_path = options
+            .base_path
+            .as_deref()
+            .and_then(|base| issue.path.strip_prefix(base))
+            .unwrap_or(&issue.path);
+        let Some(value) = instance.pointer(instance_path) else {
+            continue;
+        };
+        issue.matched_branches = (0..branches.len())
+            .map(|index| {
+                let branch =
+                    SubschemaValidator::new(schema, &format!("{}/{index}", issue.schema_path))
+                        .ok()?;
+                Some(branch.validator.is_valid(value).then_some(index))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|matched| matched.into_iter().flatten().collect());
+    }
+}
 
-        match perform_validation(&self.validator, &instance, self.mask_values) {
-            Ok(_) => Ok(()),
-            Err(errors) => Err(serde_wasm_bindgen::to_value(&errors).unwrap()),
+/// Recodes the `required` failures that `dependentRequired`, `dependentSchemas`
+/// or `dependencies` cause as `dependent_required` or `dependent_schema`, naming
+/// the property whose presence triggered them. Other failures inside a dependent
+/// schema keep their own codes.
+fn add_dependency_codes(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+    issues: &mut [ValidationIssue],
+) {
+    for issue in issues.iter_mut() {
+        if issue.code != "missing_property" {
+            continue;
         }
+        let Some(property) = issue.message_params.get("property").and_then(Value::as_str) else {
+            continue;
+        };
+        let instance_path = options
+            .base_path
+            .as_deref()
+            .and_then(|base| issue.path.strip_prefix(base))
+            .unwrap_or(&issue.path);
+        let object = instance.pointer(instance_path).and_then(Value::as_object);
+        let Some((code, trigger)) =
+            dependency_trigger(schema, &issue.schema_path, object, property)
+        else {
+            continue;
+        };
+        let property = property.to_string();
+        let mut message_params = serde_json::Map::new();
+        message_params.insert("property".to_string(), Value::from(property.as_str()));
+        message_params.insert("trigger".to_string(), Value::from(trigger.as_str()));
+        *issue = ValidationIssue {
+            id: issue_id(
+                &walk::join(&issue.path, &property),
+                &issue.schema_path,
+                code,
+            ),
+            message: format!(
+                "\"{}\" is required when \"{}\" is present",
+                property, trigger
+            ),
+            message_template: "{property} is required when {trigger} is present".to_string(),
+            message_params,
+            code: code.to_string(),
+            code_id: code_id(code),
+            generated_message: true,
+            ..issue.clone()
+        };
     }
 }
 
-#[wasm_bindgen]
-pub fn validate(
-    schema_js: JsValue,
-    instance_js: JsValue,
-    mask_values_js: Option<bool>,
-) -> Result<(), JsValue> {
-    let validator = WasmValidator::new(schema_js, mask_values_js)?;
-    validator.validate(instance_js)
+/// Recodes the `disallowed_value` failures of an `items: false` that follows
+/// `prefixItems` as `additional_items`, like `additionalItems: false` in the
+/// drafts before 2020-12.
+fn add_tuple_codes(schema: &Value, issues: &mut [ValidationIssue]) {
+    for issue in issues
+        .iter_mut()
+        .filter(|issue| issue.code == "disallowed_value")
+    {
+        let Some(parent) = issue.schema_path.strip_suffix("/items") else {
+            continue;
+        };
+        if walk::at_schema_path(schema, parent).is_some_and(|parent| {
+            parent.get("prefixItems").is_some() && parent.get("items") == Some(&Value::Bool(false))
+        }) {
+            let code = "additional_items";
+            issue.id = issue_id(&issue.path, &issue.schema_path, code);
+            issue.code = code.to_string();
+            issue.code_id = code_id(code);
+        }
+    }
 }
 
-fn map_error_kind_to_code(kind: &ValidationErrorKind) -> String {
-    match kind {
-        ValidationErrorKind::AdditionalItems { .. } => "additional_items",
-        ValidationErrorKind::AdditionalProperties { .. } => "additional_properties",
-        ValidationErrorKind::AnyOf => "any_of_mismatch",
-        ValidationErrorKind::BacktrackLimitExceeded { .. } => "regex_backtrack_limit",
-        ValidationErrorKind::Constant { .. } => "const_mismatch",
-        ValidationErrorKind::Contains { .. } => "no_match_in_contains",
-        ValidationErrorKind::ContentEncoding { .. } => "invalid_content_encoding",
-        ValidationErrorKind::ContentMediaType { .. } => "invalid_media_type",
-        ValidationErrorKind::Custom { .. } => "custom_error",
-        ValidationErrorKind::Enum { .. } => "enum_mismatch",
-        ValidationErrorKind::ExclusiveMaximum { .. } => "exclusive_max",
-        ValidationErrorKind::ExclusiveMinimum { .. } => "exclusive_min",
-        ValidationErrorKind::FalseSchema => "disallowed_value",
-        ValidationErrorKind::Format { .. } => "format_mismatch",
-        ValidationErrorKind::FromUtf8 { .. } => "invalid_utf8",
-        ValidationErrorKind::Maximum { .. } => "too_large",
-        ValidationErrorKind::MaxItems { .. } => "too_many_items",
-        ValidationErrorKind::MaxLength { .. } => "too_long",
-        ValidationErrorKind::MaxProperties { .. } => "too_many_properties",
-        ValidationErrorKind::Minimum { .. } => "too_small",
-        ValidationErrorKind::MinItems { .. } => "too_few_items",
-        ValidationErrorKind::MinLength { .. } => "too_short",
-        ValidationErrorKind::MinProperties { .. } => "too_few_properties",
-        ValidationErrorKind::MultipleOf { .. } => "not_a_multiple",
-        ValidationErrorKind::Not { .. } => "negated_schema_match",
-        ValidationErrorKind::OneOfMultipleValid => "one_of_multiple_matches",
-        ValidationErrorKind::OneOfNotValid => "one_of_no_match",
-        ValidationErrorKind::Pattern { .. } => "pattern_mismatch",
-        ValidationErrorKind::PropertyNames { .. } => "invalid_property_name",
-        ValidationErrorKind::Required { .. } => "missing_property",
-        ValidationErrorKind::Type { .. } => "invalid_type",
-        ValidationErrorKind::UnevaluatedItems { .. } => "unevaluated_items",
-        ValidationErrorKind::UnevaluatedProperties { .. } => "unevaluated_properties",
-        ValidationErrorKind::UniqueItems => "duplicate_items",
-        ValidationErrorKind::Referencing(..) => "schema_reference_error",
+/// The code and triggering property for a `required` failure at `schema_path`
+/// caused by a dependency keyword, if it is one.
+fn dependency_trigger(
+    schema: &Value,
+    schema_path: &str,
+    object: Option<&serde_json::Map<String, Value>>,
+    property: &str,
+) -> Option<(&'static str, String)> {
+    if schema_path.ends_with("/dependentRequired") || schema_path.ends_with("/dependencies") {
+        // The schema path stops at the keyword, so look for the present property
+        // whose list names the missing one.
+        let dependencies = schema.pointer(schema_path)?.as_object()?;
+        let object = object?;
+        let trigger = dependencies.iter().find_map(|(trigger, required)| {
+            let listed = required.as_array()?.iter().any(|name| name == property);
+            (listed && object.contains_key(trigger)).then(|| trigger.clone())
+        })?;
+        return Some(("dependent_required", trigger));
     }
-        .to_string()
+    ["/dependentSchemas/", "/dependencies/"]
+        .into_iter()
+        .find_map(|keyword| {
+            let (_, rest) = schema_path.rsplit_once(keyword)?;
+            let (trigger, rest) = rest.split_once('/')?;
+            (rest == "required").then(|| trigger.replace("~1", "/").replace("~0", "~"))
+        })
+        .map(|trigger| ("dependent_schema", trigger))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
+/// Replaces the message of each issue whose failing subschema has an
+/// `errorMessage` entry for the failed keyword, e.g.
+/// `{ "minimum": 18, "errorMessage": { "minimum": "Too young" } }`.
+fn apply_schema_messages(schema: &Value, issues: &mut [ValidationIssue]) {
+    for issue in issues.iter_mut() {
+        let Some((parent, keyword)) = issue.schema_path.rsplit_once('/') else {
+            continue;
+        };
+        let keyword = keyword.replace("~1", "/").replace("~0", "~");
+        let Some(message) = walk::at_schema_path(schema, parent)
+            .and_then(|subschema| subschema.get("errorMessage"))
+            .and_then(|messages| messages.get(&keyword))
+            .and_then(Value::as_str)
+        else {
+            continue;
+        };
+        issue.message = message.to_string();
+        issue.message_template = message.to_string();
+        issue.generated_message = false;
+    }
+}
 
-    fn get_complex_schema() -> Value {
-        json!({
-          "type": "object",
-          "properties": {
-            "id": { "type": "string", "pattern": "^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$" },
-            "username": { "type": "string", "minLength": 3 },
-            "status": { "type": "string", "enum": ["active", "inactive", "pending"] },
-            "profile": {
-              "type": "object",
-              "properties": { "fullName": { "type": "string" }, "age": { "type": "number", "minimum": 18 } },
-              "required": ["fullName"],
-            },
-            "tags": { "type": "array", "items": { "type": "string" }, "minItems": 1, "uniqueItems": true },
-          },
-          "required": ["id", "username", "status", "tags"],
-        })
+/// Applies the `codes_from_comment` option: an issue whose failing subschema has
+/// a `$comment` of `code:SOME_CODE` gets `SOME_CODE` as its code.
+fn apply_comment_codes(schema: &Value, issues: &mut [ValidationIssue]) {
+    for issue in issues.iter_mut() {
+        let Some((parent, _)) = issue.schema_path.rsplit_once('/') else {
+            continue;
+        };
+        let Some(code) = walk::at_schema_path(schema, parent)
+            .and_then(|subschema| subschema.get("$comment"))
+            .and_then(Value::as_str)
+            .and_then(|comment| comment.strip_prefix("code:"))
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+        else {
+            continue;
+        };
+        let path = match issue.message_params.get("property").and_then(Value::as_str) {
+            Some(property)
+                if matches!(
+                    issue.code.as_str(),
+                    "missing_property" | "dependent_required" | "dependent_schema"
+                ) =>
+            {
+                walk::join(&issue.path, property)
+            }
+            _ => issue.path.clone(),
+        };
+        issue.id = issue_id(&path, &issue.schema_path, code);
+        issue.code = code.to_string();
+        issue.code_id = code_id(code);
     }
+}
 
-    #[test]
-    fn test_pass_on_valid_complex_instance() {
-        let schema = get_complex_schema();
-        let validator = Validator::new(&schema).unwrap();
-        let valid_instance = json!({
-          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
-          "username": "testuser",
-          "status": "active",
-          "profile": { "fullName": "Test User", "age": 30 },
-          "tags": ["rust", "validate", "nodejs"],
-        });
-        let result = perform_validation(&validator, &valid_instance, false);
-        assert!(result.is_ok());
+/// Parses a JSON Pointer into a jsonschema [`Location`](jsonschema::paths::Location).
+fn location(pointer: &str) -> jsonschema::paths::Location {
+    let segments: Vec<String> = pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    segments
+        .iter()
+        .map(|segment| jsonschema::paths::LocationSegment::Property(segment))
+        .collect()
+}
+
+/// The full validation pipeline: instance limits, the compiled schema itself,
+/// then the checks that work on the raw `schema` document.
+fn validate_internal(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    validate_internal_with(validator, schema, instance, options, None)
+}
+
+/// [`validate_internal`] rendering messages through a caller-owned `scratch`
+/// buffer when given one, so a validator used for many instances reuses one
+/// allocation.
+fn validate_internal_with(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+    mut scratch: Option<&mut String>,
+) -> Result<(), Vec<ValidationIssue>> {
+    check_instance_size(instance, options).map_err(|mut issues| {
+        normalize_messages(&mut issues, options);
+        truncate_messages(&mut issues, options);
+        issues
+    })?;
+    let parsed;
+    let instance = if options.numeric_strings || options.treat_null_as_missing {
+        let mut copy = instance.clone();
+        if options.treat_null_as_missing {
+            normalize::remove_null_properties(&mut copy);
+        }
+        if options.numeric_strings {
+            normalize::parse_numeric_strings(schema, &mut copy);
+        }
+        parsed = copy;
+        &parsed
+    } else {
+        instance
+    };
+    let mut issues = perform_validation_with(validator, instance, options, scratch.as_deref_mut())
+        .err()
+        .unwrap_or_default();
+    add_absolute_locations(validator, schema, instance, options, &mut issues);
+    add_matched_branches(schema, instance, options, &mut issues);
+    add_dependency_codes(schema, instance, options, &mut issues);
+    add_tuple_codes(schema, &mut issues);
+    if options.closed {
+        issues.extend(
+            closed::undeclared_properties(schema, instance)
+                .into_iter()
+                .map(|undeclared| {
+                    let error = ValidationError {
+                        instance: std::borrow::Cow::Borrowed(
+                            instance
+                                .pointer(&undeclared.instance_path)
+                                .unwrap_or(instance),
+                        ),
+                        kind: ValidationErrorKind::AdditionalProperties {
+                            unexpected: undeclared.unexpected,
+                        },
+                        instance_path: location(&undeclared.instance_path),
+                        schema_path: location(&undeclared.schema_path),
+                    };
+                    issue_from_error(&error, options, scratch.as_deref_mut())
+                }),
+        );
     }
+    if let Some(context) = options.context {
+        issues.extend(
+            access::access_violations(schema, instance, context)
+                .into_iter()
+                .map(|violation| {
+                    ValidationIssue::new(
+                        prefix_path(options.base_path.as_deref(), &violation.instance_path),
+                        violation.message,
+                        violation.code,
+                    )
+                    .with_schema_path(violation.schema_path)
+                }),
+        );
+    }
+    if options.use_schema_messages {
+        apply_schema_messages(schema, &mut issues);
+    }
+    if options.codes_from_comment {
+        apply_comment_codes(schema, &mut issues);
+    }
+    let issues = finish_issues(issues, instance, options);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
 
-    #[test]
-    fn test_fail_on_nested_property_error() {
-        let schema = get_complex_schema();
-        let validator = Validator::new(&schema).unwrap();
-        let invalid_instance = json!({
-          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
-          "username": "testuser",
-          "status": "active",
-          "profile": { "fullName": "Test User", "age": 17 },
-          "tags": ["testing"],
-        });
-        let issues = perform_validation(&validator, &invalid_instance, false).unwrap_err();
-        assert_eq!(issues.len(), 1);
-        assert_eq!(issues[0].code, "too_small");
-        assert_eq!(issues[0].path, "/profile/age");
+/// The steps every issue goes through last, including ones raised outside
+/// validation: the code filters, `collapse_arrays`, `doc_url`, the dot
+/// `display_path`, and message normalization and truncation.
+fn finish_issues(
+    mut issues: Vec<ValidationIssue>,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Vec<ValidationIssue> {
+    // The limit marker survives the filters, so a truncated result is never
+    // mistaken for a complete one.
+    issues.retain(|issue| {
+        issue.code == "operation_limit_exceeded"
+            || (!options.structural_only || STRUCTURAL_CODES.contains(&issue.code.as_str()))
+                && !options.ignore_codes.contains(&issue.code)
+                && options
+                    .only_codes
+                    .as_ref()
+                    .is_none_or(|codes| codes.contains(&issue.code))
+    });
+    if options.collapse_arrays {
+        issues = collapse::collapse_arrays(issues, instance, options.base_path.as_deref());
+    }
+    if let Some(base) = &options.doc_base_url {
+        for issue in &mut issues {
+            let keyword = issue.schema_path.rsplit('/').next().unwrap_or_default();
+            if !keyword.is_empty() {
+                let keyword = keyword.replace("~1", "/").replace("~0", "~");
+                issue.doc_url = Some(format!("{}#{}", base, keyword));
+            }
+        }
+    }
+    if options.path_format == PathFormat::Dot {
+        for issue in &mut issues {
+            issue.display_path = Some(paths::to_dot_path(
+                &issue.path,
+                options.base_path.as_deref(),
+                instance,
+            ));
+        }
     }
+    normalize_messages(&mut issues, options);
+    truncate_messages(&mut issues, options);
+    issues
+}
 
-    #[test]
-    fn test_report_multiple_simultaneous_errors() {
-        let schema = get_complex_schema();
-        let validator = Validator::new(&schema).unwrap();
+/// Applies the `normalize_messages` option. A leading acronym such as `CSV` is
+/// left alone, as are messages from `jsonschema` and from the schema itself.
+fn normalize_messages(issues: &mut [ValidationIssue], options: &ValidatorOptions) {
+    if !options.normalize_messages {
+        return;
+    }
+    for issue in issues.iter_mut().filter(|issue| issue.generated_message) {
+        let message = issue.message.trim_end().trim_end_matches('.');
+        let mut chars = message.chars();
+        let normalized = match (chars.next(), chars.next()) {
+            (Some(first), second) if !second.is_some_and(char::is_uppercase) => first
+                .to_lowercase()
+                .chain(message[first.len_utf8()..].chars())
+                .collect(),
+            _ => message.to_string(),
+        };
+        issue.message = normalized;
+    }
+}
+
+/// Applies the `max_message_len` option to `message` and `masked_message`,
+/// counting chars so that a multibyte character is never split.
+fn truncate_messages(issues: &mut [ValidationIssue], options: &ValidatorOptions) {
+    let Some(max) = options.max_message_len else {
+        return;
+    };
+    let truncate = |message: &mut String| {
+        if max == 0 {
+            message.clear();
+        } else if let Some((cut, _)) = message.char_indices().nth(max.saturating_sub(1)) {
+            if message[cut..].chars().nth(1).is_some() {
+                message.truncate(cut);
+                message.push('…');
+            }
+        }
+    };
+    for issue in issues {
+        truncate(&mut issue.message);
+        if let Some(masked_message) = &mut issue.masked_message {
+            truncate(masked_message);
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct WasmValidator {
+    /// `None` once the validator has been disposed.
+    validator: Option<Arc<Validator>>,
+    schema: Value,
+    options: ValidatorOptions,
+    /// Results by serialized instance, with the `memoize` option.
+    memo: Option<RefCell<cache::SchemaCache<ValidationResult>>>,
+    /// Message buffer kept between calls, see [`validate_internal_with`].
+    scratch: RefCell<String>,
+    /// External documents resolved while compiling, with the `record_refs` option.
+    resolved_refs: Option<Vec<String>>,
+}
+
+/// Whether compiling a schema resolved any external `$ref`, and to which URIs.
+#[derive(Serialize, Debug, PartialEq)]
+struct RefReport {
+    used_refs: bool,
+    refs: Vec<String>,
+}
+
+type ValidationResult = Result<(), Vec<ValidationIssue>>;
+
+const DISPOSED_MESSAGE: &str = "Validator has been disposed";
+
+impl WasmValidator {
+    fn from_parts(validator: Arc<Validator>, schema: Value, options: ValidatorOptions) -> Self {
+        WasmValidator {
+            validator: Some(validator),
+            memo: options
+                .memoize
+                .then(|| RefCell::new(cache::SchemaCache::new(cache::CAPACITY))),
+            scratch: RefCell::new(String::new()),
+            resolved_refs: options.record_refs.then(Vec::new),
+            schema,
+            options,
+        }
+    }
+
+    fn compiled(&self) -> Result<&Validator, &'static str> {
+        self.validator.as_deref().ok_or(DISPOSED_MESSAGE)
+    }
+
+    /// Validates `instance`, answering from the memo when the same instance was
+    /// validated before. The schema is fixed per validator, so the instance alone
+    /// is the key.
+    fn validate_value(&self, validator: &Validator, instance: &Value) -> ValidationResult {
+        let validate = || {
+            validate_internal_with(
+                validator,
+                &self.schema,
+                instance,
+                &self.options,
+                Some(&mut self.scratch.borrow_mut()),
+            )
+        };
+        let Some(memo) = &self.memo else {
+            return validate();
+        };
+        let key = instance.to_string();
+        if let Some(result) = memo.borrow_mut().get(&key) {
+            return (*result).clone();
+        }
+        let result = validate();
+        memo.borrow_mut().insert(key, Arc::new(result.clone()));
+        result
+    }
+
+    fn compile(
+        schema: Value,
+        options: ValidatorOptions,
+    ) -> Result<WasmValidator, Vec<ValidationIssue>> {
+        let validator = compile_schema(&schema, &options)?;
+        Ok(WasmValidator::from_parts(
+            Arc::new(validator),
+            schema,
+            options,
+        ))
+    }
+
+    /// Fetches the schema graph rooted at `uri` with `fetch` and compiles it.
+    /// Fetch failures are reported as `invalid_schema`.
+    fn compile_from_uri(
+        uri: &str,
+        fetch: &mut dyn FnMut(&str) -> Result<Value, String>,
+        options: ValidatorOptions,
+    ) -> Result<WasmValidator, Vec<ValidationIssue>> {
+        let (schema, retriever) = resources::prefetch(uri, fetch).map_err(|e| {
+            vec![schema_issue(
+                format!("Schema fetch error: {}", e),
+                String::new(),
+            )]
+        })?;
+        let builder = jsonschema::options().with_base_uri(uri);
+        let retrieved = retriever.retrieved();
+        let validator = compile_schema_with(builder, retriever, &schema, &options)?;
+        let mut compiled = WasmValidator::from_parts(Arc::new(validator), schema, options);
+        if let Some(resolved_refs) = &mut compiled.resolved_refs {
+            resolved_refs.extend(
+                retrieved
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .drain(..),
+            );
+        }
+        Ok(compiled)
+    }
+
+    fn resolved_ref_report(&self) -> Option<RefReport> {
+        self.resolved_refs.as_ref().map(|refs| RefReport {
+            used_refs: !refs.is_empty(),
+            refs: refs.clone(),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl WasmValidator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema_js: JsValue, options_js: JsValue) -> Result<WasmValidator, JsValue> {
+        let options = parse_options(options_js)?;
+        let schema: Value = from_js(schema_js, "Schema")?;
+        Self::compile(schema, options).map_err(|issues| issues_to_js(&issues))
+    }
+
+    /// Non-throwing constructor: returns `{ ok: true, validator }` or
+    /// `{ ok: false, issues }`. Unusable options or a schema that isn't JSON are
+    /// still thrown, like any other argument error.
+    #[wasm_bindgen]
+    pub fn try_new(schema_js: JsValue, options_js: JsValue) -> Result<JsValue, JsValue> {
+        let options = parse_options(options_js)?;
+        let schema: Value = from_js(schema_js, "Schema")?;
+        let result = js_sys::Object::new();
+        let set = |key: &str, value: JsValue| {
+            js_sys::Reflect::set(&result, &JsValue::from_str(key), &value).map(|_| ())
+        };
+        match Self::compile(schema, options) {
+            Ok(validator) => {
+                set("ok", JsValue::TRUE)?;
+                set("validator", validator.into())?;
+            }
+            Err(issues) => {
+                set("ok", JsValue::FALSE)?;
+                set("issues", issues_to_js(&issues))?;
+            }
+        }
+        Ok(result.into())
+    }
+
+    /// Compiles the schema at `uri`, loading it and every document it references
+    /// through `fetch(uri)`, which must synchronously return the schema or throw.
+    #[wasm_bindgen]
+    pub fn from_uri(
+        uri: &str,
+        fetch: js_sys::Function,
+        options_js: JsValue,
+    ) -> Result<WasmValidator, JsValue> {
+        let options = parse_options(options_js)?;
+        let mut fetch = |uri: &str| {
+            let document = fetch
+                .call1(&JsValue::NULL, &JsValue::from_str(uri))
+                .map_err(|e| e.as_string().unwrap_or_else(|| format!("{:?}", e)))?;
+            // A Promise would deserialize as an empty object, i.e. the `{}` schema.
+            if js_sys::Reflect::get(&document, &JsValue::from_str("then"))
+                .is_ok_and(|then| then.is_function())
+            {
+                return Err(
+                    "fetch returned a Promise; it must return the schema synchronously".to_string(),
+                );
+            }
+            serde_wasm_bindgen::from_value(document).map_err(|e| e.to_string())
+        };
+        Self::compile_from_uri(uri, &mut fetch, options).map_err(|issues| issues_to_js(&issues))
+    }
+
+    /// Returns the normalized instance when the `normalize` option is set, and
+    /// `undefined` otherwise.
+    #[wasm_bindgen]
+    pub fn validate(&self, instance_js: JsValue) -> Result<JsValue, JsValue> {
+        let validator = self.compiled().map_err(JsValue::from_str)?;
+        let mut instance: Value = from_js(instance_js, "Instance")?;
+        if self.options.normalize {
+            normalize::normalize(&self.schema, &mut instance);
+        }
+        self.validate_value(validator, &instance)
+            .map_err(|issues| issues_to_js(&issues))?;
+        Ok(if self.options.normalize {
+            to_js(&instance)
+        } else {
+            JsValue::UNDEFINED
+        })
+    }
+
+    /// Returns `{ used_refs, refs }`, the URIs of the external documents compiling
+    /// the schema resolved `$ref`s to, with the `record_refs` option, and
+    /// `undefined` otherwise.
+    #[wasm_bindgen]
+    pub fn ref_report(&self) -> JsValue {
+        self.resolved_ref_report()
+            .map_or(JsValue::UNDEFINED, |report| to_js(&report))
+    }
+
+    /// Drops the compiled schema immediately instead of waiting for `free()` or
+    /// finalization. Any later `validate` call fails.
+    #[wasm_bindgen]
+    pub fn dispose(&mut self) {
+        self.validator = None;
+        self.memo = None;
+    }
+}
+
+#[wasm_bindgen]
+pub fn validate(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = parse_options(options_js)?;
+    // The `true` schema accepts everything, so skip compiling it. Only the size
+    // limits still apply, and `closed`, which can fail even a `true` schema.
+    if schema_js.as_bool() == Some(true) && !options.closed {
+        let instance: Value = from_js(instance_js, "Instance")?;
+        check_instance_size(&instance, &options).map_err(|issues| issues_to_js(&issues))?;
+        return Ok(if options.normalize {
+            to_js(&instance)
+        } else {
+            JsValue::UNDEFINED
+        });
+    }
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let validator = compile_cached(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    WasmValidator::from_parts(validator, schema, options).validate(instance_js)
+}
+
+/// Compiled schemas shared by the stateless [`validate`] calls.
+static SCHEMA_CACHE: LazyLock<Mutex<cache::SchemaCache<Validator>>> =
+    LazyLock::new(|| Mutex::new(cache::SchemaCache::new(cache::CAPACITY)));
+
+fn schema_cache() -> std::sync::MutexGuard<'static, cache::SchemaCache<Validator>> {
+    lock_cache(&SCHEMA_CACHE)
+}
+
+fn lock_cache(
+    cache: &Mutex<cache::SchemaCache<Validator>>,
+) -> std::sync::MutexGuard<'_, cache::SchemaCache<Validator>> {
+    // The cache holds no invariants a panicking thread could break.
+    cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Compiles `schema` through [`SCHEMA_CACHE`], see [`compile_cached_in`].
+fn compile_cached(
+    schema: &Value,
+    options: &ValidatorOptions,
+) -> Result<Arc<Validator>, Vec<ValidationIssue>> {
+    compile_cached_in(&SCHEMA_CACHE, schema, options)
+}
+
+/// Compiles `schema` through `cache`, keyed by the schema text and the options
+/// that affect compilation. Schemas with custom keywords aren't cached, since
+/// their functions can't be compared.
+fn compile_cached_in(
+    cache: &Mutex<cache::SchemaCache<Validator>>,
+    schema: &Value,
+    options: &ValidatorOptions,
+) -> Result<Arc<Validator>, Vec<ValidationIssue>> {
+    if !options.keywords.is_empty() {
+        return compile_schema(schema, options).map(Arc::new);
+    }
+    let key = format!(
+        "{}\0{}\0{}\0{}\0{:?}\0{}",
+        schema,
+        options
+            .meta_schema
+            .as_ref()
+            .map(Value::to_string)
+            .unwrap_or_default(),
+        options.enforce_date_formats,
+        options.lax_email,
+        options.draft,
+        options.legacy_definitions,
+    );
+    if let Some(validator) = lock_cache(cache).get(&key) {
+        return Ok(validator);
+    }
+    let validator = Arc::new(compile_schema(schema, options)?);
+    lock_cache(cache).insert(key, validator.clone());
+    Ok(validator)
+}
+
+/// Drops every schema compiled by [`validate`], e.g. after a deploy.
+#[wasm_bindgen]
+pub fn clear_schema_cache() {
+    schema_cache().clear();
+}
+
+/// Returns `{ hits, misses, size }` for the schema cache behind [`validate`].
+#[wasm_bindgen]
+pub fn schema_cache_stats() -> JsValue {
+    to_js(&schema_cache().stats())
+}
+
+/// Validates an instance against the intersection of several schemas by wrapping
+/// them in a synthetic `{ "allOf": [...] }` root.
+fn validate_against_all(
+    schemas: Vec<Value>,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    let schema = serde_json::json!({ "allOf": schemas });
+    let validator = compile_schema(&schema, options)?;
+    validate_internal(&validator, &schema, instance, options)
+}
+
+#[wasm_bindgen]
+pub fn validate_all_of(
+    schemas_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let options = parse_options(options_js)?;
+    let schemas: Vec<Value> = from_js(schemas_js, "Schemas")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    validate_against_all(schemas, &instance, &options).map_err(|issues| issues_to_js(&issues))
+}
+
+/// Finds the first branch of a root `oneOf`/`anyOf` that accepts the instance.
+/// The full schema is validated first, and its issues returned if it fails.
+/// Each branch is then checked in place of the whole union so that sibling
+/// keywords and `$defs` still apply.
+fn find_matching_branch(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<usize, Vec<ValidationIssue>> {
+    let (keyword, branches) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|keyword| {
+            schema
+                .get(keyword)
+                .and_then(Value::as_array)
+                .map(|branches| (*keyword, branches))
+        })
+        .ok_or_else(|| {
+            vec![schema_issue(
+                "Schema root has no `oneOf` or `anyOf` keyword".to_string(),
+                String::new(),
+            )]
+        })?;
+    validate_internal(&compile_schema(schema, options)?, schema, instance, options)?;
+    // The full schema passed, so some branch matches: the last one if no other did.
+    let last = branches.len().saturating_sub(1);
+    for (index, branch) in branches[..last].iter().enumerate() {
+        let mut single = schema.clone();
+        single[keyword] = Value::Array(vec![branch.clone()]);
+        if compile_schema(&single, options)?.is_valid(instance) {
+            return Ok(index);
+        }
+    }
+    Ok(last)
+}
+
+#[wasm_bindgen]
+pub fn validate_which_branch(schema_js: JsValue, instance_js: JsValue) -> Result<usize, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    find_matching_branch(&schema, &instance, &ValidatorOptions::default())
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum FieldStatus {
+    Valid,
+    Invalid,
+    Missing,
+}
+
+/// The top-level properties declared in `properties` or `required`, as
+/// `(name, path, required)`. A name declared in both appears twice.
+fn top_level_properties(schema: &Value) -> Vec<(&str, String, bool)> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|properties| properties.keys().map(String::as_str))
+        .chain(required.iter().copied())
+        .map(|name| (name, walk::join("", name), required.contains(&name)))
+        .collect()
+}
+
+/// Classifies each top-level property declared in `properties` or `required`.
+/// Optional properties that are absent have no status and are left out.
+fn field_statuses(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<BTreeMap<String, FieldStatus>, Vec<ValidationIssue>> {
+    let validator = compile_schema(schema, options)?;
+    let issues = validate_internal(&validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    let mut statuses = BTreeMap::new();
+    for (name, path, required) in top_level_properties(schema) {
+        let status = if instance.get(name).is_none() {
+            if !required {
+                continue;
+            }
+            FieldStatus::Missing
+        } else if issues.iter().any(|issue| is_within(&issue.path, &path)) {
+            FieldStatus::Invalid
+        } else {
+            FieldStatus::Valid
+        };
+        statuses.insert(path, status);
+    }
+    Ok(statuses)
+}
+
+#[wasm_bindgen]
+pub fn validate_field_status(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    field_statuses(&schema, &instance, &ValidatorOptions::default())
+        .map(|statuses| to_js(&statuses))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Maps each top-level property, as in [`field_statuses`], to the code of its
+/// first issue, `missing_property` if it's required and absent, or `None` if
+/// it's valid.
+fn first_codes_by_property(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<BTreeMap<String, Option<String>>, Vec<ValidationIssue>> {
+    let validator = compile_cached(schema, options)?;
+    let issues = validate_internal(&validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    let mut codes = BTreeMap::new();
+    for (name, path, required) in top_level_properties(schema) {
+        let code = if instance.get(name).is_none() {
+            if !required {
+                continue;
+            }
+            Some("missing_property".to_string())
+        } else {
+            issues
+                .iter()
+                .find(|issue| is_within(&issue.path, &path))
+                .map(|issue| issue.code.clone())
+        };
+        codes.insert(path, code);
+    }
+    Ok(codes)
+}
+
+/// Returns `{ [path]: code | null }` for each top-level property: the code of
+/// its first issue, or `null` if it's valid. Lighter than the full issue list
+/// for coloring form fields.
+#[wasm_bindgen]
+pub fn property_status(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    first_codes_by_property(&schema, &instance, &ValidatorOptions::default())
+        .map(|codes| to_js(&codes))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug, Default)]
+struct IssueDiff {
+    added: Vec<ValidationIssue>,
+    removed: Vec<ValidationIssue>,
+    unchanged: Vec<ValidationIssue>,
+}
+
+/// Compares the issues of two instances, matching them on `(path, code)` so that
+/// message changes caused by the new value don't count as a different issue.
+fn diff_issues(old: Vec<ValidationIssue>, new: Vec<ValidationIssue>) -> IssueDiff {
+    let key = |issue: &ValidationIssue| (issue.path.clone(), issue.code.clone());
+    let old_keys: HashSet<_> = old.iter().map(key).collect();
+    let new_keys: HashSet<_> = new.iter().map(key).collect();
+    let mut diff = IssueDiff::default();
+    for issue in new {
+        if old_keys.contains(&key(&issue)) {
+            diff.unchanged.push(issue);
+        } else {
+            diff.added.push(issue);
+        }
+    }
+    diff.removed = old
+        .into_iter()
+        .filter(|issue| !new_keys.contains(&key(issue)))
+        .collect();
+    diff
+}
+
+#[wasm_bindgen]
+pub fn validate_diff(
+    schema_js: JsValue,
+    old_js: JsValue,
+    new_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let old: Value = from_js(old_js, "Instance")?;
+    let new: Value = from_js(new_js, "Instance")?;
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    let issues_of = |instance: &Value| {
+        validate_internal(&validator, &schema, instance, &options)
+            .err()
+            .unwrap_or_default()
+    };
+    Ok(to_js(&diff_issues(issues_of(&old), issues_of(&new))))
+}
+
+/// A validation result returned as a value instead of through the error channel.
+#[derive(Serialize, Debug)]
+struct ValidationOutcome {
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+    /// Present when the `profile` option is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timing: Option<Timing>,
+}
+
+/// Wall-clock durations, in microseconds.
+#[derive(Serialize, Debug, Default)]
+struct Timing {
+    compile_us: f64,
+    validate_us: f64,
+}
+
+impl From<Result<(), Vec<ValidationIssue>>> for ValidationOutcome {
+    fn from(result: Result<(), Vec<ValidationIssue>>) -> Self {
+        let issues = result.err().unwrap_or_default();
+        ValidationOutcome {
+            valid: issues.is_empty(),
+            issues,
+            timing: None,
+        }
+    }
+}
+
+fn invalid_json_issue(what: &str, error: impl std::fmt::Display) -> ValidationIssue {
+    ValidationIssue::new(
+        "/".to_string(),
+        format!("{} deserialization error: {}", what, error),
+        "invalid_json",
+    )
+}
+
+/// Like [`validate_internal`], but folds deserialization and compilation
+/// failures into the outcome so the caller never has to catch.
+fn validate_to_outcome<E: std::fmt::Display>(
+    schema: Result<Value, E>,
+    instance: Result<Value, E>,
+    options: &ValidatorOptions,
+) -> ValidationOutcome {
+    let mut timing = Timing::default();
+    let result = schema
+        .map_err(|e| vec![invalid_json_issue("Schema", e)])
+        .and_then(|schema| {
+            let start = clock::now_micros();
+            let validator = compile_schema(&schema, options);
+            timing.compile_us = clock::now_micros() - start;
+            let validator = validator?;
+            let instance = instance.map_err(|e| vec![invalid_json_issue("Instance", e)])?;
+            let start = clock::now_micros();
+            let result = validate_internal(&validator, &schema, &instance, options);
+            timing.validate_us = clock::now_micros() - start;
+            result
+        });
+    ValidationOutcome {
+        timing: options.profile.then_some(timing),
+        ..result.into()
+    }
+}
+
+/// Non-throwing variant of [`validate`] that always returns `{ valid, issues }`.
+#[wasm_bindgen]
+pub fn validate_result(schema_js: JsValue, instance_js: JsValue, options_js: JsValue) -> JsValue {
+    let outcome = match parse_options(options_js) {
+        Ok(options) => validate_to_outcome(
+            serde_wasm_bindgen::from_value(schema_js),
+            serde_wasm_bindgen::from_value(instance_js),
+            &options,
+        ),
+        Err(error) => ValidationOutcome::from(Err(vec![ValidationIssue::new(
+            "/".to_string(),
+            error.as_string().unwrap_or_default(),
+            "invalid_json",
+        )])),
+    };
+    to_js(&outcome)
+}
+
+/// Lists the JSON Pointer of every missing required property, including the
+/// property name itself (unlike `missing_property` issues, whose path is the parent).
+fn collect_missing_required(validator: &Validator, instance: &Value) -> Vec<String> {
+    validator
+        .iter_errors(instance)
+        .filter_map(|error| match &error.kind {
+            ValidationErrorKind::Required {
+                property: Value::String(property),
+            } => Some(walk::join(error.instance_path.as_str(), property)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[wasm_bindgen]
+pub fn missing_required(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let validator = compile_schema(&schema, &ValidatorOptions::default())
+        .map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&collect_missing_required(&validator, &instance)))
+}
+
+/// Validates only the values that are present by dropping `missing_property`
+/// issues, and their `dependent_required`/`dependent_schema` counterparts.
+/// `required` inside `anyOf`/`oneOf` branches still decides the branch.
+fn validate_present(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    let Err(issues) = validate_internal(validator, schema, instance, options) else {
+        return Ok(());
+    };
+    let issues: Vec<_> = issues
+        .into_iter()
+        .filter(|issue| {
+            !matches!(
+                issue.code.as_str(),
+                "missing_property" | "dependent_required" | "dependent_schema"
+            )
+        })
+        .collect();
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+#[wasm_bindgen]
+pub fn validate_partial(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    validate_present(&validator, &schema, &instance, &options)
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug)]
+struct CompileReport {
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+    warnings: Vec<drafts::CompileWarning>,
+}
+
+/// Compiles `schema` and lists keywords the effective draft would ignore.
+fn build_compile_report(schema: &Value, options: &ValidatorOptions) -> CompileReport {
+    let draft = options.draft.map_or_else(
+        || {
+            jsonschema::Draft::default()
+                .detect(schema)
+                .unwrap_or_default()
+        },
+        jsonschema::Draft::from,
+    );
+    let issues = compile_schema(schema, options).err().unwrap_or_default();
+    let mut warnings = drafts::incompatible_keywords(schema, draft);
+    warnings.extend(drafts::embedded_dialects(schema));
+    CompileReport {
+        valid: issues.is_empty(),
+        issues,
+        warnings,
+    }
+}
+
+#[wasm_bindgen]
+pub fn compile_report(schema_js: JsValue, draft_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let draft: Option<SchemaDraft> = from_js(draft_js, "Draft")?;
+    let options = ValidatorOptions {
+        draft,
+        ..ValidatorOptions::default()
+    };
+    Ok(to_js(&build_compile_report(&schema, &options)))
+}
+
+/// Validates each instance and encodes the per-instance issue lists as MessagePack.
+///
+/// Layout: an array with one entry per instance, in input order. Each entry is
+/// an array of issues (empty when the instance is valid), and each issue is a map
+/// keyed by the same field names as `ValidationIssue` in JSON.
+fn validate_many_to_msgpack(
+    validator: &Validator,
+    schema: &Value,
+    instances: &[Value],
+    options: &ValidatorOptions,
+) -> Vec<u8> {
+    let results: Vec<Vec<ValidationIssue>> = instances
+        .iter()
+        .map(|instance| {
+            validate_internal(validator, schema, instance, options)
+                .err()
+                .unwrap_or_default()
+        })
+        .collect();
+    rmp_serde::to_vec_named(&results).expect("issues always serialize to MessagePack")
+}
+
+/// Batched validation returning a transferable `Uint8Array`; see
+/// [`validate_many_to_msgpack`] for the layout.
+#[wasm_bindgen]
+pub fn validate_many_binary(schema_js: JsValue, instances_js: JsValue) -> Result<Vec<u8>, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instances: Vec<Value> = from_js(instances_js, "Instances")?;
+    let options = ValidatorOptions::default();
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    Ok(validate_many_to_msgpack(
+        &validator, &schema, &instances, &options,
+    ))
+}
+
+/// The sorted, distinct codes of the issues `instance` produces.
+fn distinct_codes(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> BTreeSet<String> {
+    validate_internal(validator, schema, instance, options)
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|issue| issue.code)
+        .collect()
+}
+
+#[wasm_bindgen]
+pub fn error_codes(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = ValidatorOptions::default();
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&distinct_codes(
+        &validator, &schema, &instance, &options,
+    )))
+}
+
+/// Validates the JSON text `instance_json[start..end]`, by UTF-8 byte offsets,
+/// as the value at `pointer` of a larger document, against the subschema found
+/// by [`walk::subschema_pointer`]. Issue paths are relative to the whole document.
+fn validate_slice_internal(
+    schema: &Value,
+    instance_json: &str,
+    start: usize,
+    end: usize,
+    pointer: &str,
+) -> Result<(), Vec<ValidationIssue>> {
+    let slice = instance_json.get(start..end).ok_or_else(|| {
+        vec![invalid_json_issue(
+            "Instance slice",
+            format!("{start}..{end} is out of bounds or not on a character boundary"),
+        )]
+    })?;
+    let instance: Value =
+        serde_json::from_str(slice).map_err(|e| vec![invalid_json_issue("Instance slice", e)])?;
+    let Some(schema_pointer) = walk::subschema_pointer(schema, pointer) else {
+        // Nothing in the schema constrains this location.
+        return Ok(());
+    };
+    let options = ValidatorOptions {
+        base_path: Some(pointer.to_string()),
+        ..ValidatorOptions::default()
+    };
+    SubschemaValidator::new(schema, &schema_pointer)?.validate(&instance, &options)
+}
+
+/// A compiled subschema of a larger schema document, whose `$ref`s still
+/// resolve against the whole document.
+struct SubschemaValidator {
+    validator: Validator,
+    schema_pointer: String,
+}
+
+impl SubschemaValidator {
+    fn new(schema: &Value, schema_pointer: &str) -> Result<Self, Vec<ValidationIssue>> {
+        let compilation_error = |e: &dyn std::fmt::Display| {
+            vec![schema_issue(
+                format!("Schema compilation error: {}", e),
+                String::new(),
+            )]
+        };
+        let base_uri = schema
+            .get("$id")
+            .and_then(Value::as_str)
+            .unwrap_or("urn:wasm-validator:root");
+        let resource = jsonschema::Resource::from_contents(schema.clone())
+            .map_err(|e| compilation_error(&e))?;
+        let fragment = walk::pointer_fragment(schema_pointer);
+        let mut wrapper = serde_json::json!({ "$ref": format!("{base_uri}#{fragment}") });
+        if let Some(dialect) = schema.get("$schema") {
+            wrapper["$schema"] = dialect.clone();
+        }
+        let validator = jsonschema::options()
+            .with_resource(base_uri, resource)
+            .build(&wrapper)
+            .map_err(|e| compilation_error(&e))?;
+        Ok(SubschemaValidator {
+            validator,
+            schema_pointer: schema_pointer.to_string(),
+        })
+    }
+
+    /// Validates `instance`, reporting schema paths within the whole document.
+    fn validate(
+        &self,
+        instance: &Value,
+        options: &ValidatorOptions,
+    ) -> Result<(), Vec<ValidationIssue>> {
+        perform_validation(&self.validator, instance, options).map_err(|issues| {
+            issues
+                .into_iter()
+                .map(|issue| {
+                    let schema_path = match issue.schema_path.strip_prefix("/$ref") {
+                        Some(rest) => format!("{}{}", self.schema_pointer, rest),
+                        None => issue.schema_path.clone(),
+                    };
+                    issue.with_schema_path(schema_path)
+                })
+                .collect()
+        })
+    }
+}
+
+#[wasm_bindgen]
+pub fn validate_slice(
+    schema_json: &str,
+    instance_json: &str,
+    start_byte: usize,
+    end_byte: usize,
+    pointer: &str,
+) -> Result<(), JsValue> {
+    let schema: Value = serde_json::from_str(schema_json)
+        .map_err(|e| issues_to_js(&[invalid_json_issue("Schema", e)]))?;
+    validate_slice_internal(&schema, instance_json, start_byte, end_byte, pointer)
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Validates JSON text, so that parse problems such as duplicate keys can be
+/// reported as `invalid_json` before schema validation.
+fn validate_text(
+    schema_json: &str,
+    instance_json: &str,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    let schema: Value =
+        serde_json::from_str(schema_json).map_err(|e| vec![invalid_json_issue("Schema", e)])?;
+    let instance = parse_instance_text(instance_json, options)?;
+    let validator = compile_schema(&schema, options)?;
+    validate_internal(&validator, &schema, &instance, options)
+}
+
+/// Parses instance JSON text, rejecting duplicate keys if the options ask to.
+fn parse_instance_text(
+    text: &str,
+    options: &ValidatorOptions,
+) -> Result<Value, Vec<ValidationIssue>> {
+    if options.reject_duplicate_keys {
+        json::from_str_unique_keys(text)
+    } else {
+        serde_json::from_str(text)
+    }
+    .map_err(|e| vec![invalid_json_issue("Instance", e)])
+}
+
+#[wasm_bindgen]
+pub fn validate_str(
+    schema_json: &str,
+    instance_json: &str,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let options = parse_options(options_js)?;
+    validate_text(schema_json, instance_json, &options).map_err(|issues| issues_to_js(&issues))
+}
+
+/// Validates a stream of instances one at a time against a schema compiled
+/// once, keeping counts of what it has seen until `reset()`.
+#[wasm_bindgen]
+pub struct StreamingValidator {
+    validator: Validator,
+    schema: Value,
+    options: ValidatorOptions,
+    pushed: u32,
+    invalid: u32,
+}
+
+impl StreamingValidator {
+    fn push_value(&mut self, instance: &Value, mask_values: Option<bool>) -> ValidationOutcome {
+        let masked;
+        let options = match mask_values {
+            Some(mask_values) if mask_values != self.options.mask_values => {
+                masked = ValidatorOptions {
+                    mask_values,
+                    ..self.options.clone()
+                };
+                &masked
+            }
+            _ => &self.options,
+        };
+        let outcome = ValidationOutcome::from(validate_internal(
+            &self.validator,
+            &self.schema,
+            instance,
+            options,
+        ));
+        self.pushed += 1;
+        if !outcome.valid {
+            self.invalid += 1;
+        }
+        outcome
+    }
+}
+
+#[wasm_bindgen]
+impl StreamingValidator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema_js: JsValue, options_js: JsValue) -> Result<StreamingValidator, JsValue> {
+        let options = parse_options(options_js)?;
+        let schema: Value = from_js(schema_js, "Schema")?;
+        let validator =
+            compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+        Ok(StreamingValidator {
+            validator,
+            schema,
+            options,
+            pushed: 0,
+            invalid: 0,
+        })
+    }
+
+    /// Validates the next instance, returning `{ valid, issues }`. `mask_values_js`
+    /// overrides the `mask_values` option for this call when it is a boolean.
+    #[wasm_bindgen]
+    pub fn push(
+        &mut self,
+        instance_js: JsValue,
+        mask_values_js: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let instance: Value = from_js(instance_js, "Instance")?;
+        Ok(to_js(&self.push_value(&instance, mask_values_js.as_bool())))
+    }
+
+    /// The number of instances pushed since creation or the last `reset()`.
+    #[wasm_bindgen]
+    pub fn pushed(&self) -> u32 {
+        self.pushed
+    }
+
+    /// The number of pushed instances that were invalid.
+    #[wasm_bindgen]
+    pub fn invalid(&self) -> u32 {
+        self.invalid
+    }
+
+    /// Clears the counts, keeping the compiled schema.
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.pushed = 0;
+        self.invalid = 0;
+    }
+}
+
+/// Rejects schemas nested deeper than `max_depth` or with more than
+/// `max_keywords` object members as `schema_too_large`, before compiling them.
+fn check_schema_within(
+    schema: &Value,
+    max_depth: Option<usize>,
+    max_keywords: Option<usize>,
+) -> Result<(), Vec<ValidationIssue>> {
+    limits::check_schema_size(schema, max_depth, max_keywords).map_err(|exceeded| {
+        let message = match exceeded {
+            limits::SchemaLimitExceeded::Depth(limit) => {
+                format!("Schema is nested deeper than the limit of {} levels", limit)
+            }
+            limits::SchemaLimitExceeded::Keywords(limit) => {
+                format!("Schema has more than the limit of {} keywords", limit)
+            }
+        };
+        vec![ValidationIssue::new(
+            "/".to_string(),
+            message,
+            "schema_too_large",
+        )]
+    })?;
+    compile_schema(schema, &ValidatorOptions::default()).map(|_| ())
+}
+
+#[wasm_bindgen]
+pub fn check_schema_bounded(
+    schema_js: JsValue,
+    max_depth: Option<usize>,
+    max_keywords: Option<usize>,
+) -> Result<(), JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    check_schema_within(&schema, max_depth, max_keywords).map_err(|issues| issues_to_js(&issues))
+}
+
+/// The instance with defaults filled in, and how it fared against the schema.
+#[derive(Serialize, Debug)]
+struct DefaultsOutcome {
+    value: Value,
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+}
+
+fn validate_with_defaults(
+    validator: &Validator,
+    schema: &Value,
+    mut instance: Value,
+    options: &ValidatorOptions,
+) -> DefaultsOutcome {
+    defaults::apply_defaults(schema, &mut instance);
+    let issues = validate_internal(validator, schema, &instance, options)
+        .err()
+        .unwrap_or_default();
+    DefaultsOutcome {
+        value: instance,
+        valid: issues.is_empty(),
+        issues,
+    }
+}
+
+#[wasm_bindgen]
+pub fn apply_defaults(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = ValidatorOptions::default();
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&validate_with_defaults(
+        &validator, &schema, instance, &options,
+    )))
+}
+
+/// An entry of an `examples` array that doesn't satisfy its own subschema.
+#[derive(Serialize, Debug)]
+struct ExampleFailure {
+    /// JSON Pointer to the example within the schema.
+    schema_path: String,
+    issues: Vec<ValidationIssue>,
+}
+
+/// Validates every value in every `examples` array against the subschema that
+/// declares it.
+fn check_examples(schema: &Value) -> Result<Vec<ExampleFailure>, Vec<ValidationIssue>> {
+    let mut examples = Vec::new();
+    walk::for_each_subschema(schema, &mut |subschema, path, _| {
+        if let Some(values) = subschema.get("examples").and_then(Value::as_array) {
+            examples.push((path.to_string(), values));
+        }
+        true
+    });
+    let options = ValidatorOptions::default();
+    let mut failures = Vec::new();
+    for (path, values) in examples {
+        let validator = SubschemaValidator::new(schema, &path)?;
+        for (index, example) in values.iter().enumerate() {
+            if let Err(issues) = validator.validate(example, &options) {
+                failures.push(ExampleFailure {
+                    schema_path: walk::join(&walk::join(&path, "examples"), &index.to_string()),
+                    issues,
+                });
+            }
+        }
+    }
+    Ok(failures)
+}
+
+#[wasm_bindgen]
+pub fn validate_examples(schema_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    compile_schema(&schema, &ValidatorOptions::default())
+        .map_err(|issues| issues_to_js(&issues))?;
+    let failures = check_examples(&schema).map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&failures))
+}
+
+/// Merges the subschemas that apply at `pointer` into one object, with `$ref`
+/// and `allOf` resolved away. `properties` are merged per property and
+/// `required` lists are combined; for any other keyword the first subschema
+/// declaring it wins. `None` if no subschema governs the location.
+fn effective_subschema(schema: &Value, instance: &Value, pointer: &str) -> Option<Value> {
+    let applied = walk::applied_at(schema, instance, pointer);
+    if applied.is_empty() {
+        return None;
+    }
+    let mut merged = serde_json::Map::new();
+    for (keyword, value) in applied
+        .iter()
+        .filter_map(|schema| schema.as_object())
+        .flatten()
+    {
+        match (keyword.as_str(), merged.get_mut(keyword), value) {
+            ("$ref" | "allOf", _, _) => {}
+            ("properties", Some(Value::Object(properties)), Value::Object(more)) => {
+                for (name, property) in more {
+                    properties
+                        .entry(name.clone())
+                        .or_insert_with(|| property.clone());
+                }
+            }
+            ("required", Some(Value::Array(required)), Value::Array(more)) => {
+                for name in more {
+                    if !required.contains(name) {
+                        required.push(name.clone());
+                    }
+                }
+            }
+            (_, Some(_), _) => {}
+            (_, None, _) => {
+                merged.insert(keyword.clone(), value.clone());
+            }
+        }
+    }
+    Some(Value::Object(merged))
+}
+
+/// Returns the merged subschema governing `pointer`, or `null`.
+#[wasm_bindgen]
+pub fn schema_at(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    pointer: &str,
+) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    Ok(to_js(&effective_subschema(&schema, &instance, pointer)))
+}
+
+/// The validation result for one CSV data row.
+#[derive(Serialize, Debug)]
+struct CsvRowResult {
+    /// 1-based line the row starts on; the header is line 1.
+    line: usize,
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+}
+
+/// Validates each data row of `text` as an object keyed by the header row, with
+/// cells coerced per [`csv::row_object`]. Malformed CSV, such as an unterminated
+/// quote, fails the whole document with an `invalid_csv` issue; a row whose cell
+/// count differs from the header's gets one as a row issue instead.
+fn validate_csv_rows(
+    validator: &Validator,
+    schema: &Value,
+    text: &str,
+    options: &ValidatorOptions,
+) -> Result<Vec<CsvRowResult>, Vec<ValidationIssue>> {
+    let mut records = csv::parse(text)
+        .map_err(|e| {
+            vec![ValidationIssue::new(
+                "/".to_string(),
+                format!("CSV parse error: {}", e),
+                "invalid_csv",
+            )]
+        })?
+        .into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    Ok(records
+        .map(|record| {
+            let issues = if record.cells.len() != header.cells.len() {
+                let issue = ValidationIssue::new(
+                    prefix_path(options.base_path.as_deref(), ""),
+                    format!(
+                        "Row has {} cells but the header has {}",
+                        record.cells.len(),
+                        header.cells.len()
+                    ),
+                    "invalid_csv",
+                );
+                finish_issues(vec![issue], &Value::Null, options)
+            } else {
+                let row = csv::row_object(schema, &header.cells, &record.cells);
+                validate_internal(validator, schema, &row, options)
+                    .err()
+                    .unwrap_or_default()
+            };
+            CsvRowResult {
+                line: record.line,
+                valid: issues.is_empty(),
+                issues,
+            }
+        })
+        .collect())
+}
+
+/// Validates CSV rows against a schema, returning `[{ line, valid, issues }]`.
+#[wasm_bindgen]
+pub fn validate_csv(
+    schema_js: JsValue,
+    csv: &str,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    validate_csv_rows(&validator, &schema, csv, &options)
+        .map(|rows| to_js(&rows))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Groups the instance paths of the issues `instance` produces by the
+/// `schema_path` of the keyword that raised them.
+fn paths_by_schema_path(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> BTreeMap<String, Vec<String>> {
+    let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for issue in validate_internal(validator, schema, instance, options)
+        .err()
+        .unwrap_or_default()
+    {
+        grouped
+            .entry(issue.schema_path)
+            .or_default()
+            .push(issue.path);
+    }
+    grouped
+}
+
+#[wasm_bindgen]
+pub fn validate_by_schema_path(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&paths_by_schema_path(
+        &validator, &schema, &instance, &options,
+    )))
+}
+
+/// The members of an issue [`problem_details`] uses, so issues built by hand on
+/// the JS side convert too.
+#[derive(Deserialize)]
+struct IssueMessage {
+    path: String,
+    message: String,
+}
+
+/// An RFC 9457 Problem Details object for a validation failure, with the
+/// messages grouped by instance path under the `errors` extension member.
+fn problem_details(issues: &[IssueMessage], status: u16) -> Value {
+    let mut errors = serde_json::Map::new();
+    for issue in issues {
+        let messages = errors
+            .entry(issue.path.clone())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(messages) = messages {
+            messages.push(Value::from(issue.message.as_str()));
+        }
+    }
+    serde_json::json!({
+        "type": "urn:wasm-validator:problem:validation-failed",
+        "title": "Validation failed",
+        "status": status,
+        "detail": format!("The request has {} validation error(s).", issues.len()),
+        "errors": errors,
+    })
+}
+
+#[wasm_bindgen]
+pub fn to_problem_details(issues_js: JsValue, status: u16) -> Result<JsValue, JsValue> {
+    let issues: Vec<IssueMessage> = from_js(issues_js, "Issues")?;
+    Ok(to_js(&problem_details(&issues, status)))
+}
+
+/// Bundles `schema` and the `resources` it references into one self-contained
+/// document, see [`bundle::bundle`]. The result is checked to compile.
+fn bundle_with_resources(
+    schema: &Value,
+    resources: &HashMap<String, Value>,
+) -> Result<Value, Vec<ValidationIssue>> {
+    let bundled = bundle::bundle(schema, resources).map_err(|e| {
+        vec![ValidationIssue::new(
+            "/".to_string(),
+            format!("Schema bundle error: {}", e),
+            "schema_reference_error",
+        )]
+    })?;
+    compile_schema(&bundled, &ValidatorOptions::default())?;
+    Ok(bundled)
+}
+
+/// Inlines the documents external `$ref`s point to into the schema's `$defs`.
+/// `resources_js` maps absolute URIs to schema documents.
+#[wasm_bindgen]
+pub fn bundle_schema(schema_js: JsValue, resources_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let resources: HashMap<String, Value> = if resources_js.is_undefined() || resources_js.is_null()
+    {
+        HashMap::new()
+    } else {
+        from_js(resources_js, "Resources")?
+    };
+    bundle_with_resources(&schema, &resources)
+        .map(|bundled| to_js(&bundled))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// A sample the old schema accepts but the new one rejects.
+#[derive(Serialize, Debug)]
+struct BreakingSample {
+    /// Index into the samples.
+    index: usize,
+    /// The issues the new schema reports for the sample.
+    issues: Vec<ValidationIssue>,
+}
+
+#[derive(Serialize, Debug)]
+struct RefinementReport {
+    /// Whether every sample valid under the old schema is still valid under the new one.
+    compatible: bool,
+    breaking: Vec<BreakingSample>,
+}
+
+/// Checks, by sampling, that data valid under `old` stays valid under `new`.
+/// Samples the old schema already rejects are ignored.
+fn check_refinement(
+    old: &Value,
+    new: &Value,
+    samples: &[Value],
+) -> Result<RefinementReport, Vec<ValidationIssue>> {
+    let options = ValidatorOptions::default();
+    let old_validator = compile_schema(old, &options)?;
+    let new_validator = compile_schema(new, &options)?;
+    let breaking: Vec<BreakingSample> = samples
+        .iter()
+        .enumerate()
+        .filter(|(_, sample)| validate_internal(&old_validator, old, sample, &options).is_ok())
+        .filter_map(|(index, sample)| {
+            let issues = validate_internal(&new_validator, new, sample, &options).err()?;
+            Some(BreakingSample { index, issues })
+        })
+        .collect();
+    Ok(RefinementReport {
+        compatible: breaking.is_empty(),
+        breaking,
+    })
+}
+
+#[wasm_bindgen]
+pub fn is_refinement(
+    old_schema_js: JsValue,
+    new_schema_js: JsValue,
+    samples_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let old: Value = from_js(old_schema_js, "Old schema")?;
+    let new: Value = from_js(new_schema_js, "New schema")?;
+    let samples: Vec<Value> = from_js(samples_js, "Samples")?;
+    check_refinement(&old, &new, &samples)
+        .map(|report| to_js(&report))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Returns a JSON Patch repairing the trivially fixable issues of the instance;
+/// see [`fixes::suggest_fixes`]. `seed` mirrors [`example_for`]'s, but each issue
+/// has at most one fix today, so it doesn't change the patch yet.
+#[wasm_bindgen]
+pub fn suggest_fixes(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    seed: Option<u64>,
+) -> Result<JsValue, JsValue> {
+    let _ = seed;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let validator = compile_schema(&schema, &ValidatorOptions::default())
+        .map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&fixes::suggest_fixes(&schema, &validator, &instance)))
+}
+
+/// Validates a dictionary-shaped object and returns its issues per key, in key
+/// order, with an empty list for valid entries. Issues about the object as a
+/// whole, such as `required` or `minProperties`, belong to no key and are left
+/// out; an instance that isn't an object fails with its issues.
+fn validate_entries(
+    validator: &Validator,
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<EntryIssues, Vec<ValidationIssue>> {
+    let issues = validate_internal(validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    let Value::Object(object) = instance else {
+        return Err(issues);
+    };
+    let mut entries: Vec<(&String, Vec<ValidationIssue>)> =
+        object.keys().map(|key| (key, Vec::new())).collect();
+    let base = options.base_path.as_deref().unwrap_or_default();
+    for issue in issues {
+        let Some(entry) = entries
+            .iter_mut()
+            .find(|(key, _)| is_within(&issue.path, &walk::join(base, key)))
+        else {
+            continue;
+        };
+        entry.1.push(issue);
+    }
+    Ok(EntryIssues(
+        entries
+            .into_iter()
+            .map(|(key, issues)| (key.clone(), issues))
+            .collect(),
+    ))
+}
+
+/// The issues of each key from [`validate_entries`], serialized as an object in
+/// key order.
+#[derive(Debug)]
+struct EntryIssues(Vec<(String, Vec<ValidationIssue>)>);
+
+impl Serialize for EntryIssues {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(key, issues)| (key, issues)))
+    }
+}
+
+#[wasm_bindgen]
+pub fn validate_map(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    validate_entries(&validator, &schema, &instance, &options)
+        .map(|entries| to_js(&entries))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Lists every property the schema declares as `[{ path, type, required }]`,
+/// with nested objects flattened into JSON Pointer paths.
+#[wasm_bindgen]
+pub fn schema_properties(schema_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    Ok(to_js(&properties::schema_properties(&schema)))
+}
+
+/// Validates a JSON5 document, such as a config file with comments. A syntax
+/// error is reported as an `invalid_json` issue naming the line and column.
+fn validate_json5_text(
+    schema: &Value,
+    text: &str,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    let instance =
+        json::from_json5_str(text).map_err(|e| vec![invalid_json_issue("Instance", e)])?;
+    let validator = compile_schema(schema, options)?;
+    validate_internal(&validator, schema, &instance, options)
+}
+
+/// Validates JSON5 text against a schema.
+#[wasm_bindgen]
+pub fn validate_json5(
+    schema_js: JsValue,
+    json5_instance: &str,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let options = parse_options(options_js)?;
+    validate_json5_text(&schema, json5_instance, &options).map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug)]
+struct PresenceReport {
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+    /// Paths of the declared, non-required properties the instance includes.
+    present_optional: Vec<String>,
+    /// Paths of the declared, non-required properties it leaves out.
+    absent_optional: Vec<String>,
+}
+
+/// Validates `instance` and sorts the optional properties that
+/// [`properties::schema_properties`] lists into present and absent ones.
+fn presence(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<PresenceReport, Vec<ValidationIssue>> {
+    let validator = compile_schema(schema, options)?;
+    let issues = validate_internal(&validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    let (present_optional, absent_optional) = properties::schema_properties(schema)
+        .into_iter()
+        .filter(|property| !property.required)
+        .map(|property| property.path)
+        .partition(|path| instance.pointer(path).is_some());
+    Ok(PresenceReport {
+        valid: issues.is_empty(),
+        issues,
+        present_optional,
+        absent_optional,
+    })
+}
+
+/// Validates an instance and reports which optional properties it includes, as
+/// `{ valid, issues, present_optional, absent_optional }`.
+#[wasm_bindgen]
+pub fn presence_report(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    presence(&schema, &instance, &ValidatorOptions::default())
+        .map(|report| to_js(&report))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Sets each issue's `context_value` to the instance subtree `context_depth`
+/// levels above its path, or the whole instance if the path is shallower. With
+/// `mask_values`, every leaf of the subtree is replaced by `null`.
+fn attach_context(
+    issues: &mut [ValidationIssue],
+    instance: &Value,
+    context_depth: usize,
+    options: &ValidatorOptions,
+) {
+    for issue in issues.iter_mut() {
+        let path = options
+            .base_path
+            .as_deref()
+            .and_then(|base| issue.path.strip_prefix(base))
+            .unwrap_or(&issue.path);
+        let segments: Vec<&str> = path.split('/').skip(1).collect();
+        let keep = segments.len().saturating_sub(context_depth);
+        let ancestor: String = segments[..keep]
+            .iter()
+            .map(|segment| format!("/{}", segment))
+            .collect();
+        issue.context_value = instance.pointer(&ancestor).map(|subtree| {
+            let mut subtree = subtree.clone();
+            if options.mask_values {
+                mask_leaves(&mut subtree);
+            }
+            subtree
+        });
+    }
+}
+
+fn mask_leaves(value: &mut Value) {
+    match value {
+        Value::Object(properties) => properties.values_mut().for_each(mask_leaves),
+        Value::Array(items) => items.iter_mut().for_each(mask_leaves),
+        leaf => *leaf = Value::Null,
+    }
+}
+
+/// Validates like [`validate`], attaching to each issue the instance subtree
+/// `context_depth` levels above the failure as `context_value`.
+#[wasm_bindgen]
+pub fn validate_with_context(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    context_depth: usize,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = parse_options(options_js)?;
+    let validator = compile_schema(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    validate_internal(&validator, &schema, &instance, &options).map_err(|mut issues| {
+        attach_context(&mut issues, &instance, context_depth, &options);
+        issues_to_js(&issues)
+    })
+}
+
+/// The issues of one failing array element.
+#[derive(Serialize, Debug)]
+struct ItemIssues {
+    index: usize,
+    /// Issues with paths into the whole array, e.g. `/3/name`.
+    issues: Vec<ValidationIssue>,
+}
+
+/// Validates each element of the array `instance` against the schema's `items`
+/// subschema on its own, stopping once `max_failing_items` elements have failed.
+/// Keywords about the array as a whole, such as `minItems`, aren't checked.
+fn validate_items(
+    schema: &Value,
+    instance: &Value,
+    max_failing_items: Option<usize>,
+    options: &ValidatorOptions,
+) -> Result<Vec<ItemIssues>, Vec<ValidationIssue>> {
+    if !schema
+        .get("items")
+        .is_some_and(|items| items.is_object() || items.is_boolean())
+    {
+        return Err(vec![schema_issue(
+            "Schema has no single `items` subschema".to_string(),
+            String::new(),
+        )]);
+    }
+    let Value::Array(items) = instance else {
+        // Validated rather than built by hand, so masking and `base_path` apply.
+        let array = serde_json::json!({ "type": "array" });
+        let validator = compile_schema(&array, options)?;
+        return Err(validate_internal(&validator, &array, instance, options)
+            .err()
+            .unwrap_or_default());
+    };
+    let validator = SubschemaValidator::new(schema, "/items")?;
+    let mut failing = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        if max_failing_items.is_some_and(|max| failing.len() >= max) {
+            break;
+        }
+        let item_options = ValidatorOptions {
+            base_path: Some(prefix_path(
+                options.base_path.as_deref(),
+                &format!("/{}", index),
+            )),
+            ..options.clone()
+        };
+        if let Err(issues) = validator.validate(item, &item_options) {
+            failing.push(ItemIssues { index, issues });
+        }
+    }
+    Ok(failing)
+}
+
+/// Validates each element of an array against the schema's `items`, returning
+/// `[{ index, issues }]` for the failing elements, at most `max_failing_items`.
+#[wasm_bindgen]
+pub fn validate_array(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    max_failing_items: Option<usize>,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = parse_options(options_js)?;
+    validate_items(&schema, &instance, max_failing_items, &options)
+        .map(|failing| to_js(&failing))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// The closed set of values the subschema at `schema_pointer` allows: its
+/// `const`, its `enum`, or the values of a `type` made only of `boolean` and
+/// `null`. A subschema that is just a local `$ref` is resolved first. `None` if
+/// the values aren't enumerable or there is no subschema at the pointer.
+fn allowed_values(schema: &Value, schema_pointer: &str) -> Option<Vec<Value>> {
+    let mut subschema = walk::at_schema_path(schema, schema_pointer)?;
+    // Bounds `$ref` chains, which may be cyclic.
+    for _ in 0..32 {
+        match subschema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => subschema = walk::resolve_local_ref(schema, reference)?,
+            None => break,
+        }
+    }
+    if let Some(value) = subschema.get("const") {
+        return Some(vec![value.clone()]);
+    }
+    if let Some(Value::Array(options)) = subschema.get("enum") {
+        return Some(options.clone());
+    }
+    let types: Vec<&str> = match subschema.get("type")? {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+        _ => return None,
+    };
+    types
+        .iter()
+        .map(|name| match *name {
+            "boolean" => Some(vec![Value::Bool(true), Value::Bool(false)]),
+            "null" => Some(vec![Value::Null]),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|values| values.concat())
+}
+
+/// Returns the allowed values of the subschema at a JSON Pointer into the
+/// schema, e.g. `/properties/status`, or `null` if they aren't a closed set.
+#[wasm_bindgen]
+pub fn allowed_values_at(schema_js: JsValue, pointer: &str) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    Ok(to_js(&allowed_values(&schema, pointer)))
+}
+
+/// Reduces issues to `[path, message]` pairs.
+fn flat_issues(issues: Vec<ValidationIssue>) -> Vec<(String, String)> {
+    issues
+        .into_iter()
+        .map(|issue| (issue.path, issue.message))
+        .collect()
+}
+
+/// Like [`validate`], but throws just `[[path, message], ...]`, including for an
+/// invalid schema.
+#[wasm_bindgen]
+pub fn validate_flat(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    compile_cached(&schema, &options)
+        .and_then(|validator| validate_internal(&validator, &schema, &instance, &options))
+        .map_err(|issues| to_js(&flat_issues(issues)))
+}
+
+/// Estimates how costly a schema is to validate against, as `{ keyword_count,
+/// regex_count, max_ref_depth, branch_product }`, without compiling it.
+#[wasm_bindgen]
+pub fn schema_cost(schema_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    Ok(to_js(&cost::schema_cost(&schema)))
+}
+
+/// The validation result for one NDJSON line.
+#[derive(Serialize, Debug)]
+struct NdjsonLineResult {
+    /// 1-based line number.
+    line: usize,
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+}
+
+/// Validates each non-blank line of `ndjson` as a JSON document, handing each
+/// result to `on_line` as soon as it's ready, until `on_line` returns `false`.
+/// A line that isn't valid JSON gets an `invalid_json` issue. Returns how many
+/// lines were handed over.
+fn for_each_ndjson_line<E>(
+    validator: &Validator,
+    schema: &Value,
+    ndjson: &str,
+    options: &ValidatorOptions,
+    on_line: &mut dyn FnMut(NdjsonLineResult) -> Result<bool, E>,
+) -> Result<u32, E> {
+    let mut count = 0;
+    for (index, text) in ndjson.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        let issues = parse_instance_text(text, options)
+            .and_then(|instance| validate_internal(validator, schema, &instance, options))
+            .err()
+            .unwrap_or_default();
+        count += 1;
+        let result = NdjsonLineResult {
+            line: index + 1,
+            valid: issues.is_empty(),
+            issues,
+        };
+        if !on_line(result)? {
+            break;
+        }
+    }
+    Ok(count)
+}
+
+/// Validates newline-delimited JSON, calling `callback({ line, valid, issues })`
+/// for each line as it's processed. Stops early if the callback returns `false`.
+/// Returns the number of lines processed.
+#[wasm_bindgen]
+pub fn validate_ndjson_each(
+    schema_js: JsValue,
+    ndjson: &str,
+    callback: js_sys::Function,
+    options_js: JsValue,
+) -> Result<u32, JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let validator = compile_cached(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    for_each_ndjson_line(&validator, &schema, ndjson, &options, &mut |result| {
+        let proceed = callback.call1(&JsValue::NULL, &to_js(&result))?;
+        Ok(proceed.as_bool() != Some(false))
+    })
+}
+
+/// Validates `instance` against the schema at `schema_pointer` within `document`,
+/// resolving the schema's `$ref`s against the whole document.
+fn validate_embedded_schema(
+    document: &Value,
+    schema_pointer: &str,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<(), Vec<ValidationIssue>> {
+    if document.pointer(schema_pointer).is_none() {
+        return Err(vec![schema_issue(
+            format!("No schema at '{}' in the document", schema_pointer),
+            schema_pointer.to_string(),
+        )]);
+    }
+    SubschemaValidator::new(document, schema_pointer)?.validate(instance, options)
+}
+
+/// Validates against a schema embedded in a larger document, such as an OpenAPI
+/// `/components/schemas/User`, whose `$ref`s point elsewhere in the document.
+#[wasm_bindgen]
+pub fn validate_embedded(
+    document_js: JsValue,
+    schema_pointer: &str,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let document: Value = from_js(document_js, "Document")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = parse_options(options_js)?;
+    validate_embedded_schema(&document, schema_pointer, &instance, &options)
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug)]
+struct PairIssues {
+    request: Vec<ValidationIssue>,
+    response: Vec<ValidationIssue>,
+}
+
+/// Validates a request and a response against their own schemas. Either schema
+/// failing to compile fails the whole call.
+fn validate_request_response(
+    request_schema: &Value,
+    request: &Value,
+    response_schema: &Value,
+    response: &Value,
+    options: &ValidatorOptions,
+) -> Result<PairIssues, Vec<ValidationIssue>> {
+    let issues = |schema: &Value, instance: &Value| -> Result<_, Vec<ValidationIssue>> {
+        let validator = compile_cached(schema, options)?;
+        Ok(validate_internal(&validator, schema, instance, options)
+            .err()
+            .unwrap_or_default())
+    };
+    Ok(PairIssues {
+        request: issues(request_schema, request)?,
+        response: issues(response_schema, response)?,
+    })
+}
+
+/// Validates a request/response pair for contract tests, returning
+/// `{ request, response }` with the issues of each.
+#[wasm_bindgen]
+pub fn validate_pair(
+    request_schema_js: JsValue,
+    request_js: JsValue,
+    response_schema_js: JsValue,
+    response_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let request_schema: Value = from_js(request_schema_js, "Request schema")?;
+    let request: Value = from_js(request_js, "Request")?;
+    let response_schema: Value = from_js(response_schema_js, "Response schema")?;
+    let response: Value = from_js(response_js, "Response")?;
+    let options = parse_options(options_js)?;
+    validate_request_response(
+        &request_schema,
+        &request,
+        &response_schema,
+        &response,
+        &options,
+    )
+    .map(|issues| to_js(&issues))
+    .map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum UsedSchema {
+    Primary,
+    Fallback,
+}
+
+#[derive(Serialize, Debug)]
+struct FallbackResult {
+    /// Which schema the instance was validated against.
+    schema: UsedSchema,
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+}
+
+/// Validates `instance` against `primary`, or against `fallback` when `primary`
+/// doesn't compile. Only a `fallback` that doesn't compile either is an error.
+fn validate_with_fallback_schema(
+    primary: &Value,
+    fallback: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<FallbackResult, Vec<ValidationIssue>> {
+    let (schema, used, validator) = match compile_cached(primary, options) {
+        Ok(validator) => (primary, UsedSchema::Primary, validator),
+        Err(_) => (
+            fallback,
+            UsedSchema::Fallback,
+            compile_cached(fallback, options)?,
+        ),
+    };
+    let issues = validate_internal(&validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    Ok(FallbackResult {
+        schema: used,
+        valid: issues.is_empty(),
+        issues,
+    })
+}
+
+/// Validates against a dynamically loaded schema, falling back to a built-in one
+/// if it doesn't compile. Returns `{ schema, valid, issues }`, where `schema` is
+/// `"primary"` or `"fallback"`.
+#[wasm_bindgen]
+pub fn validate_with_fallback(
+    primary_schema_js: JsValue,
+    fallback_schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let primary: Value = from_js(primary_schema_js, "Primary schema")?;
+    let fallback: Value = from_js(fallback_schema_js, "Fallback schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = parse_options(options_js)?;
+    validate_with_fallback_schema(&primary, &fallback, &instance, &options)
+        .map(|result| to_js(&result))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Synthesizes a minimal value the subschema at a JSON Pointer into the schema
+/// accepts, e.g. `/properties/status`, to show users what a failing field
+/// expects. Returns `undefined` if the pointer doesn't resolve or no example
+/// could be made. A `seed` varies the example reproducibly.
+#[wasm_bindgen]
+pub fn example_for(
+    schema_js: JsValue,
+    pointer: &str,
+    seed: Option<u64>,
+) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    Ok(walk::at_schema_path(&schema, pointer)
+        .and_then(|subschema| example::example_for(&schema, subschema, seed))
+        .map_or(JsValue::UNDEFINED, |example| to_js(&example)))
+}
+
+/// Per-entry compile results, shaped like the input collection.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum SchemaChecks {
+    List(Vec<ValidationOutcome>),
+    Map(serde_json::Map<String, Value>),
+}
+
+/// Compiles each schema in an array, or each value of an object keyed by name,
+/// on its own so that one bad schema doesn't hide the others. Any object is read
+/// as the keyed form, so a lone schema object must be wrapped in an array. Any
+/// other value is checked as a list of one.
+fn check_schema_set(schemas: Value) -> SchemaChecks {
+    let check = |schema: &Value| -> ValidationOutcome {
+        compile_schema(schema, &ValidatorOptions::default())
+            .map(|_| ())
+            .into()
+    };
+    match schemas {
+        Value::Object(named) => SchemaChecks::Map(
+            named
+                .iter()
+                .map(|(name, schema)| (name.clone(), serde_json::json!(check(schema))))
+                .collect(),
+        ),
+        Value::Array(schemas) => SchemaChecks::List(schemas.iter().map(check).collect()),
+        schema => SchemaChecks::List(vec![check(&schema)]),
+    }
+}
+
+/// Lints a batch of schemas, e.g. every schema file in a repository. Returns a
+/// `{ valid, issues }` per entry, in an array or an object matching the input.
+/// Never throws: input that isn't JSON is reported as a single failed entry.
+#[wasm_bindgen]
+pub fn check_schemas(schemas_js: JsValue) -> JsValue {
+    let checks = match serde_wasm_bindgen::from_value(schemas_js) {
+        Ok(schemas) => check_schema_set(schemas),
+        Err(e) => SchemaChecks::List(vec![Err(vec![invalid_json_issue("Schemas", e)]).into()]),
+    };
+    to_js(&checks)
+}
+
+#[derive(Serialize, Debug)]
+struct Completeness {
+    /// `satisfied` as a whole percentage of `total`; 100 when nothing applies.
+    score: u32,
+    satisfied: usize,
+    total: usize,
+    issues: Vec<ValidationIssue>,
+}
+
+/// Scores how close `instance` is to valid, as the share of the constraints
+/// [`completeness::applicable_constraints`] counts that raised no issue.
+fn completeness_report(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<Completeness, Vec<ValidationIssue>> {
+    let validator = compile_cached(schema, options)?;
+    let issues = validate_internal(&validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    let total = completeness::applicable_constraints(schema, instance);
+    let satisfied = total.saturating_sub(issues.len());
+    let score = match total {
+        0 if issues.is_empty() => 100,
+        0 => 0,
+        _ => (satisfied * 100 / total) as u32,
+    };
+    Ok(Completeness {
+        score,
+        satisfied,
+        total,
+        issues,
+    })
+}
+
+/// Returns `{ score, satisfied, total, issues }`, where `score` from 0 to 100
+/// tells how much of the schema a partly filled instance already satisfies,
+/// e.g. for a form progress bar.
+#[wasm_bindgen]
+pub fn completeness(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    completeness_report(&schema, &instance, &ValidatorOptions::default())
+        .map(|report| to_js(&report))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Finds the first of `schemas` that compiles and accepts `instance`. When none
+/// does, returns the issues of the last one, compilation issues included.
+fn first_matching_schema(
+    schemas: &[Value],
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<usize, Vec<ValidationIssue>> {
+    let mut last_issues = vec![schema_issue(
+        "No candidate schemas were given".to_string(),
+        String::new(),
+    )];
+    for (index, schema) in schemas.iter().enumerate() {
+        match compile_cached(schema, options)
+            .and_then(|validator| validate_internal(&validator, schema, instance, options))
+        {
+            Ok(()) => return Ok(index),
+            Err(issues) => last_issues = issues,
+        }
+    }
+    Err(last_issues)
+}
+
+/// Validates against several schema versions in order, e.g. for versioned
+/// payloads, returning the index of the first that compiles and accepts the
+/// instance. Throws the issues of the last schema if none does.
+#[wasm_bindgen]
+pub fn validate_first_match(
+    schemas_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<usize, JsValue> {
+    let options = parse_options(options_js)?;
+    let schemas: Vec<Value> = from_js(schemas_js, "Schemas")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    first_matching_schema(&schemas, &instance, &options).map_err(|issues| issues_to_js(&issues))
+}
+
+/// A minimal SARIF 2.1.0 log with one result per issue, each located in
+/// `source_uri` and, logically, at the issue's instance path.
+fn sarif_log(issues: &[ValidationIssue], source_uri: &str) -> Value {
+    let results: Vec<Value> = issues
+        .iter()
+        .map(|issue| {
+            serde_json::json!({
+                "ruleId": issue.code,
+                "level": "error",
+                "message": { "text": issue.message },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": source_uri } },
+                    "logicalLocations": [{ "fullyQualifiedName": issue.path, "kind": "member" }],
+                }],
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Validates `instance` and returns the issues as a SARIF log for code-scanning
+/// UIs, locating each in `source_uri`, the file the instance was read from. A
+/// valid instance gives a run with no results.
+#[wasm_bindgen]
+pub fn validate_sarif(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    source_uri: &str,
+) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    let options = ValidatorOptions::default();
+    let validator = compile_cached(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    let issues = validate_internal(&validator, &schema, &instance, &options)
+        .err()
+        .unwrap_or_default();
+    Ok(to_js(&sarif_log(&issues, source_uri)))
+}
+
+/// Applies `patch` to `base` and validates the result, keeping only the issues
+/// at or under a path the patch changed. A missing property counts as being at
+/// its own path, so removing a required property is reported.
+fn validate_patched(
+    validator: &Validator,
+    schema: &Value,
+    base: &Value,
+    patch: &[Value],
+    options: &ValidatorOptions,
+) -> Result<Result<(), Vec<ValidationIssue>>, String> {
+    let mut patched = base.clone();
+    let changed: Vec<String> = patch::apply_patch(&mut patched, patch)?
+        .iter()
+        .map(|path| prefix_path(options.base_path.as_deref(), path))
+        .collect();
+    let Err(issues) = validate_internal(validator, schema, &patched, options) else {
+        return Ok(Ok(()));
+    };
+    let issues: Vec<_> = issues
+        .into_iter()
+        .filter(|issue| {
+            let path = match issue.message_params.get("property").and_then(Value::as_str) {
+                Some(property) if issue.code == "missing_property" => {
+                    walk::join(&issue.path, property)
+                }
+                _ => issue.path.clone(),
+            };
+            changed.iter().any(|changed| is_within(&path, changed))
+        })
+        .collect();
+    Ok(if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    })
+}
+
+/// Applies a JSON Patch to the instance and validates the result, reporting
+/// only issues at or under the paths the patch changed, e.g. while editing a
+/// large document. A patch that doesn't apply is thrown as a string.
+#[wasm_bindgen]
+pub fn validate_patch(
+    schema_js: JsValue,
+    base_instance_js: JsValue,
+    patch_js: JsValue,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let base: Value = from_js(base_instance_js, "Instance")?;
+    let patch: Vec<Value> = from_js(patch_js, "Patch")?;
+    let validator = compile_cached(&schema, &options).map_err(|issues| issues_to_js(&issues))?;
+    validate_patched(&validator, &schema, &base, &patch, &options)
+        .map_err(|e| JsValue::from_str(&format!("Patch error: {}", e)))?
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+#[derive(Serialize, Debug)]
+struct ValidationSummary {
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+    /// Every value in the instance: objects, arrays and scalars.
+    instance_nodes: usize,
+}
+
+fn summarize(
+    schema: &Value,
+    instance: &Value,
+    options: &ValidatorOptions,
+) -> Result<ValidationSummary, Vec<ValidationIssue>> {
+    let validator = compile_cached(schema, options)?;
+    let issues = validate_internal(&validator, schema, instance, options)
+        .err()
+        .unwrap_or_default();
+    Ok(ValidationSummary {
+        valid: issues.is_empty(),
+        issues,
+        instance_nodes: limits::count_nodes(instance),
+    })
+}
+
+/// Returns `{ valid, issues, instance_nodes }`, where `instance_nodes` counts
+/// every value in the instance, e.g. to bill or rate-limit by payload size.
+#[wasm_bindgen]
+pub fn validate_summary(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    summarize(&schema, &instance, &options)
+        .map(|summary| to_js(&summary))
+        .map_err(|issues| issues_to_js(&issues))
+}
+
+/// Maps each key of the `instance` object to the first of the root schema's
+/// `patternProperties` patterns it matches, in schema order, or to `null` if
+/// none does. Patterns that don't compile as regexes never match.
+fn matched_patterns(schema: &Value, instance: &Value) -> serde_json::Map<String, Value> {
+    let patterns: Vec<(&String, fancy_regex::Regex)> = schema
+        .get("patternProperties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|patterns| patterns.keys())
+        .filter_map(|pattern| Some((pattern, fancy_regex::Regex::new(pattern).ok()?)))
+        .collect();
+    instance
+        .as_object()
+        .into_iter()
+        .flat_map(|object| object.keys())
+        .map(|key| {
+            let matched = patterns
+                .iter()
+                .find(|(_, regex)| regex.is_match(key).unwrap_or(false))
+                .map_or(Value::Null, |(pattern, _)| Value::from(pattern.as_str()));
+            (key.clone(), matched)
+        })
+        .collect()
+}
+
+/// Returns `{ [key]: pattern | null }` for a dictionary-shaped instance: the
+/// `patternProperties` pattern each key matched, or `null` for keys left to
+/// `properties` or `additionalProperties`.
+#[wasm_bindgen]
+pub fn pattern_property_map(schema_js: JsValue, instance_js: JsValue) -> Result<JsValue, JsValue> {
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    compile_cached(&schema, &ValidatorOptions::default())
+        .map_err(|issues| issues_to_js(&issues))?;
+    Ok(to_js(&matched_patterns(&schema, &instance)))
+}
+
+/// Renders issues as CSV with a `path,code,message` header and one row each.
+fn issues_csv(issues: &[ValidationIssue]) -> String {
+    let mut out = String::new();
+    csv::write_record(&mut out, &["path", "code", "message"]);
+    for issue in issues {
+        csv::write_record(&mut out, &[&issue.path, &issue.code, &issue.message]);
+    }
+    out
+}
+
+/// Like [`validate`], but throws the issues as a `path,code,message` CSV string
+/// for spreadsheet triage, including for an invalid schema.
+#[wasm_bindgen]
+pub fn validate_csv_report(
+    schema_js: JsValue,
+    instance_js: JsValue,
+    options_js: JsValue,
+) -> Result<(), JsValue> {
+    let options = parse_options(options_js)?;
+    let schema: Value = from_js(schema_js, "Schema")?;
+    let instance: Value = from_js(instance_js, "Instance")?;
+    compile_cached(&schema, &options)
+        .and_then(|validator| validate_internal(&validator, &schema, &instance, &options))
+        .map_err(|issues| JsValue::from_str(&issues_csv(&issues)))
+}
+
+fn map_error_kind_to_code(kind: &ValidationErrorKind) -> &'static str {
+    match kind {
+        ValidationErrorKind::AdditionalItems { .. } => "additional_items",
+        ValidationErrorKind::AdditionalProperties { .. } => "additional_properties",
+        ValidationErrorKind::AnyOf => "any_of_mismatch",
+        ValidationErrorKind::BacktrackLimitExceeded { .. } => "regex_backtrack_limit",
+        ValidationErrorKind::Constant { .. } => "const_mismatch",
+        ValidationErrorKind::Contains => "no_match_in_contains",
+        ValidationErrorKind::ContentEncoding { .. } => "invalid_content_encoding",
+        ValidationErrorKind::ContentMediaType { .. } => "invalid_media_type",
+        ValidationErrorKind::Custom { .. } => "custom_error",
+        ValidationErrorKind::Enum { .. } => "enum_mismatch",
+        ValidationErrorKind::ExclusiveMaximum { .. } => "exclusive_max",
+        ValidationErrorKind::ExclusiveMinimum { .. } => "exclusive_min",
+        ValidationErrorKind::FalseSchema => "disallowed_value",
+        ValidationErrorKind::Format { .. } => "format_mismatch",
+        ValidationErrorKind::FromUtf8 { .. } => "invalid_utf8",
+        ValidationErrorKind::Maximum { .. } => "too_large",
+        ValidationErrorKind::MaxItems { .. } => "too_many_items",
+        ValidationErrorKind::MaxLength { .. } => "too_long",
+        ValidationErrorKind::MaxProperties { .. } => "too_many_properties",
+        ValidationErrorKind::Minimum { .. } => "too_small",
+        ValidationErrorKind::MinItems { .. } => "too_few_items",
+        ValidationErrorKind::MinLength { .. } => "too_short",
+        ValidationErrorKind::MinProperties { .. } => "too_few_properties",
+        ValidationErrorKind::MultipleOf { .. } => "not_a_multiple",
+        ValidationErrorKind::Not { .. } => "negated_schema_match",
+        ValidationErrorKind::OneOfMultipleValid => "one_of_multiple_matches",
+        ValidationErrorKind::OneOfNotValid => "one_of_no_match",
+        ValidationErrorKind::Pattern { .. } => "pattern_mismatch",
+        ValidationErrorKind::PropertyNames { .. } => "invalid_property_name",
+        ValidationErrorKind::Required { .. } => "missing_property",
+        ValidationErrorKind::Type { .. } => "invalid_type",
+        ValidationErrorKind::UnevaluatedItems { .. } => "unevaluated_items",
+        ValidationErrorKind::UnevaluatedProperties { .. } => "unevaluated_properties",
+        ValidationErrorKind::UniqueItems => "duplicate_items",
+        ValidationErrorKind::Referencing(..) => "schema_reference_error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Counts the allocations of the current thread only, so tests running in
+    /// parallel don't disturb each other's counts.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            std::alloc::System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            std::alloc::System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn count_allocations(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(std::cell::Cell::get);
+        f();
+        ALLOCATIONS.with(std::cell::Cell::get) - before
+    }
+
+    fn get_complex_schema() -> Value {
+        json!({
+          "type": "object",
+          "properties": {
+            "id": { "type": "string", "pattern": "^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$" },
+            "username": { "type": "string", "minLength": 3 },
+            "status": { "type": "string", "enum": ["active", "inactive", "pending"] },
+            "profile": {
+              "type": "object",
+              "properties": { "fullName": { "type": "string" }, "age": { "type": "number", "minimum": 18 } },
+              "required": ["fullName"],
+            },
+            "tags": { "type": "array", "items": { "type": "string" }, "minItems": 1, "uniqueItems": true },
+          },
+          "required": ["id", "username", "status", "tags"],
+        })
+    }
+
+    #[test]
+    fn test_pass_on_valid_complex_instance() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let valid_instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 30 },
+          "tags": ["rust", "validate", "nodejs"],
+        });
+        let result = perform_validation(&validator, &valid_instance, &ValidatorOptions::default());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_fail_on_nested_property_error() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let invalid_instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 17 },
+          "tags": ["testing"],
+        });
+        let issues =
+            perform_validation(&validator, &invalid_instance, &ValidatorOptions::default())
+                .unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "too_small");
+        assert_eq!(issues[0].path, "/profile/age");
+    }
+
+    #[test]
+    fn test_report_multiple_simultaneous_errors() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let very_invalid_instance = json!({
+          "id": "invalid-uuid",
+          "username": "a",
+          "profile": { "age": 20 },
+          "tags": [],
+        });
+        let issues = perform_validation(
+            &validator,
+            &very_invalid_instance,
+            &ValidatorOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues.len(), 5);
+        let codes: Vec<_> = issues.iter().map(|issue| &issue.code).collect();
+        assert!(codes.contains(&&"pattern_mismatch".to_string()));
+        assert!(codes.contains(&&"too_short".to_string()));
+        assert!(codes.contains(&&"missing_property".to_string()));
+        assert!(codes.contains(&&"too_few_items".to_string()));
+    }
+
+    #[test]
+    fn test_fail_with_masked_values() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let invalid_instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "a",
+          "status": "pending",
+          "tags": ["masked"],
+        });
+        let masked = ValidatorOptions {
+            mask_values: true,
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &invalid_instance, &masked).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        let issue = &issues[0];
+        assert!(!issue.message.contains(r#""a""#));
+        assert!(issue.message.contains("value is shorter than 3 characters"));
+    }
+
+    #[test]
+    fn test_prefix_paths_with_base_path() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let invalid_instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 17 },
+          "tags": ["testing"],
+        });
+        let options = ValidatorOptions {
+            base_path: Some("/order".to_string()),
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &invalid_instance, &options).unwrap_err();
+        assert_eq!(issues[0].path, "/order/profile/age");
+
+        let options: ValidatorOptions =
+            serde_json::from_value(json!({ "base_path": "/order/" })).unwrap();
+        assert_eq!(options.base_path.as_deref(), Some("/order"));
+        let issues = perform_validation(&validator, &invalid_instance, &options).unwrap_err();
+        assert_eq!(issues[0].path, "/order/profile/age");
+        assert_eq!(prefix_path(Some("/order/"), "/x"), "/order/x");
+    }
+
+    #[test]
+    fn test_base_path_replaces_root_path() {
+        let validator = Validator::new(&json!({ "type": "object" })).unwrap();
+        let options = ValidatorOptions {
+            base_path: Some("/order/items/0".to_string()),
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &json!(42), &options).unwrap_err();
+        assert_eq!(issues[0].path, "/order/items/0");
+    }
+
+    #[test]
+    fn test_boolean_root_schemas() {
+        let accept_all = Validator::new(&json!(true)).unwrap();
+        let reject_all = Validator::new(&json!(false)).unwrap();
+        for instance in [json!(null), json!(1), json!("a"), json!({ "a": [1] })] {
+            assert!(
+                perform_validation(&accept_all, &instance, &ValidatorOptions::default()).is_ok()
+            );
+            let issues = perform_validation(&reject_all, &instance, &ValidatorOptions::default())
+                .unwrap_err();
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].code, "disallowed_value");
+            assert_eq!(issues[0].path, "");
+        }
+    }
+
+    #[test]
+    fn test_boolean_schemas_nested_in_properties() {
+        let schema = json!({
+          "type": "object",
+          "properties": { "anything": true, "forbidden": false },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let valid_instance = json!({ "anything": { "nested": [1, 2] } });
+        assert!(
+            perform_validation(&validator, &valid_instance, &ValidatorOptions::default()).is_ok()
+        );
+        let invalid_instance = json!({ "anything": 1, "forbidden": "x" });
+        let issues =
+            perform_validation(&validator, &invalid_instance, &ValidatorOptions::default())
+                .unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "disallowed_value");
+        assert_eq!(issues[0].path, "/forbidden");
+    }
+
+    #[test]
+    fn test_all_of_reports_second_schema_violation() {
+        let base = json!({ "type": "object", "required": ["name"] });
+        let extension = json!({ "properties": { "age": { "type": "integer", "minimum": 0 } } });
+        let instance = json!({ "name": "Ada", "age": -1 });
+        let issues = validate_against_all(
+            vec![base, extension],
+            &instance,
+            &ValidatorOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "too_small");
+        assert_eq!(issues[0].path, "/age");
+        assert!(issues[0].schema_path.starts_with("/allOf/1/"));
+    }
+
+    #[test]
+    fn test_reject_instance_exceeding_max_depth() {
+        let validator = Validator::new(&json!(true)).unwrap();
+        let mut deep = json!("leaf");
+        for _ in 0..100 {
+            deep = json!({ "child": deep });
+        }
+        let options = ValidatorOptions {
+            max_depth: Some(32),
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_internal(&validator, &json!(true), &deep, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "instance_too_large");
+        assert_eq!(issues[0].path, "");
+    }
+
+    #[test]
+    fn test_reject_instance_exceeding_max_nodes() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let wide: serde_json::Map<String, Value> =
+            (0..1000).map(|i| (format!("key{}", i), json!(i))).collect();
+        let options = ValidatorOptions {
+            max_nodes: Some(100),
+            ..ValidatorOptions::default()
+        };
+        let issues =
+            validate_internal(&validator, &schema, &Value::Object(wide), &options).unwrap_err();
+        // Validation is short-circuited, so the missing required properties aren't reported.
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "instance_too_large");
+    }
+
+    #[test]
+    fn test_which_branch_selects_matching_index() {
+        let schema = json!({
+          "anyOf": [
+            { "type": "object", "properties": { "kind": { "const": "circle" } }, "required": ["kind", "radius"] },
+            { "type": "object", "properties": { "kind": { "const": "square" } }, "required": ["kind", "side"] },
+          ],
+        });
+        let options = ValidatorOptions::default();
+        let circle = json!({ "kind": "circle", "radius": 2 });
+        let square = json!({ "kind": "square", "side": 3 });
+        assert_eq!(find_matching_branch(&schema, &circle, &options), Ok(0));
+        assert_eq!(find_matching_branch(&schema, &square, &options), Ok(1));
+    }
+
+    #[test]
+    fn test_which_branch_returns_issues_without_match() {
+        let schema = json!({ "anyOf": [{ "type": "string" }, { "type": "integer" }] });
+        let issues =
+            find_matching_branch(&schema, &json!(1.5), &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "any_of_mismatch");
+    }
+
+    #[test]
+    fn test_grapheme_count_for_string_length_errors() {
+        let validator = Validator::new(&json!({ "type": "string", "maxLength": 2 })).unwrap();
+        // "e" + combining acute accent + thumbs up: three code points, two graphemes.
+        let issues = perform_validation(
+            &validator,
+            &json!("e\u{301}👍"),
+            &ValidatorOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues[0].code, "too_long");
+        assert_eq!(issues[0].grapheme_count, Some(2));
+
+        let validator = Validator::new(&json!({ "type": "string", "minLength": 3 })).unwrap();
+        // Thumbs up + skin tone modifier: two code points, one grapheme.
+        let issues =
+            perform_validation(&validator, &json!("👍🏽"), &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues[0].code, "too_short");
+        assert_eq!(issues[0].grapheme_count, Some(1));
+        assert!(issues[0].message.contains("shorter than 3 characters"));
+    }
+
+    #[test]
+    fn test_grapheme_count_absent_for_other_errors() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let issues =
+            perform_validation(&validator, &json!({}), &ValidatorOptions::default()).unwrap_err();
+        assert!(issues.iter().all(|issue| issue.grapheme_count.is_none()));
+    }
+
+    fn get_unit_meta_schema() -> Value {
+        json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "$id": "https://example.com/meta/units",
+          "type": "object",
+          "properties": { "x-unit": { "enum": ["cm", "mm"] } },
+        })
+    }
+
+    #[test]
+    fn test_custom_meta_schema_accepts_vocabulary_keyword() {
+        let schema = json!({
+          "$schema": "https://example.com/meta/units",
+          "type": "number",
+          "x-unit": "cm",
+        });
+        let issues = compile_schema(&schema, &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_schema");
+
+        let options = ValidatorOptions {
+            meta_schema: Some(get_unit_meta_schema()),
+            ..ValidatorOptions::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        assert!(perform_validation(&validator, &json!(12), &options).is_ok());
+        let issues = perform_validation(&validator, &json!("12"), &options).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_type");
+    }
+
+    #[test]
+    fn test_custom_meta_schema_rejects_nonconforming_schema() {
+        let schema = json!({ "$schema": "https://example.com/meta/units", "x-unit": "km" });
+        let options = ValidatorOptions {
+            meta_schema: Some(get_unit_meta_schema()),
+            ..ValidatorOptions::default()
+        };
+        let issues = compile_schema(&schema, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "invalid_schema");
+        assert_eq!(issues[0].schema_path, "/x-unit");
+    }
+
+    #[test]
+    fn test_custom_meta_schema_without_id_is_rejected() {
+        let options = ValidatorOptions {
+            meta_schema: Some(json!({ "type": "object" })),
+            ..ValidatorOptions::default()
+        };
+        let issues = compile_schema(&json!({}), &options).unwrap_err();
+        assert!(issues[0]
+            .message
+            .starts_with("Meta-schema registration error"));
+    }
+
+    #[test]
+    fn test_field_status_on_complex_schema() {
+        let schema = get_complex_schema();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "a",
+          "tags": ["rust"],
+        });
+        let statuses = field_statuses(&schema, &instance, &ValidatorOptions::default()).unwrap();
+        assert_eq!(statuses["/id"], FieldStatus::Valid);
+        assert_eq!(statuses["/username"], FieldStatus::Invalid);
+        assert_eq!(statuses["/status"], FieldStatus::Missing);
+        assert_eq!(statuses["/tags"], FieldStatus::Valid);
+        assert!(!statuses.contains_key("/profile"));
+    }
+
+    #[test]
+    fn test_field_status_marks_nested_errors_invalid() {
+        let schema = get_complex_schema();
+        let instance = json!({ "profile": { "fullName": "Ada", "age": 3 } });
+        let statuses = field_statuses(&schema, &instance, &ValidatorOptions::default()).unwrap();
+        assert_eq!(statuses["/profile"], FieldStatus::Invalid);
+    }
+
+    #[test]
+    fn test_property_status_maps_first_code() {
+        let schema = get_complex_schema();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "a",
+          "tags": ["rust"],
+          "profile": { "age": 12 },
+        });
+        let codes =
+            first_codes_by_property(&schema, &instance, &ValidatorOptions::default()).unwrap();
+        assert_eq!(codes["/username"].as_deref(), Some("too_short"));
+        assert_eq!(codes["/status"].as_deref(), Some("missing_property"));
+        assert_eq!(codes["/id"], None);
+        assert_eq!(codes["/tags"], None);
+        assert!(codes["/profile"].is_some());
+    }
+
+    #[test]
+    fn test_disposed_validator_is_unusable() {
+        let schema = get_complex_schema();
+        let mut validator = WasmValidator::from_parts(
+            Arc::new(Validator::new(&schema).unwrap()),
+            schema,
+            ValidatorOptions::default(),
+        );
+        assert!(validator.compiled().is_ok());
+        validator.dispose();
+        assert_eq!(validator.compiled().err(), Some(DISPOSED_MESSAGE));
+    }
+
+    #[test]
+    fn test_diff_reports_fixed_and_introduced_issues() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let old = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "a",
+          "status": "active",
+          "tags": [],
+        });
+        let new = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "ab",
+          "status": "archived",
+          "tags": ["rust"],
+        });
+        let old_issues = perform_validation(&validator, &old, &options).unwrap_err();
+        let new_issues = perform_validation(&validator, &new, &options).unwrap_err();
+        let diff = diff_issues(old_issues, new_issues);
+        let codes = |issues: &[ValidationIssue]| -> Vec<String> {
+            issues.iter().map(|issue| issue.code.clone()).collect()
+        };
+        assert_eq!(codes(&diff.added), ["enum_mismatch"]);
+        assert_eq!(codes(&diff.removed), ["too_few_items"]);
+        // Still too short, with a different value in the message.
+        assert_eq!(codes(&diff.unchanged), ["too_short"]);
+    }
+
+    #[test]
+    fn test_enforce_date_formats() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "createdAt": { "type": "string", "format": "date-time" },
+            "homepage": { "type": "string", "format": "uri" },
+          },
+        });
+        let malformed = json!({ "createdAt": "yesterday", "homepage": "not a uri" });
+        let lenient = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        assert!(perform_validation(&lenient, &malformed, &ValidatorOptions::default()).is_ok());
+
+        let options = ValidatorOptions {
+            enforce_date_formats: true,
+            ..ValidatorOptions::default()
+        };
+        let strict = compile_schema(&schema, &options).unwrap();
+        let issues = perform_validation(&strict, &malformed, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "format_mismatch");
+        assert_eq!(issues[0].path, "/createdAt");
+
+        let valid = json!({ "createdAt": "2024-01-02T03:04:05Z", "homepage": "not a uri" });
+        assert!(perform_validation(&strict, &valid, &options).is_ok());
+
+        // Draft 7 asserts every format already, and must keep doing so.
+        let schema = json!({ "type": "string", "format": "email" });
+        let options = ValidatorOptions {
+            enforce_date_formats: true,
+            draft: Some(SchemaDraft::Draft7),
+            ..ValidatorOptions::default()
+        };
+        let draft7 = compile_schema(&schema, &options).unwrap();
+        let issues = perform_validation(&draft7, &json!("not an email"), &options).unwrap_err();
+        assert_eq!(issues[0].code, "format_mismatch");
+    }
+
+    #[test]
+    fn test_code_ids_are_fixed() {
+        assert_eq!(code_id("too_small"), 1);
+        assert_eq!(code_id("invalid_type"), 2);
+        assert_eq!(code_id("invalid_schema"), 36);
+        assert_eq!(code_id("not_a_real_code"), 0);
+        let ids: HashSet<u16> = CODE_IDS.iter().map(|(_, id)| *id).collect();
+        assert_eq!(ids.len(), CODE_IDS.len());
+        assert!(!ids.contains(&0));
+    }
+
+    #[test]
+    fn test_every_reported_code_has_an_id() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "a": { "type": "integer", "multipleOf": 2, "exclusiveMaximum": 0 },
+            "b": { "not": { "const": 1 }, "oneOf": [{ "type": "integer" }, { "minimum": 0 }] },
+            "c": { "type": "array", "contains": { "type": "string" }, "maxItems": 1 },
+            "d": { "propertyNames": { "maxLength": 1 }, "maxProperties": 1 },
+            "e": { "anyOf": [{ "type": "string" }], "const": "x" },
+            "f": false,
+          },
+          "additionalProperties": false,
+        });
+        let instance = json!({
+          "a": 3, "b": 1, "c": [1, 1], "d": { "long": 1, "key": 2 }, "e": 1, "f": 0, "g": 0,
+        });
+        let issues = compile_schema(&schema, &ValidatorOptions::default())
+            .and_then(|validator| {
+                perform_validation(&validator, &instance, &ValidatorOptions::default())
+            })
+            .unwrap_err();
+        assert!(issues.len() > 10);
+        for issue in issues {
+            assert_ne!(issue.code_id, 0, "no id for {}", issue.code);
+            assert_eq!(issue.code_id, code_id(&issue.code));
+        }
+    }
+
+    #[test]
+    fn test_validate_to_outcome() {
+        let options = ValidatorOptions::default();
+        let valid = validate_to_outcome::<String>(
+            Ok(json!({ "type": "string" })),
+            Ok(json!("a")),
+            &options,
+        );
+        assert!(valid.valid);
+        assert!(valid.issues.is_empty());
+
+        let invalid =
+            validate_to_outcome::<String>(Ok(json!({ "type": "string" })), Ok(json!(1)), &options);
+        assert!(!invalid.valid);
+        assert_eq!(invalid.issues[0].code, "invalid_type");
+
+        let unparseable = validate_to_outcome(
+            Ok(json!({ "type": "string" })),
+            serde_json::from_str::<Value>("{ not json"),
+            &options,
+        );
+        assert!(!unparseable.valid);
+        assert_eq!(unparseable.issues.len(), 1);
+        assert_eq!(unparseable.issues[0].code, "invalid_json");
+        assert_eq!(unparseable.issues[0].code_id, 38);
+    }
+
+    #[test]
+    fn test_write_context_flags_read_only_fields() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "id": { "type": "string", "readOnly": true },
+            "name": { "type": "string", "minLength": 2 },
+          },
+        });
+        let validator = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        let payload = json!({ "id": "abc", "name": "A" });
+        let options = ValidatorOptions {
+            context: Some(AccessContext::Write),
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_internal(&validator, &schema, &payload, &options).unwrap_err();
+        let codes: Vec<_> = issues.iter().map(|issue| issue.code.as_str()).collect();
+        assert_eq!(codes, ["too_short", "readonly_write"]);
+        assert_eq!(issues[1].path, "/id");
+        assert_eq!(issues[1].code_id, 39);
+
+        let options = ValidatorOptions {
+            context: Some(AccessContext::Read),
+            ..ValidatorOptions::default()
+        };
+        let payload = json!({ "id": "abc", "name": "Ada" });
+        assert!(validate_internal(&validator, &schema, &payload, &options).is_ok());
+    }
+
+    #[test]
+    fn test_missing_required_paths_at_every_level() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({
+          "id": "invalid-uuid",
+          "tags": [],
+          "profile": { "age": 20 },
+        });
+        let mut missing = collect_missing_required(&validator, &instance);
+        missing.sort();
+        assert_eq!(missing, ["/profile/fullName", "/status", "/username"]);
+    }
+
+    #[test]
+    fn test_errors_follow_document_key_order() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "zeta": { "type": "string" },
+            "alpha": { "type": "string" },
+            "mid": { "type": "string" },
+          },
+          "required": ["zulu", "bravo", "mike"],
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "mid": 3, "zeta": 1, "alpha": 2 });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        // Properties report in document order, not the order the schema lists them;
+        // missing ones follow `required`, since the document doesn't have them.
+        let messages: Vec<_> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                r#"3 is not of type "string""#,
+                r#"1 is not of type "string""#,
+                r#"2 is not of type "string""#,
+                r#""zulu" is a required property"#,
+                r#""bravo" is a required property"#,
+                r#""mike" is a required property"#,
+            ]
+        );
+        let paths: Vec<_> = issues
+            .iter()
+            .take(3)
+            .map(|issue| issue.path.as_str())
+            .collect();
+        assert_eq!(paths, ["/mid", "/zeta", "/alpha"]);
+    }
+
+    #[test]
+    fn test_decimal_multiple_of() {
+        let schema = json!({ "type": "number", "multipleOf": 0.1 });
+        let validator = Validator::new(&schema).unwrap();
+        let issues =
+            perform_validation(&validator, &json!(0.3), &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues[0].code, "not_a_multiple");
+
+        let options = ValidatorOptions {
+            decimal_multiple_of: true,
+            ..ValidatorOptions::default()
+        };
+        assert!(perform_validation(&validator, &json!(0.3), &options).is_ok());
+        assert!(perform_validation(&validator, &json!(12.7), &options).is_ok());
+        let issues = perform_validation(&validator, &json!(0.35), &options).unwrap_err();
+        assert_eq!(issues[0].code, "not_a_multiple");
+    }
+
+    #[test]
+    fn test_partial_validation_ignores_missing_fields() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let incomplete = json!({ "username": "testuser", "profile": { "age": 30 } });
+        assert!(validate_internal(&validator, &schema, &incomplete, &options).is_err());
+        assert!(validate_present(&validator, &schema, &incomplete, &options).is_ok());
+
+        let invalid = json!({ "username": "a" });
+        let issues = validate_present(&validator, &schema, &invalid, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "too_short");
+    }
+
+    #[test]
+    fn test_suggest_closest_enum_value() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "status": "inactiv" });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        let enum_issue = issues
+            .iter()
+            .find(|issue| issue.code == "enum_mismatch")
+            .unwrap();
+        assert_eq!(enum_issue.suggestion.as_deref(), Some("inactive"));
+        let instance = json!({ "status": "archived" });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        let enum_issue = issues
+            .iter()
+            .find(|issue| issue.code == "enum_mismatch")
+            .unwrap();
+        assert_eq!(enum_issue.suggestion.as_deref(), Some("active"));
+        assert!(issues
+            .iter()
+            .filter(|issue| issue.code != "enum_mismatch")
+            .all(|issue| issue.suggestion.is_none()));
+    }
+
+    #[test]
+    fn test_no_suggestion_for_non_string_values() {
+        let validator = Validator::new(&json!({ "enum": [1, 2, "one"] })).unwrap();
+        let issues =
+            perform_validation(&validator, &json!(3), &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues[0].suggestion, None);
+        let validator = Validator::new(&json!({ "const": "admin" })).unwrap();
+        let issues = perform_validation(&validator, &json!("admn"), &ValidatorOptions::default())
+            .unwrap_err();
+        assert_eq!(issues[0].suggestion.as_deref(), Some("admin"));
+    }
+
+    #[test]
+    fn test_normalization_satisfies_pattern_and_enum() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "code": { "type": "string", "pattern": "^[A-Z]{3}$", "x-trim": true },
+            "status": { "enum": ["active", "inactive"], "x-lowercase": true },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let mut instance = json!({ "code": " ABC ", "status": "ACTIVE" });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 2);
+
+        normalize::normalize(&schema, &mut instance);
+        assert_eq!(instance, json!({ "code": "ABC", "status": "active" }));
+        assert!(validate_internal(&validator, &schema, &instance, &options).is_ok());
+    }
+
+    #[test]
+    fn test_compile_report_warns_on_draft_mismatch() {
+        let schema = json!({
+          "$schema": "http://json-schema.org/draft-07/schema#",
+          "type": "array",
+          "prefixItems": [{ "type": "string" }],
+        });
+        let report = build_compile_report(&schema, &ValidatorOptions::default());
+        assert!(report.valid);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].schema_path, "/prefixItems");
+
+        let options = ValidatorOptions {
+            draft: Some(SchemaDraft::Draft202012),
+            ..ValidatorOptions::default()
+        };
+        assert!(build_compile_report(&schema, &options).warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_many_binary_round_trip() {
+        let schema = json!({ "type": "object", "required": ["id"] });
+        let instances = [json!({ "id": 1 }), json!({}), json!(3)];
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let bytes = validate_many_to_msgpack(&validator, &schema, &instances, &options);
+        let expected: Vec<Vec<ValidationIssue>> = instances
+            .iter()
+            .map(|instance| {
+                validate_internal(&validator, &schema, instance, &options)
+                    .err()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let decoded: Vec<Vec<ValidationIssue>> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, expected);
+        assert!(decoded[0].is_empty());
+        assert_eq!(decoded[1][0].code, "missing_property");
+        assert_eq!(decoded[2][0].code, "invalid_type");
+    }
+
+    #[test]
+    fn test_unevaluated_properties_across_all_of() {
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "type": "object",
+          "properties": {
+            "address": {
+              "allOf": [
+                { "properties": { "street": { "type": "string" } } },
+                { "properties": { "city": { "type": "string" } } },
+              ],
+              "unevaluatedProperties": false,
+            },
+          },
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let valid = json!({ "address": { "street": "Main", "city": "X" } });
+        assert!(validate_internal(&validator, &schema, &valid, &options).is_ok());
+
+        let instance = json!({ "address": { "street": "Main", "zip": "1", "kind": 2 } });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 2);
+        assert!(issues
+            .iter()
+            .all(|issue| issue.code == "unevaluated_properties"
+                && issue.schema_path == "/properties/address/unevaluatedProperties"));
+        assert_eq!(issues[0].path, "/address/zip");
+        assert_eq!(issues[1].path, "/address/kind");
+    }
+
+    #[test]
+    fn test_unevaluated_properties_split_masked_message_names_one_key() {
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "properties": { "a": {} },
+          "unevaluatedProperties": false,
+        });
+        let options = ValidatorOptions {
+            include_both_messages: true,
+            ..Default::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        let instance = json!({ "a": 1, "x": 2, "y": 3 });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 2);
+        for (issue, (own, other)) in issues.iter().zip([("'x'", "'y'"), ("'y'", "'x'")]) {
+            let masked = issue.masked_message.as_deref().unwrap();
+            assert!(masked.contains(own), "{masked}");
+            assert!(!masked.contains(other), "{masked}");
+        }
+    }
+
+    #[test]
+    fn test_unevaluated_items_after_prefix_items() {
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "allOf": [{ "prefixItems": [{ "type": "string" }] }],
+          "unevaluatedItems": false,
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        assert!(validate_internal(&validator, &schema, &json!(["a"]), &options).is_ok());
+        let issues =
+            validate_internal(&validator, &schema, &json!(["a", 1]), &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "unevaluated_items");
+        // Unexpected items are reported by value, so the path stays at the array.
+        assert_eq!(issues[0].path, "");
+    }
+
+    #[test]
+    fn test_error_codes_are_sorted_and_distinct() {
+        let schema = get_complex_schema();
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let instance = json!({
+          "id": "invalid-uuid",
+          "username": "a",
+          "profile": { "age": 20 },
+          "tags": [],
+        });
+        let codes = distinct_codes(&validator, &schema, &instance, &options);
+        assert_eq!(
+            codes.into_iter().collect::<Vec<_>>(),
+            [
+                "missing_property",
+                "pattern_mismatch",
+                "too_few_items",
+                "too_short"
+            ]
+        );
+        let valid = json!({ "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479", "username": "abc", "status": "active", "tags": ["a"] });
+        assert!(distinct_codes(&validator, &schema, &valid, &options).is_empty());
+    }
+
+    #[test]
+    fn test_validate_slice_of_nested_object() {
+        let schema = get_complex_schema();
+        let document = r#"{"id": "x", "profile": {"fullName": "Ann", "age": 12}, "tags": []}"#;
+        let start = document.find(r#"{"fullName"#).unwrap();
+        let end = document.find('}').unwrap() + 1;
+        let issues =
+            validate_slice_internal(&schema, document, start, end, "/profile").unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "too_small");
+        assert_eq!(issues[0].path, "/profile/age");
+        assert_eq!(
+            issues[0].schema_path,
+            "/properties/profile/properties/age/minimum"
+        );
+
+        let fixed = r#"{"profile": {"fullName": "Ann", "age": 30}}"#;
+        let start = fixed.find(r#"{"fullName"#).unwrap();
+        assert!(
+            validate_slice_internal(&schema, fixed, start, fixed.len() - 1, "/profile").is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validate_slice_reports_bad_ranges_as_invalid_json() {
+        let schema = get_complex_schema();
+        let issues =
+            validate_slice_internal(&schema, r#"{"tags": [1]}"#, 9, 40, "/tags").unwrap_err();
+        assert_eq!(issues[0].code, "invalid_json");
+        let issues =
+            validate_slice_internal(&schema, r#"{"tags": [1]}"#, 9, 11, "/tags").unwrap_err();
+        assert_eq!(issues[0].code, "invalid_json");
+    }
+
+    #[test]
+    fn test_validate_slice_counts_utf8_bytes() {
+        let schema = json!({
+          "properties": {
+            "café notes": { "type": "object", "properties": { "n": { "maximum": 1 } } },
+          },
+        });
+        let document = r#"{"emoji": "😀", "café notes": {"n": 5}}"#;
+        let start = document.find(r#"{"n""#).unwrap();
+        let end = start + r#"{"n": 5}"#.len();
+        let issues =
+            validate_slice_internal(&schema, document, start, end, "/café notes").unwrap_err();
+        assert_eq!(issues[0].code, "too_large");
+        assert_eq!(issues[0].path, "/café notes/n");
+        assert_eq!(
+            issues[0].schema_path,
+            "/properties/café notes/properties/n/maximum"
+        );
+
+        // Byte 12 falls inside the emoji's 4-byte encoding.
+        let issues = validate_slice_internal(&schema, document, 12, 20, "/emoji").unwrap_err();
+        assert_eq!(issues[0].code, "invalid_json");
+    }
+
+    #[test]
+    fn test_message_template_for_too_small() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "profile": { "fullName": "Ann", "age": 12 } });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        let issue = issues
+            .iter()
+            .find(|issue| issue.code == "too_small")
+            .unwrap();
+        assert_eq!(
+            issue.message_template,
+            "{value} is less than the minimum of {limit}"
+        );
+        assert_eq!(
+            issue.message_params,
+            *json!({ "value": 12, "limit": 18 }).as_object().unwrap()
+        );
+
+        let masked = ValidatorOptions {
+            mask_values: true,
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &instance, &masked).unwrap_err();
+        let issue = issues
+            .iter()
+            .find(|issue| issue.code == "too_small")
+            .unwrap();
+        assert_eq!(
+            issue.message_template,
+            "{value} is less than the minimum of {limit}"
+        );
+        assert_eq!(
+            issue.message_params,
+            *json!({ "limit": 18 }).as_object().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compile_from_uri_with_fake_fetcher() {
+        let mut fetch = |uri: &str| match uri {
+            "https://example.com/user.json" => Ok(json!({
+              "type": "object",
+              "properties": { "address": { "$ref": "address.json" } },
+            })),
+            "https://example.com/address.json" => Ok(json!({
+              "type": "object",
+              "required": ["city"],
+            })),
+            _ => Err(format!("404 for {uri}")),
+        };
+        let compiled = WasmValidator::compile_from_uri(
+            "https://example.com/user.json",
+            &mut fetch,
+            ValidatorOptions::default(),
+        )
+        .unwrap();
+        let validator = compiled.compiled().unwrap();
+        assert!(validator.is_valid(&json!({ "address": { "city": "Paris" } })));
+        let issues = perform_validation(validator, &json!({ "address": {} }), &compiled.options)
+            .unwrap_err();
+        assert_eq!(issues[0].code, "missing_property");
+        assert_eq!(issues[0].path, "/address");
+
+        let Err(issues) = WasmValidator::compile_from_uri(
+            "https://example.com/missing.json",
+            &mut fetch,
+            ValidatorOptions::default(),
+        ) else {
+            panic!("expected a fetch error");
+        };
+        assert_eq!(issues[0].code, "invalid_schema");
+        assert!(issues[0].message.contains("404"));
+    }
+
+    #[test]
+    fn test_record_refs_lists_resolved_documents() {
+        let mut fetch = |uri: &str| match uri {
+            "https://example.com/user.json" => Ok(json!({
+              "properties": { "address": { "$ref": "address.json" } },
+            })),
+            "https://example.com/address.json" => Ok(json!({ "required": ["city"] })),
+            _ => Err(format!("404 for {uri}")),
+        };
+        let options = ValidatorOptions {
+            record_refs: true,
+            ..Default::default()
+        };
+        let compiled =
+            WasmValidator::compile_from_uri("https://example.com/user.json", &mut fetch, options)
+                .unwrap();
+        assert_eq!(
+            compiled.resolved_ref_report(),
+            Some(RefReport {
+                used_refs: true,
+                refs: vec!["https://example.com/address.json".to_string()],
+            })
+        );
+
+        let local = WasmValidator::compile(
+            json!({ "$ref": "#/$defs/a", "$defs": { "a": {} } }),
+            ValidatorOptions {
+                record_refs: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            local.resolved_ref_report().map(|r| r.used_refs),
+            Some(false)
+        );
+        let unrecorded = WasmValidator::compile(json!({}), ValidatorOptions::default()).unwrap();
+        assert!(unrecorded.resolved_ref_report().is_none());
+    }
+
+    #[test]
+    fn test_validate_str_rejects_duplicate_keys() {
+        let schema = r#"{ "type": "object", "properties": { "id": { "type": "integer" } } }"#;
+        let instance = r#"{ "id": "oops", "id": 7 }"#;
+        assert!(validate_text(schema, instance, &ValidatorOptions::default()).is_ok());
+
+        let options = ValidatorOptions {
+            reject_duplicate_keys: true,
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_text(schema, instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "invalid_json");
+        assert!(issues[0].message.contains("duplicate key `id`"));
+    }
+
+    #[test]
+    fn test_profile_reports_timing() {
+        let schema = Ok::<_, String>(get_complex_schema());
+        let instance = Ok(json!({ "id": "invalid-uuid", "username": "a", "tags": [] }));
+        let outcome = validate_to_outcome(
+            schema.clone(),
+            instance.clone(),
+            &ValidatorOptions::default(),
+        );
+        assert!(outcome.timing.is_none());
+
+        let options = ValidatorOptions {
+            profile: true,
+            ..ValidatorOptions::default()
+        };
+        let outcome = validate_to_outcome(schema, instance, &options);
+        assert!(!outcome.valid);
+        let timing = outcome.timing.unwrap();
+        assert!(timing.compile_us >= 0.0);
+        assert!(timing.validate_us >= 0.0);
+    }
+
+    #[test]
+    fn test_custom_keyword_checks_sibling_divisibility() {
+        let divisible_by_field: custom::KeywordCheck =
+            std::sync::Arc::new(|instance: &Value, value: &Value| {
+                let (Some(field), Some(divisor)) = (
+                    value.get("field").and_then(Value::as_str),
+                    value.get("by").and_then(Value::as_str),
+                ) else {
+                    return Ok(());
+                };
+                let (Some(amount), Some(step)) = (
+                    instance.get(field).and_then(Value::as_i64),
+                    instance.get(divisor).and_then(Value::as_i64),
+                ) else {
+                    return Ok(());
+                };
+                if step != 0 && amount % step == 0 {
+                    Ok(())
+                } else {
+                    Err(Some(format!("{field} must be divisible by {divisor}")))
+                }
+            });
+        let options = ValidatorOptions {
+            keywords: vec![custom::CustomKeyword {
+                name: "divisibleByField".to_string(),
+                check: divisible_by_field,
+            }],
+            ..ValidatorOptions::default()
+        };
+        let schema = json!({
+          "properties": {
+            "order": { "divisibleByField": { "field": "quantity", "by": "packSize" } },
+          },
+        });
+        let validator = compile_schema(&schema, &options).unwrap();
+        let valid = json!({ "order": { "quantity": 12, "packSize": 4 } });
+        assert!(perform_validation(&validator, &valid, &options).is_ok());
+
+        let invalid = json!({ "order": { "quantity": 10, "packSize": 4 } });
+        let issues = perform_validation(&validator, &invalid, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "custom_error");
+        assert_eq!(issues[0].path, "/order");
+        assert_eq!(issues[0].message, "quantity must be divisible by packSize");
+        assert_eq!(issues[0].schema_path, "/properties/order/divisibleByField");
+
+        let secret: custom::KeywordCheck = std::sync::Arc::new(|_: &Value, _: &Value| Err(None));
+        let options = ValidatorOptions {
+            mask_values: true,
+            keywords: vec![custom::CustomKeyword {
+                name: "rejectAll".to_string(),
+                check: secret,
+            }],
+            ..ValidatorOptions::default()
+        };
+        let schema = json!({ "rejectAll": true });
+        let validator = compile_schema(&schema, &options).unwrap();
+        let issues = perform_validation(&validator, &json!("hunter2"), &options).unwrap_err();
+        assert_eq!(
+            issues[0].message,
+            "value is not valid under the `rejectAll` keyword"
+        );
+    }
+
+    #[test]
+    fn test_issue_ids_stable_across_values() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let first =
+            perform_validation(&validator, &json!({ "username": "a" }), &options).unwrap_err();
+        let second =
+            perform_validation(&validator, &json!({ "username": "ab" }), &options).unwrap_err();
+        let too_short = |issues: &[ValidationIssue]| {
+            issues
+                .iter()
+                .find(|issue| issue.code == "too_short")
+                .unwrap()
+                .clone()
+        };
+        assert_ne!(too_short(&first).message, too_short(&second).message);
+        assert_eq!(too_short(&first).id, too_short(&second).id);
+        assert_eq!(too_short(&first).id.len(), 16);
+
+        let ids: HashSet<_> = first.iter().map(|issue| &issue.id).collect();
+        assert_eq!(ids.len(), first.len());
+    }
+
+    #[test]
+    fn test_collapse_arrays_summarizes_repeated_item_errors() {
+        let schema = json!({
+          "properties": {
+            "scores": { "type": "array", "items": { "type": "integer", "maximum": 10 } },
+          },
+        });
+        let instance = json!({ "scores": [1, "a", 20, "b", 3, "c", 11] });
+        let validator = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        let per_item =
+            validate_internal(&validator, &schema, &instance, &ValidatorOptions::default())
+                .unwrap_err();
+        assert_eq!(per_item.len(), 5);
+
+        let options = ValidatorOptions {
+            collapse_arrays: true,
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 2);
+        let invalid_type = issues
+            .iter()
+            .find(|issue| issue.code == "invalid_type")
+            .unwrap();
+        assert_eq!(invalid_type.path, "/scores");
+        assert_eq!(invalid_type.count, Some(3));
+        assert_eq!(invalid_type.indices, Some(vec![1, 3, 5]));
+        assert!(invalid_type.message.starts_with("3 of 7 items are invalid"));
+        let too_large = issues
+            .iter()
+            .find(|issue| issue.code == "too_large")
+            .unwrap();
+        assert_eq!(too_large.indices, Some(vec![2, 6]));
+    }
+
+    #[test]
+    fn test_operation_limit_returns_partial_results() {
+        let schema = json!({ "type": "array", "items": { "type": "string" } });
+        let instance = Value::Array((0..1000).map(Value::from).collect());
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions {
+            operation_limit: Some(10),
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 11);
+        assert!(issues[..10]
+            .iter()
+            .all(|issue| issue.code == "invalid_type"));
+        assert_eq!(issues[10].code, "operation_limit_exceeded");
+        assert_eq!(issues[10].code_id, 41);
+
+        let options = ValidatorOptions {
+            operation_limit: Some(1000),
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 1000);
+
+        let options = ValidatorOptions {
+            operation_limit: Some(10),
+            only_codes: Some(vec!["too_small".to_string()]),
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "operation_limit_exceeded");
+        assert_eq!(
+            issues[0].message,
+            "Validation stopped after the limit of 10 errors"
+        );
+    }
+
+    #[test]
+    fn test_streaming_validator_push_and_reset() {
+        let schema =
+            json!({ "type": "object", "properties": { "n": { "type": "integer", "minimum": 0 } } });
+        let options = ValidatorOptions::default();
+        let mut stream = StreamingValidator {
+            validator: compile_schema(&schema, &options).unwrap(),
+            schema,
+            options,
+            pushed: 0,
+            invalid: 0,
+        };
+        assert!(stream.push_value(&json!({ "n": 1 }), None).valid);
+        let outcome = stream.push_value(&json!({ "n": -5 }), None);
+        assert!(!outcome.valid);
+        assert!(outcome.issues[0].message.contains("-5"));
+        let masked = stream.push_value(&json!({ "n": -5 }), Some(true));
+        assert!(!masked.issues[0].message.contains("-5"));
+        assert!(stream.push_value(&json!({}), None).valid);
+        assert_eq!((stream.pushed(), stream.invalid()), (4, 2));
+
+        stream.reset();
+        assert_eq!((stream.pushed(), stream.invalid()), (0, 0));
+        assert!(stream.push_value(&json!({ "n": 2 }), None).valid);
+        assert_eq!(stream.pushed(), 1);
+    }
+
+    #[test]
+    fn test_absolute_keyword_location_names_referenced_document() {
+        let address = json!({
+          "$id": "https://example.com/address.json",
+          "$defs": { "zip": { "type": "string", "minLength": 5 } },
+          "properties": { "zip": { "$ref": "#/$defs/zip" }, "city": { "type": "string" } },
+        });
+        let schema = json!({
+          "$id": "https://example.com/root.json",
+          "properties": {
+            "home": { "$ref": "address.json" },
+            "zip": { "$ref": "address.json#/$defs/zip" },
+            "n": { "minimum": 3 },
+          },
+        });
+        let mut retriever = resources::MapRetriever::default();
+        retriever.insert("https://example.com/address.json", address);
+        let options = ValidatorOptions::default();
+        let validator =
+            compile_schema_with(jsonschema::options(), retriever, &schema, &options).unwrap();
+        let instance = json!({ "home": { "zip": "1", "city": 7 }, "zip": "2", "n": 1 });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        let location = |path: &str| {
+            issues
+                .iter()
+                .find(|issue| issue.path == path)
+                .unwrap()
+                .absolute_keyword_location
+                .clone()
+        };
+        assert_eq!(
+            location("/zip").as_deref(),
+            Some("https://example.com/address.json#/$defs/zip/minLength")
+        );
+        assert_eq!(
+            location("/home/city").as_deref(),
+            Some("https://example.com/address.json#/properties/city/type")
+        );
+        assert_eq!(location("/n"), None);
+        // Crosses two references, so only the document is certain.
+        assert_eq!(
+            location("/home/zip").as_deref(),
+            Some("https://example.com/address.json")
+        );
+    }
+
+    #[test]
+    fn test_check_schema_bounded_rejects_deep_and_wide_schemas() {
+        let mut deep = json!({ "type": "string" });
+        for _ in 0..200 {
+            deep = json!({ "properties": { "child": deep } });
+        }
+        // Never compiled: compiling this deep a schema could overflow the stack.
+        let issues = check_schema_within(&deep, Some(64), None).unwrap_err();
+        assert_eq!(issues[0].code, "schema_too_large");
+        assert_eq!(issues[0].code_id, 42);
+
+        let properties: serde_json::Map<String, Value> = (0..5000)
+            .map(|index| (format!("field{index}"), json!({ "type": "string" })))
+            .collect();
+        let wide = json!({ "type": "object", "properties": properties });
+        assert!(check_schema_within(&wide, Some(64), Some(20_000)).is_ok());
+        let issues = check_schema_within(&wide, Some(64), Some(1000)).unwrap_err();
+        assert!(issues[0].message.contains("1000 keywords"));
+
+        let issues = check_schema_within(&json!({ "type": 12 }), Some(8), Some(8)).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_schema");
+    }
+
+    #[test]
+    fn test_apply_defaults_satisfies_required() {
+        let schema = json!({
+          "type": "object",
+          "required": ["role", "profile"],
+          "properties": {
+            "role": { "enum": ["admin", "user"], "default": "user" },
+            "profile": {
+              "type": "object",
+              "default": {},
+              "required": ["locale"],
+              "properties": { "locale": { "type": "string", "default": "en" } },
+            },
+          },
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let outcome = validate_with_defaults(&validator, &schema, json!({}), &options);
+        assert!(outcome.valid, "{:?}", outcome.issues);
+        assert_eq!(
+            outcome.value,
+            json!({ "role": "user", "profile": { "locale": "en" } })
+        );
+
+        let outcome =
+            validate_with_defaults(&validator, &schema, json!({ "role": "root" }), &options);
+        assert!(!outcome.valid);
+        assert_eq!(outcome.issues[0].code, "enum_mismatch");
+        assert_eq!(outcome.value["role"], "root");
+    }
+
+    #[test]
+    fn test_validate_examples_reports_invalid_example() {
+        let schema = json!({
+          "$defs": { "age": { "type": "integer", "minimum": 0 } },
+          "properties": {
+            "age": { "$ref": "#/$defs/age", "examples": [42, -1] },
+            "tags": { "type": "array", "items": { "type": "string", "examples": ["a"] } },
+          },
+          "examples": [{ "age": 3 }, { "age": "three" }],
+        });
+        let failures = check_examples(&schema).unwrap();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].schema_path, "/examples/1");
+        assert_eq!(failures[0].issues[0].path, "/age");
+        assert_eq!(failures[1].schema_path, "/properties/age/examples/1");
+        assert_eq!(failures[1].issues[0].code, "too_small");
+        assert_eq!(
+            failures[1].issues[0].schema_path,
+            "/properties/age/$ref/minimum"
+        );
+    }
+
+    #[test]
+    fn test_schema_at_profile_age_and_array_item() {
+        let schema = get_complex_schema();
+        let instance = json!({ "profile": { "age": 3 }, "tags": ["a", "b"] });
+        assert_eq!(
+            effective_subschema(&schema, &instance, "/profile/age"),
+            Some(json!({ "type": "number", "minimum": 18 }))
+        );
+        assert_eq!(
+            effective_subschema(&schema, &instance, "/tags/1"),
+            Some(json!({ "type": "string" }))
+        );
+        assert_eq!(effective_subschema(&schema, &instance, "/unknown"), None);
+    }
+
+    #[test]
+    fn test_schema_at_merges_refs_and_all_of() {
+        let schema = json!({
+          "$defs": { "base": { "type": "object", "required": ["id"], "properties": { "id": {} } } },
+          "additionalProperties": {
+            "$ref": "#/$defs/base",
+            "allOf": [{ "required": ["name"], "properties": { "name": { "type": "string" } } }],
+          },
+        });
+        let instance = json!({ "first": { "id": 1 } });
+        assert_eq!(
+            effective_subschema(&schema, &instance, "/first"),
+            Some(json!({
+              "type": "object",
+              "required": ["id", "name"],
+              "properties": { "id": {}, "name": { "type": "string" } },
+            }))
+        );
+    }
+
+    #[test]
+    fn test_compile_returns_validator_or_issues() {
+        let compiled =
+            WasmValidator::compile(get_complex_schema(), ValidatorOptions::default()).unwrap();
+        assert!(compiled.compiled().is_ok());
+
+        let Err(issues) =
+            WasmValidator::compile(json!({ "minLength": "three" }), ValidatorOptions::default())
+        else {
+            panic!("expected compilation to fail");
+        };
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "invalid_schema");
+    }
+
+    #[test]
+    fn test_validate_csv_rows_reports_line_numbers() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer", "minimum": 0, "maximum": 150 },
+          },
+          "required": ["name", "age"],
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let text = "name,age\n\"Lovelace, Ada\",36\nMethuselah,969\nBob\n";
+        let rows = validate_csv_rows(&validator, &schema, text, &options).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].valid);
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].issues.len(), 1);
+        assert_eq!(rows[1].issues[0].path, "/age");
+        assert_eq!(rows[1].issues[0].code, "too_large");
+        assert_eq!(rows[2].issues[0].code, "invalid_csv");
+
+        let issues = validate_csv_rows(&validator, &schema, "name\n\"Ada", &options).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_csv");
+
+        let options = ValidatorOptions {
+            base_path: Some("/rows".to_string()),
+            max_message_len: Some(10),
+            ..ValidatorOptions::default()
+        };
+        let rows = validate_csv_rows(&validator, &schema, text, &options).unwrap();
+        assert_eq!(rows[1].issues[0].path, "/rows/age");
+        assert_eq!(rows[2].issues[0].path, "/rows");
+        assert_eq!(rows[2].issues[0].message.chars().count(), 10);
+    }
+
+    #[test]
+    fn test_paths_by_schema_path() {
+        let schema = get_complex_schema();
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 12 },
+          "tags": ["ok", 1],
+        });
+
+        let grouped = paths_by_schema_path(&validator, &schema, &instance, &options);
+        assert_eq!(
+            grouped["/properties/profile/properties/age/minimum"],
+            vec!["/profile/age"]
+        );
+        assert!(grouped
+            .keys()
+            .any(|path| path.starts_with("/properties/tags")));
+    }
+
+    #[test]
+    fn test_ignore_and_only_codes() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
         let very_invalid_instance = json!({
           "id": "invalid-uuid",
           "username": "a",
           "profile": { "age": 20 },
           "tags": [],
         });
-        let issues = perform_validation(&validator, &very_invalid_instance, false).unwrap_err();
-        assert_eq!(issues.len(), 5);
-        let codes: Vec<_> = issues.iter().map(|issue| &issue.code).collect();
-        assert!(codes.contains(&&"pattern_mismatch".to_string()));
-        assert!(codes.contains(&&"too_short".to_string()));
-        assert!(codes.contains(&&"missing_property".to_string()));
-        assert!(codes.contains(&&"too_few_items".to_string()));
+        let codes = |options: ValidatorOptions| -> Vec<String> {
+            validate_internal(&validator, &schema, &very_invalid_instance, &options)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|issue| issue.code)
+                .collect()
+        };
+
+        let ignored = codes(ValidatorOptions {
+            ignore_codes: vec!["missing_property".to_string(), "too_short".to_string()],
+            ..ValidatorOptions::default()
+        });
+        assert_eq!(ignored.len(), 2);
+        assert!(ignored.contains(&"pattern_mismatch".to_string()));
+        assert!(ignored.contains(&"too_few_items".to_string()));
+
+        let only = codes(ValidatorOptions {
+            only_codes: Some(vec!["missing_property".to_string()]),
+            ..ValidatorOptions::default()
+        });
+        assert_eq!(only, vec!["missing_property", "missing_property"]);
+
+        let none = codes(ValidatorOptions {
+            only_codes: Some(vec!["format_mismatch".to_string()]),
+            ..ValidatorOptions::default()
+        });
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_shortfall_and_excess() {
+        let schema = json!({
+          "properties": {
+            "tags": { "minItems": 3 },
+            "meta": { "maxProperties": 1 },
+            "code": { "minLength": 4, "maxLength": 2 },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "tags": ["a"], "meta": { "a": 1, "b": 2, "c": 3 }, "code": "xyz" });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        let gap = |path: &str, code: &str| {
+            let issue = issues
+                .iter()
+                .find(|issue| issue.path == path && issue.code == code)
+                .unwrap();
+            (issue.shortfall, issue.excess)
+        };
+        assert_eq!(gap("/tags", "too_few_items"), (Some(2), None));
+        assert_eq!(gap("/meta", "too_many_properties"), (None, Some(2)));
+        assert_eq!(gap("/code", "too_short"), (Some(1), None));
+        assert_eq!(gap("/code", "too_long"), (None, Some(1)));
+    }
+
+    #[test]
+    fn test_matched_branches_for_ambiguous_one_of() {
+        let schema = json!({
+          "properties": {
+            "id": {
+              "oneOf": [
+                { "type": "integer" },
+                { "type": "string" },
+                { "minimum": 0 },
+              ],
+            },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions {
+            base_path: Some("/body".to_string()),
+            ..ValidatorOptions::default()
+        };
+        let issues =
+            validate_internal(&validator, &schema, &json!({ "id": 7 }), &options).unwrap_err();
+        assert_eq!(issues[0].code, "one_of_multiple_matches");
+        assert_eq!(issues[0].path, "/body/id");
+        assert_eq!(issues[0].matched_branches, Some(vec![0, 2]));
+    }
+
+    #[test]
+    fn test_problem_details() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "id": "invalid-uuid", "username": "a", "tags": [] });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        let issues: Vec<IssueMessage> =
+            serde_json::from_value(serde_json::to_value(&issues).unwrap()).unwrap();
+
+        let problem = problem_details(&issues, 422);
+        assert_eq!(
+            problem["type"],
+            "urn:wasm-validator:problem:validation-failed"
+        );
+        assert_eq!(problem["title"], "Validation failed");
+        assert_eq!(problem["status"], 422);
+        assert_eq!(problem["errors"]["/username"].as_array().unwrap().len(), 1);
+        assert_eq!(problem["errors"][""].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_bundled_schema_validates_offline() {
+        let schema = json!({
+          "$id": "https://example.com/order.json",
+          "type": "object",
+          "properties": { "shipTo": { "$ref": "address.json#/$defs/us" } },
+        });
+        let resources = HashMap::from([(
+            "https://example.com/address.json".to_string(),
+            json!({
+              "$defs": {
+                "us": {
+                  "type": "object",
+                  "properties": { "zip": { "type": "string", "pattern": "^[0-9]{5}$" } },
+                },
+              },
+            }),
+        )]);
+        assert!(compile_schema(&schema, &ValidatorOptions::default()).is_err());
+
+        let bundled = bundle_with_resources(&schema, &resources).unwrap();
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&bundled, &options).unwrap();
+        let instance = json!({ "shipTo": { "zip": "1234" } });
+        let issues = validate_internal(&validator, &bundled, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "/shipTo/zip");
+        assert_eq!(
+            issues[0].schema_path,
+            "/properties/shipTo/$ref/properties/zip/pattern"
+        );
+
+        let issues = bundle_with_resources(&schema, &HashMap::new()).unwrap_err();
+        assert_eq!(issues[0].code, "schema_reference_error");
+    }
+
+    #[test]
+    fn test_masked_messages_with_multibyte_values() {
+        // Masking substitutes a placeholder rather than truncating the value, so
+        // there is no byte boundary to split; this guards against regressions.
+        let schema = json!({
+          "properties": {
+            "a": { "maxLength": 3 },
+            "b": { "enum": ["x"] },
+            "c": { "pattern": "^[a-z]+$" },
+            "d": { "type": "integer" },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let value = "👩‍👩‍👧‍👦🇯🇵é漢字".repeat(40);
+        let instance = json!({ "a": value, "b": value, "c": value, "d": value });
+        let masked = ValidatorOptions {
+            mask_values: true,
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &instance, &masked).unwrap_err();
+        assert_eq!(issues.len(), 4);
+        for issue in &issues {
+            assert!(!issue.message.contains('\u{FFFD}'), "{}", issue.message);
+            assert!(!issue.message.contains("漢"), "{}", issue.message);
+            assert!(std::str::from_utf8(issue.message.as_bytes()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_refinement_flags_newly_invalid_samples() {
+        let old = json!({ "properties": { "age": { "type": "integer", "minimum": 0 } } });
+        let new = json!({ "properties": { "age": { "type": "integer", "minimum": 18 } } });
+        let samples = [
+            json!({ "age": 30 }),
+            json!({ "age": 12 }),
+            json!({ "age": -1 }),
+        ];
+
+        let report = check_refinement(&old, &new, &samples).unwrap();
+        assert!(!report.compatible);
+        assert_eq!(report.breaking.len(), 1);
+        assert_eq!(report.breaking[0].index, 1);
+        assert_eq!(report.breaking[0].issues[0].path, "/age");
+
+        let report = check_refinement(&new, &old, &samples).unwrap();
+        assert!(report.compatible);
+    }
+
+    #[test]
+    fn test_dot_path_format() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 17 },
+          "tags": [1],
+        });
+        let pointer =
+            validate_internal(&validator, &schema, &instance, &ValidatorOptions::default())
+                .unwrap_err();
+        let dot = validate_internal(
+            &validator,
+            &schema,
+            &instance,
+            &ValidatorOptions {
+                path_format: PathFormat::Dot,
+                ..ValidatorOptions::default()
+            },
+        )
+        .unwrap_err();
+        let paths: Vec<_> = dot
+            .iter()
+            .map(|issue| issue.display_path.as_deref().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["profile.age", "tags[0]"]);
+        assert_eq!(dot[0].path, pointer[0].path);
+        assert_eq!(dot[0].id, pointer[0].id);
+    }
+
+    #[test]
+    fn test_compile_cached_counts_hits_and_clears() {
+        // A private cache, since other tests go through the global one in parallel.
+        let cache = Mutex::new(cache::SchemaCache::new(cache::CAPACITY));
+        let schema = json!({ "type": "string", "minLength": 2 });
+        let options = ValidatorOptions::default();
+        let first = compile_cached_in(&cache, &schema, &options).unwrap();
+        let second = compile_cached_in(&cache, &schema, &options).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        let lax = ValidatorOptions {
+            lax_email: true,
+            ..ValidatorOptions::default()
+        };
+        compile_cached_in(&cache, &schema, &lax).unwrap();
+        assert!(compile_cached_in(&cache, &json!({ "type": 1 }), &options).is_err());
+        assert_eq!(
+            lock_cache(&cache).stats(),
+            cache::CacheStats {
+                hits: 1,
+                misses: 3,
+                size: 2
+            }
+        );
+
+        lock_cache(&cache).clear();
+        assert_eq!(lock_cache(&cache).stats(), cache::CacheStats::default());
+    }
+
+    #[test]
+    fn test_pattern_on_pattern_issues() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "id": "invalid-uuid", "username": "a", "tags": [] });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        for issue in &issues {
+            if issue.code == "pattern_mismatch" {
+                assert_eq!(
+                    issue.pattern.as_deref(),
+                    Some("^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$")
+                );
+            } else {
+                assert_eq!(issue.pattern, None);
+            }
+        }
+
+        let schema = json!({ "propertyNames": { "pattern": "^[a-z]+$" } });
+        let validator = Validator::new(&schema).unwrap();
+        let issues = perform_validation(
+            &validator,
+            &json!({ "Bad": 1 }),
+            &ValidatorOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues[0].code, "invalid_property_name");
+        assert_eq!(issues[0].pattern.as_deref(), Some("^[a-z]+$"));
+    }
+
+    #[test]
+    fn test_closed_flags_undeclared_nested_properties() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 30, "nickname": "T" },
+          "tags": ["testing"],
+        });
+        assert!(
+            validate_internal(&validator, &schema, &instance, &ValidatorOptions::default()).is_ok()
+        );
+
+        let closed = ValidatorOptions {
+            closed: true,
+            base_path: Some("/body".to_string()),
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_internal(&validator, &schema, &instance, &closed).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "additional_properties");
+        assert_eq!(issues[0].path, "/body/profile");
+        assert_eq!(
+            issues[0].schema_path,
+            "/properties/profile/additionalProperties"
+        );
+        assert!(
+            issues[0].message.contains("nickname"),
+            "{}",
+            issues[0].message
+        );
+    }
+
+    #[test]
+    fn test_suggest_fixes_patch() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "role": { "type": "string", "default": "member" },
+            "name": { "type": "string" },
+            "seats": { "type": "integer", "minimum": 1, "maximum": 10 },
+            "profile": {
+              "properties": { "age": { "type": "integer", "minimum": 18 } },
+            },
+          },
+          "required": ["role", "name"],
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "seats": "12", "profile": { "age": 16 } });
+        let patch = fixes::suggest_fixes(&schema, &validator, &instance);
+        assert_eq!(
+            patch,
+            vec![
+                json!({ "op": "replace", "path": "/seats", "value": 12 }),
+                json!({ "op": "replace", "path": "/profile/age", "value": 18 }),
+                json!({ "op": "add", "path": "/role", "value": "member" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dependency_codes() {
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "properties": {
+            "order": {
+              "dependentRequired": { "credit_card": ["billing_address"] },
+              "dependentSchemas": { "coupon": { "required": ["coupon_code"] } },
+            },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "order": { "credit_card": "4111", "coupon": true } });
+        let issues =
+            validate_internal(&validator, &schema, &instance, &ValidatorOptions::default())
+                .unwrap_err();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].code, "dependent_required");
+        assert_eq!(issues[0].path, "/order");
+        assert_eq!(
+            issues[0].message,
+            "\"billing_address\" is required when \"credit_card\" is present"
+        );
+        assert_eq!(issues[0].message_params["trigger"], "credit_card");
+        assert_eq!(issues[1].code, "dependent_schema");
+        assert_eq!(
+            issues[1].message,
+            "\"coupon_code\" is required when \"coupon\" is present"
+        );
+
+        let draft7 = json!({ "dependencies": { "credit_card": ["billing_address"] } });
+        let validator = Validator::new(&draft7).unwrap();
+        let issues = validate_internal(
+            &validator,
+            &draft7,
+            &json!({ "credit_card": "4111" }),
+            &ValidatorOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues[0].code, "dependent_required");
+    }
+
+    #[test]
+    fn test_numeric_strings() {
+        let schema = json!({
+          "properties": {
+            "age": { "type": "number", "minimum": 18 },
+            "count": { "type": "integer" },
+            "zip": { "type": ["string", "integer"], "maxLength": 5 },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions {
+            numeric_strings: true,
+            ..ValidatorOptions::default()
+        };
+        let validate = |instance: Value| {
+            validate_internal(&validator, &schema, &instance, &options)
+                .err()
+                .unwrap_or_default()
+        };
+        assert!(validate(json!({ "age": "18", "count": " 3 ", "zip": "01234" })).is_empty());
+
+        let issues = validate(json!({ "age": "abc", "count": "2.5" }));
+        let codes: Vec<_> = issues.iter().map(|issue| issue.code.as_str()).collect();
+        assert_eq!(codes, vec!["invalid_type", "invalid_type"]);
+        assert_eq!(validate(json!({ "age": "17" }))[0].code, "too_small");
+        assert_eq!(
+            validate_internal(
+                &validator,
+                &schema,
+                &json!({ "age": "18" }),
+                &ValidatorOptions::default()
+            )
+            .unwrap_err()[0]
+                .code,
+            "invalid_type"
+        );
+    }
+
+    fn entry<'a>(entries: &'a EntryIssues, key: &str) -> &'a [ValidationIssue] {
+        entries
+            .0
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, issues)| issues.as_slice())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_entries_per_key() {
+        let schema = json!({
+          "type": "object",
+          "minProperties": 5,
+          "patternProperties": { "^x-": { "type": "string" } },
+          "additionalProperties": {
+            "type": "object",
+            "properties": { "price": { "type": "number" } },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let instance = json!({
+          "apple": { "price": 1.5 },
+          "pear": { "price": "cheap" },
+          "x-note": 3,
+        });
+        let entries = validate_entries(&validator, &schema, &instance, &options).unwrap();
+        let keys: Vec<_> = entries.0.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["apple", "pear", "x-note"]);
+        assert!(entry(&entries, "apple").is_empty());
+        assert_eq!(entry(&entries, "pear")[0].path, "/pear/price");
+        assert_eq!(entry(&entries, "pear")[0].code, "invalid_type");
+        assert_eq!(entry(&entries, "x-note")[0].code, "invalid_type");
+        let serialized = serde_json::to_value(&entries).unwrap();
+        let keys: Vec<_> = serialized.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["apple", "pear", "x-note"]);
+
+        let issues = validate_entries(&validator, &schema, &json!([1]), &options).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_type");
+
+        let dot = ValidatorOptions {
+            path_format: PathFormat::Dot,
+            ..ValidatorOptions::default()
+        };
+        let entries = validate_entries(&validator, &schema, &instance, &dot).unwrap();
+        assert_eq!(entry(&entries, "pear")[0].path, "/pear/price");
+        assert_eq!(
+            entry(&entries, "pear")[0].display_path.as_deref(),
+            Some("pear.price")
+        );
+        assert_eq!(entry(&entries, "x-note")[0].code, "invalid_type");
+    }
+
+    #[test]
+    fn test_issue_depth() {
+        let schema = json!({
+          "required": ["name"],
+          "properties": {
+            "age": { "type": "integer" },
+            "profile": { "properties": { "age": { "type": "integer" } } },
+          },
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "age": "x", "profile": { "age": "y" } });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        let depths: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.path.as_str(), issue.depth))
+            .collect();
+        assert_eq!(depths, vec![("", 0), ("/age", 1), ("/profile/age", 2)]);
+    }
+
+    #[test]
+    fn test_legacy_definitions_compile_as_draft4() {
+        let schema = json!({
+          "id": "http://example.com/legacy.json",
+          "definitions": {
+            "price": { "type": "number", "minimum": 0, "exclusiveMinimum": true },
+            "item": {
+              "type": "object",
+              "properties": { "price": { "$ref": "#/definitions/price" } },
+              "required": ["price"],
+            },
+          },
+          "type": "object",
+          "properties": { "items": { "type": "array", "items": { "$ref": "#/definitions/item" } } },
+        });
+        assert!(compile_schema(&schema, &ValidatorOptions::default()).is_err());
+
+        let options = ValidatorOptions {
+            legacy_definitions: true,
+            ..ValidatorOptions::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        let instance = json!({ "items": [{ "price": 5 }, { "price": 0 }] });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "/items/1/price");
+        assert_eq!(issues[0].code, "exclusive_min");
+    }
+
+    #[test]
+    fn test_use_schema_messages_per_keyword() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "age": { "$ref": "#/$defs/age" },
+            "name": { "type": "string", "minLength": 1 },
+          },
+          "required": ["name"],
+          "errorMessage": { "required": "Tell us your name" },
+          "$defs": {
+            "age": { "type": "integer", "minimum": 18, "errorMessage": { "minimum": "Too young" } },
+          },
+        });
+        let options = ValidatorOptions {
+            use_schema_messages: true,
+            ..ValidatorOptions::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        let messages = |instance: Value| -> Vec<(String, String)> {
+            validate_internal(&validator, &schema, &instance, &options)
+                .unwrap_err()
+                .into_iter()
+                .map(|issue| (issue.path, issue.message))
+                .collect()
+        };
+        assert_eq!(
+            messages(json!({ "age": 12 })),
+            vec![
+                ("/age".to_string(), "Too young".to_string()),
+                ("".to_string(), "Tell us your name".to_string()),
+            ]
+        );
+        // Keywords without an entry keep the default message.
+        assert_eq!(
+            messages(json!({ "age": 20.5, "name": "" })),
+            vec![
+                (
+                    "/age".to_string(),
+                    "20.5 is not of type \"integer\"".to_string()
+                ),
+                (
+                    "/name".to_string(),
+                    "\"\" is shorter than 1 character".to_string()
+                ),
+            ]
+        );
     }
 
     #[test]
-    fn test_fail_with_masked_values() {
+    fn test_schema_properties_on_complex_schema() {
+        let listed: Vec<_> = properties::schema_properties(&get_complex_schema())
+            .into_iter()
+            .map(|property| (property.path, property.type_, property.required))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("/id".to_string(), Some(json!("string")), true),
+                ("/username".to_string(), Some(json!("string")), true),
+                ("/status".to_string(), Some(json!("string")), true),
+                ("/profile".to_string(), Some(json!("object")), false),
+                ("/profile/fullName".to_string(), Some(json!("string")), true),
+                ("/profile/age".to_string(), Some(json!("number")), false),
+                ("/tags".to_string(), Some(json!("array")), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_json5_config() {
+        let schema = json!({
+          "type": "object",
+          "properties": { "port": { "type": "integer", "maximum": 65535 }, "hosts": { "type": "array" } },
+          "required": ["port"],
+        });
+        let options = ValidatorOptions::default();
+        let config = r#"{
+          // Port the dev server listens on.
+          port: 8080,
+          hosts: ['localhost', "127.0.0.1",],
+        }"#;
+        assert!(validate_json5_text(&schema, config, &options).is_ok());
+
+        let issues = validate_json5_text(&schema, "{ port: 70000 }", &options).unwrap_err();
+        assert_eq!(issues[0].code, "too_large");
+
+        let issues = validate_json5_text(&schema, "{\n  port: ,\n}", &options).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_json");
+        assert!(
+            issues[0].message.ends_with("at line 2 column 9"),
+            "{}",
+            issues[0].message
+        );
+    }
+
+    #[test]
+    fn test_doc_url_names_failed_keyword() {
+        let schema = get_complex_schema();
+        let options = ValidatorOptions {
+            doc_base_url: Some("https://docs.example.com/errors".to_string()),
+            ..ValidatorOptions::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 17 },
+          "tags": ["rust"],
+        });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        assert_eq!(issues[0].code, "too_small");
+        assert_eq!(
+            issues[0].doc_url.as_deref(),
+            Some("https://docs.example.com/errors#minimum")
+        );
+
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues[0].doc_url, None);
+    }
+
+    #[test]
+    fn test_presence_report_on_complex_schema() {
+        let schema = get_complex_schema();
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User" },
+          "tags": ["rust"],
+        });
+        let report = presence(&schema, &instance, &ValidatorOptions::default()).unwrap();
+        assert!(report.valid);
+        assert_eq!(report.present_optional, vec!["/profile"]);
+        assert_eq!(report.absent_optional, vec!["/profile/age"]);
+
+        let instance = json!({ "username": "x" });
+        let report = presence(&schema, &instance, &ValidatorOptions::default()).unwrap();
+        assert!(!report.valid);
+        assert!(report.present_optional.is_empty());
+        assert_eq!(report.absent_optional, vec!["/profile", "/profile/age"]);
+    }
+
+    #[test]
+    fn test_memoize_serves_identical_instances_from_memo() {
+        let schema = get_complex_schema();
+        let options = ValidatorOptions {
+            memoize: true,
+            ..ValidatorOptions::default()
+        };
+        let validator = WasmValidator::compile(schema, options).unwrap();
+        let compiled = validator.compiled().unwrap();
+        let invalid = json!({ "username": "x" });
+        let first = validator.validate_value(compiled, &invalid);
+        assert!(first.is_err());
+        assert_eq!(validator.validate_value(compiled, &invalid), first);
+        assert!(validator
+            .validate_value(compiled, &json!({ "username": "y" }))
+            .is_err());
+        let stats = validator.memo.as_ref().unwrap().borrow().stats();
+        assert_eq!((stats.hits, stats.misses, stats.size), (1, 2, 2));
+
+        let unmemoized =
+            WasmValidator::compile(get_complex_schema(), ValidatorOptions::default()).unwrap();
+        assert!(unmemoized.memo.is_none());
+    }
+
+    #[test]
+    fn test_include_both_messages() {
+        let schema = json!({ "type": "string", "maxLength": 3 });
+        let options = ValidatorOptions {
+            mask_values: true,
+            include_both_messages: true,
+            ..ValidatorOptions::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        let issues =
+            validate_internal(&validator, &schema, &json!("secret"), &options).unwrap_err();
+        assert_eq!(issues[0].code, "too_long");
+        assert_eq!(issues[0].message, "\"secret\" is longer than 3 characters");
+        let masked = issues[0].masked_message.as_deref().unwrap();
+        assert!(!masked.contains("secret"), "{masked}");
+        assert_ne!(masked, issues[0].message);
+
+        let issues = validate_internal(
+            &validator,
+            &schema,
+            &json!("secret"),
+            &ValidatorOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues[0].masked_message, None);
+    }
+
+    #[test]
+    fn test_attach_context_subtree() {
         let schema = get_complex_schema();
         let validator = Validator::new(&schema).unwrap();
-        let invalid_instance = json!({
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "status": "active",
+          "profile": { "fullName": "Test User", "age": 17 },
+          "tags": ["rust"],
+        });
+        let options = ValidatorOptions::default();
+        let mut issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        attach_context(&mut issues, &instance, 1, &options);
+        assert_eq!(
+            issues[0].context_value,
+            Some(json!({ "fullName": "Test User", "age": 17 }))
+        );
+        attach_context(&mut issues, &instance, 5, &options);
+        assert_eq!(issues[0].context_value.as_ref(), Some(&instance));
+
+        let masked = ValidatorOptions {
+            mask_values: true,
+            ..ValidatorOptions::default()
+        };
+        attach_context(&mut issues, &instance, 1, &masked);
+        assert_eq!(
+            issues[0].context_value,
+            Some(json!({ "fullName": null, "age": null }))
+        );
+    }
+
+    #[test]
+    fn test_validate_items_stops_after_max_failing_items() {
+        let schema = json!({
+          "type": "array",
+          "items": { "type": "object", "properties": { "n": { "type": "integer", "multipleOf": 2 } } },
+        });
+        let instance: Value = (0..100).map(|n| json!({ "n": n })).collect();
+        let options = ValidatorOptions::default();
+        let failing = validate_items(&schema, &instance, Some(5), &options).unwrap();
+        let indices: Vec<_> = failing.iter().map(|item| item.index).collect();
+        assert_eq!(indices, vec![1, 3, 5, 7, 9]);
+        assert_eq!(failing[2].issues[0].path, "/5/n");
+        assert_eq!(
+            failing[2].issues[0].schema_path,
+            "/items/properties/n/multipleOf"
+        );
+
+        assert_eq!(
+            validate_items(&schema, &instance, None, &options)
+                .unwrap()
+                .len(),
+            50
+        );
+        let masked = ValidatorOptions {
+            mask_values: true,
+            base_path: Some("/orders".to_string()),
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_items(&schema, &json!("secret"), None, &masked).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_type");
+        assert_eq!(issues[0].path, "/orders");
+        assert!(!issues[0].message.contains("secret"));
+    }
+
+    #[test]
+    fn test_negated_schema_on_not_match() {
+        let schema = json!({ "properties": { "role": { "not": { "const": "admin" } } } });
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "role": "admin" });
+        let issues =
+            perform_validation(&validator, &instance, &ValidatorOptions::default()).unwrap_err();
+        assert_eq!(issues[0].code, "negated_schema_match");
+        assert_eq!(issues[0].negated_schema, Some(json!({ "const": "admin" })));
+        assert_eq!(
+            issues[0].message,
+            r#"{"const":"admin"} is not allowed for "admin""#
+        );
+
+        let masked = ValidatorOptions {
+            mask_values: true,
+            ..ValidatorOptions::default()
+        };
+        let issues = perform_validation(&validator, &instance, &masked).unwrap_err();
+        assert_eq!(issues[0].negated_schema, Some(json!({ "const": "admin" })));
+        assert_eq!(
+            issues[0].message,
+            r#"{"const":"admin"} is not allowed for value"#
+        );
+        assert!(!issues[0].message_params.contains_key("value"));
+    }
+
+    #[test]
+    fn test_null_versus_missing_property() {
+        let schema = json!({
+          "type": "object",
+          "properties": {
+            "name": { "type": "string" },
+            "nickname": { "type": ["string", "null"] },
+          },
+          "required": ["name"],
+        });
+        let validator = Validator::new(&schema).unwrap();
+        let codes = |instance: Value, options: &ValidatorOptions| -> Vec<(String, String)> {
+            validate_internal(&validator, &schema, &instance, options)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|issue| (issue.path, issue.code))
+                .collect()
+        };
+        let options = ValidatorOptions::default();
+        let present_null = json!({ "name": null, "nickname": null });
+        assert_eq!(
+            codes(present_null.clone(), &options),
+            vec![("/name".to_string(), "invalid_type".to_string())]
+        );
+        assert_eq!(
+            codes(json!({ "nickname": "Al" }), &options),
+            vec![("".to_string(), "missing_property".to_string())]
+        );
+        assert!(codes(json!({ "name": "Al", "nickname": null }), &options).is_empty());
+
+        let null_as_missing = ValidatorOptions {
+            treat_null_as_missing: true,
+            ..ValidatorOptions::default()
+        };
+        assert_eq!(
+            codes(present_null, &null_as_missing),
+            vec![("".to_string(), "missing_property".to_string())]
+        );
+        assert!(codes(json!({ "name": "Al", "nickname": null }), &null_as_missing).is_empty());
+    }
+
+    #[test]
+    fn test_allowed_values_at_pointer() {
+        let schema = get_complex_schema();
+        assert_eq!(
+            allowed_values(&schema, "/properties/status"),
+            Some(vec![json!("active"), json!("inactive"), json!("pending")])
+        );
+        assert_eq!(allowed_values(&schema, "/properties/username"), None);
+        assert_eq!(allowed_values(&schema, "/properties/missing"), None);
+
+        let schema = json!({
+          "properties": {
+            "flag": { "$ref": "#/$defs/flag" },
+            "kind": { "const": "user" },
+          },
+          "$defs": { "flag": { "type": ["boolean", "null"] } },
+        });
+        assert_eq!(
+            allowed_values(&schema, "/properties/flag"),
+            Some(vec![json!(true), json!(false), json!(null)])
+        );
+        assert_eq!(
+            allowed_values(&schema, "/properties/kind"),
+            Some(vec![json!("user")])
+        );
+    }
+
+    #[test]
+    fn test_scratch_buffer_reduces_allocations() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let batch: Vec<Value> = (0..500)
+            .map(|n| {
+                json!({
+                  "id": format!("not-a-uuid-{n}"),
+                  "username": "ab",
+                  "status": "archived",
+                  "tags": [],
+                })
+            })
+            .collect();
+        // Without a buffer, messages are formatted with a plain `to_string`.
+        let fresh = count_allocations(|| {
+            for instance in &batch {
+                let _ = validate_internal(&validator, &schema, instance, &options);
+            }
+        });
+        let mut scratch = String::new();
+        let reused = count_allocations(|| {
+            for instance in &batch {
+                let _ = validate_internal_with(
+                    &validator,
+                    &schema,
+                    instance,
+                    &options,
+                    Some(&mut scratch),
+                );
+            }
+        });
+        assert!(
+            reused < fresh,
+            "{reused} allocations with a reused buffer, {fresh} without"
+        );
+    }
+
+    #[test]
+    fn test_prefix_items_tuple_paths_and_codes() {
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "type": "object",
+          "properties": {
+            "point": {
+              "prefixItems": [{ "type": "string" }, { "type": "integer" }, { "type": "boolean" }],
+              "items": false,
+            },
+          },
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let issues = |instance: Value| -> Vec<(String, String, String)> {
+            validate_internal(&validator, &schema, &instance, &options)
+                .unwrap_err()
+                .into_iter()
+                .map(|issue| (issue.path, issue.code, issue.schema_path))
+                .collect()
+        };
+        assert_eq!(
+            issues(json!({ "point": ["a", 1, "yes"] })),
+            vec![(
+                "/point/2".to_string(),
+                "invalid_type".to_string(),
+                "/properties/point/prefixItems/2/type".to_string()
+            )]
+        );
+        assert_eq!(
+            issues(json!({ "point": ["a", 1, true, 4] })),
+            vec![(
+                "/point/3".to_string(),
+                "additional_items".to_string(),
+                "/properties/point/items".to_string()
+            )]
+        );
+
+        let schema = json!({
+          "$schema": "http://json-schema.org/draft-07/schema#",
+          "items": [{ "type": "string" }, { "type": "integer" }],
+          "additionalItems": false,
+        });
+        let validator = compile_schema(&schema, &options).unwrap();
+        let issues =
+            validate_internal(&validator, &schema, &json!(["a", 1, 2]), &options).unwrap_err();
+        assert_eq!(issues[0].code, "additional_items");
+
+        // A property that happens to be named `items` keeps its code.
+        let schema = json!({ "properties": { "items": false } });
+        let validator = compile_schema(&schema, &options).unwrap();
+        let issues =
+            validate_internal(&validator, &schema, &json!({ "items": 1 }), &options).unwrap_err();
+        assert_eq!(issues[0].code, "disallowed_value");
+    }
+
+    #[test]
+    fn test_flat_issues_match_full_issues() {
+        let schema = get_complex_schema();
+        let validator = Validator::new(&schema).unwrap();
+        let instance = json!({ "username": "ab", "profile": { "age": 3 } });
+        let options = ValidatorOptions::default();
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        let flat = flat_issues(issues.clone());
+        assert_eq!(flat.len(), issues.len());
+        for ((path, message), issue) in flat.iter().zip(&issues) {
+            assert_eq!((path, message), (&issue.path, &issue.message));
+        }
+        assert_eq!(
+            serde_json::to_value(&flat[0]).unwrap(),
+            json!([issues[0].path, issues[0].message])
+        );
+    }
+
+    #[test]
+    fn test_structural_only_ignores_value_constraints() {
+        let schema = get_complex_schema();
+        let options = ValidatorOptions {
+            structural_only: true,
+            ..ValidatorOptions::default()
+        };
+        let validator = compile_schema(&schema, &options).unwrap();
+        let value_invalid = json!({
+          "id": "not-a-uuid",
+          "username": "ab",
+          "status": "archived",
+          "profile": { "fullName": "Ada", "age": 3 },
+          "tags": [],
+        });
+        assert!(validate_internal(
+            &validator,
+            &schema,
+            &value_invalid,
+            &ValidatorOptions::default()
+        )
+        .is_err());
+        assert!(validate_internal(&validator, &schema, &value_invalid, &options).is_ok());
+
+        let shape_invalid = json!({ "id": "x", "username": 1, "status": "active" });
+        let codes: Vec<_> = validate_internal(&validator, &schema, &shape_invalid, &options)
+            .unwrap_err()
+            .into_iter()
+            .map(|issue| issue.code)
+            .collect();
+        assert_eq!(codes, vec!["invalid_type", "missing_property"]);
+    }
+
+    #[test]
+    fn test_referenced_resource_keeps_its_own_draft() {
+        let legacy = json!({
+          "$schema": "http://json-schema.org/draft-07/schema#",
+          "type": "object",
+          "properties": {
+            "pair": { "items": [{ "type": "string" }, { "type": "integer" }], "additionalItems": false },
+          },
+        });
+        let mut fetch = |uri: &str| match uri {
+            "https://example.com/root.json" => Ok(json!({
+              "$schema": "https://json-schema.org/draft/2020-12/schema",
+              "properties": { "legacy": { "$ref": "legacy.json" } },
+            })),
+            "https://example.com/legacy.json" => Ok(legacy.clone()),
+            _ => Err(format!("404 for {uri}")),
+        };
+        let compiled = WasmValidator::compile_from_uri(
+            "https://example.com/root.json",
+            &mut fetch,
+            ValidatorOptions::default(),
+        )
+        .unwrap();
+        let validator = compiled.compiled().unwrap();
+        // The array form of `items` is a Draft 7 tuple.
+        assert!(validator.is_valid(&json!({ "legacy": { "pair": ["a", 1] } })));
+        let issues = perform_validation(
+            validator,
+            &json!({ "legacy": { "pair": [1, 1, 1] } }),
+            &compiled.options,
+        )
+        .unwrap_err();
+        let codes: Vec<_> = issues.iter().map(|issue| issue.code.as_str()).collect();
+        assert_eq!(codes, vec!["invalid_type", "additional_items"]);
+        assert_eq!(issues[0].path, "/legacy/pair/0");
+
+        // Embedded instead, the subschema follows the root's draft, so it's flagged.
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "properties": { "legacy": { "$ref": "#/$defs/legacy" } },
+          "$defs": { "legacy": legacy },
+        });
+        let report = build_compile_report(&schema, &ValidatorOptions::default());
+        assert!(report
+            .warnings
+            .iter()
+            .any(|warning| warning.schema_path == "/$defs/legacy/$schema"));
+    }
+
+    #[test]
+    fn test_ndjson_lines_stream_until_callback_stops() {
+        let schema = json!({ "type": "object", "required": ["id"] });
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions::default();
+        let ndjson = "{\"id\": 1}\n\n{}\nnot json\n{\"id\": 4}\n";
+        let mut seen = Vec::new();
+        let count =
+            for_each_ndjson_line::<()>(&validator, &schema, ndjson, &options, &mut |result| {
+                let code = result.issues.first().map(|issue| issue.code.clone());
+                seen.push((result.line, result.valid, code));
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(
+            seen,
+            vec![
+                (1, true, None),
+                (3, false, Some("missing_property".to_string())),
+                (4, false, Some("invalid_json".to_string())),
+                (5, true, None),
+            ]
+        );
+
+        let mut calls = 0;
+        let count =
+            for_each_ndjson_line::<()>(&validator, &schema, ndjson, &options, &mut |result| {
+                calls += 1;
+                Ok(result.valid)
+            })
+            .unwrap();
+        assert_eq!((count, calls), (2, 2));
+    }
+
+    #[test]
+    fn test_normalize_messages_only_rewrites_generated_messages() {
+        let schema = json!({ "type": "object", "properties": { "n": { "minimum": 5 } } });
+        let validator = Validator::new(&schema).unwrap();
+        let options = ValidatorOptions {
+            normalize_messages: true,
+            max_depth: Some(1),
+            ..ValidatorOptions::default()
+        };
+        let issues = validate_internal(&validator, &schema, &json!({ "a": { "b": 1 } }), &options)
+            .unwrap_err();
+        assert_eq!(
+            issues[0].message,
+            "instance is nested deeper than the limit of 1 levels"
+        );
+
+        let issues =
+            validate_internal(&validator, &schema, &json!({ "n": 1 }), &options).unwrap_err();
+        assert_eq!(issues[0].message, "1 is less than the minimum of 5");
+
+        let mut issues = vec![
+            ValidationIssue::new("".to_string(), "Row is empty.".to_string(), "invalid_csv"),
+            ValidationIssue::new("".to_string(), "CSV parse error".to_string(), "invalid_csv"),
+        ];
+        normalize_messages(&mut issues, &options);
+        assert_eq!(issues[0].message, "row is empty");
+        assert_eq!(issues[1].message, "CSV parse error");
+    }
+
+    #[test]
+    fn test_validate_embedded_openapi_schema() {
+        let document = json!({
+          "openapi": "3.1.0",
+          "components": {
+            "schemas": {
+              "User": {
+                "type": "object",
+                "properties": {
+                  "name": { "type": "string" },
+                  "address": { "$ref": "#/components/schemas/Address" },
+                },
+                "required": ["name"],
+              },
+              "Address": {
+                "type": "object",
+                "properties": { "zip": { "type": "string", "pattern": "^[0-9]{5}$" } },
+              },
+            },
+          },
+        });
+        let options = ValidatorOptions::default();
+        let pointer = "/components/schemas/User";
+        let valid = json!({ "name": "Ada", "address": { "zip": "12345" } });
+        assert!(validate_embedded_schema(&document, pointer, &valid, &options).is_ok());
+
+        let invalid = json!({ "name": "Ada", "address": { "zip": "1" } });
+        let issues = validate_embedded_schema(&document, pointer, &invalid, &options).unwrap_err();
+        assert_eq!(issues[0].path, "/address/zip");
+        assert_eq!(issues[0].code, "pattern_mismatch");
+
+        let issues =
+            validate_embedded_schema(&document, "/components/schemas/Missing", &valid, &options)
+                .unwrap_err();
+        assert_eq!(issues[0].code, "invalid_schema");
+    }
+
+    #[test]
+    fn test_validate_pair_reports_each_side() {
+        let request_schema = json!({ "type": "object", "required": ["query"] });
+        let response_schema = get_complex_schema();
+        let options = ValidatorOptions::default();
+        let pair = validate_request_response(
+            &request_schema,
+            &json!({ "query": "ada" }),
+            &response_schema,
+            &json!({ "username": "ada" }),
+            &options,
+        )
+        .unwrap();
+        assert!(pair.request.is_empty());
+        let missing: Vec<_> = pair
+            .response
+            .iter()
+            .map(|issue| issue.message_params["property"].clone())
+            .collect();
+        assert_eq!(missing, vec![json!("id"), json!("status"), json!("tags")]);
+
+        let invalid_schema = json!({ "type": "nope" });
+        assert!(validate_request_response(
+            &request_schema,
+            &json!({}),
+            &invalid_schema,
+            &json!({}),
+            &options
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_with_fallback_uses_fallback_for_malformed_primary() {
+        let options = ValidatorOptions::default();
+        let malformed = json!({ "type": "strnig" });
+        let fallback = json!({ "type": "object", "required": ["id"] });
+        let result =
+            validate_with_fallback_schema(&malformed, &fallback, &json!({ "id": 1 }), &options)
+                .unwrap();
+        assert_eq!(result.schema, UsedSchema::Fallback);
+        assert!(result.valid);
+        let result =
+            validate_with_fallback_schema(&malformed, &fallback, &json!({}), &options).unwrap();
+        assert_eq!(result.issues[0].code, "missing_property");
+
+        let primary = json!({ "type": "string" });
+        let result =
+            validate_with_fallback_schema(&primary, &fallback, &json!(1), &options).unwrap();
+        assert_eq!(result.schema, UsedSchema::Primary);
+        assert_eq!(result.issues[0].code, "invalid_type");
+
+        let issues =
+            validate_with_fallback_schema(&malformed, &malformed, &json!(1), &options).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_schema");
+    }
+
+    #[test]
+    fn test_instance_type_survives_masking() {
+        let schema = json!({ "properties": { "name": { "maxLength": 3 } } });
+        let validator = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        let options = ValidatorOptions {
+            mask_values: true,
+            ..Default::default()
+        };
+        let issues = validate_internal(&validator, &schema, &json!({ "name": "secret" }), &options)
+            .unwrap_err();
+        assert_eq!(issues[0].instance_type.as_deref(), Some("string"));
+        assert!(!issues[0].message.contains("secret"));
+    }
+
+    #[test]
+    fn test_example_for_string_and_enum_fields() {
+        let schema = json!({
+          "properties": {
+            "name": { "type": "string", "minLength": 3 },
+            "status": { "$ref": "#/$defs/status" },
+          },
+          "$defs": { "status": { "enum": ["active", "archived"] } },
+        });
+        let example_at = |pointer| {
+            let subschema = walk::at_schema_path(&schema, pointer).unwrap();
+            example::example_for(&schema, subschema, None)
+        };
+        let name = example_at("/properties/name").unwrap();
+        assert_eq!(name, json!("aaa"));
+        let status = example_at("/properties/status").unwrap();
+        assert_eq!(status, json!("active"));
+        for (pointer, example) in [("/properties/name", name), ("/properties/status", status)] {
+            let subschema = walk::at_schema_path(&schema, pointer).unwrap();
+            let mut resource = subschema.clone();
+            resource["$defs"] = schema["$defs"].clone();
+            assert!(jsonschema::is_valid(&resource, &example));
+        }
+    }
+
+    #[test]
+    fn test_check_schemas_reports_each_entry() {
+        let SchemaChecks::List(checks) = check_schema_set(json!([
+          { "type": "string" },
+          { "type": "strnig" },
+          true,
+          { "minLength": -1 },
+        ])) else {
+            panic!("expected a list");
+        };
+        let valid: Vec<bool> = checks.iter().map(|check| check.valid).collect();
+        assert_eq!(valid, [true, false, true, false]);
+        assert_eq!(checks[1].issues[0].code, "invalid_schema");
+
+        let SchemaChecks::Map(checks) = check_schema_set(json!({
+          "user.json": { "type": "object" },
+          "order.json": { "required": "id" },
+        })) else {
+            panic!("expected a map");
+        };
+        assert_eq!(checks["user.json"]["valid"], json!(true));
+        assert_eq!(checks["order.json"]["valid"], json!(false));
+        assert_eq!(
+            checks["order.json"]["issues"][0]["code"],
+            json!("invalid_schema")
+        );
+
+        // An unwrapped schema object is taken for a map, keyword by keyword.
+        let SchemaChecks::Map(checks) = check_schema_set(json!({ "type": "string" })) else {
+            panic!("expected a map");
+        };
+        assert_eq!(checks["type"]["valid"], json!(false));
+    }
+
+    #[test]
+    fn test_sibling_keys_expose_misspelled_key() {
+        let schema = json!({
+          "properties": { "username": { "type": "string" }, "age": {} },
+          "required": ["username"],
+          "additionalProperties": false,
+        });
+        let validator = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        let options = ValidatorOptions {
+            include_sibling_keys: true,
+            mask_values: true,
+            ..Default::default()
+        };
+        let instance = json!({ "usernam": "ada", "age": 36 });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        let keys = Some(vec!["usernam".to_string(), "age".to_string()]);
+        let by_code = |code| issues.iter().find(|issue| issue.code == code).unwrap();
+        assert_eq!(by_code("missing_property").sibling_keys, keys);
+        assert_eq!(by_code("additional_properties").sibling_keys, keys);
+        assert!(!serde_json::to_string(&issues).unwrap().contains("ada"));
+
+        let issues =
+            validate_internal(&validator, &schema, &instance, &ValidatorOptions::default())
+                .unwrap_err();
+        assert!(issues.iter().all(|issue| issue.sibling_keys.is_none()));
+    }
+
+    #[test]
+    fn test_dynamic_ref_tree_resolves_at_depth() {
+        let tree = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "$id": "https://example.com/tree",
+          "$dynamicAnchor": "node",
+          "type": "object",
+          "properties": {
+            "value": true,
+            "children": { "type": "array", "items": { "$dynamicRef": "#node" } },
+          },
+        });
+        // Extends the tree so that every node, however deep, takes integer values.
+        let schema = json!({
+          "$schema": "https://json-schema.org/draft/2020-12/schema",
+          "$id": "https://example.com/integer-tree",
+          "$dynamicAnchor": "node",
+          "$ref": "tree",
+          "properties": { "value": { "type": "integer" } },
+          "$defs": { "tree": tree },
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let node =
+            |value: Value, children: Vec<Value>| json!({ "value": value, "children": children });
+        let valid = node(
+            json!(1),
+            vec![node(
+                json!(2),
+                vec![node(json!(3), vec![node(json!(4), vec![])])],
+            )],
+        );
+        assert!(validate_internal(&validator, &schema, &valid, &options).is_ok());
+        let invalid = node(
+            json!(1),
+            vec![node(
+                json!(2),
+                vec![node(json!(3), vec![node(json!("4"), vec![])])],
+            )],
+        );
+        let issues = validate_internal(&validator, &schema, &invalid, &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "invalid_type");
+        assert_eq!(issues[0].path, "/children/0/children/0/children/0/value");
+
+        let issues = compile_schema(&json!({ "$dynamicRef": "#missing" }), &options).unwrap_err();
+        assert_eq!(issues[0].code, "schema_reference_error");
+        let issues = compile_schema(&json!({ "$ref": "#/$defs/missing" }), &options).unwrap_err();
+        assert_eq!(issues[0].code, "schema_reference_error");
+    }
+
+    #[test]
+    fn test_completeness_scores_partial_form() {
+        let schema = json!({
+          "type": "object",
+          "required": ["name", "email", "age"],
+          "properties": {
+            "name": { "type": "string", "minLength": 1 },
+            "email": { "type": "string", "format": "email" },
+            "age": { "type": "integer", "minimum": 18 },
+          },
+        });
+        let options = ValidatorOptions::default();
+        let partial = completeness_report(&schema, &json!({ "name": "Ada" }), &options).unwrap();
+        assert_eq!((partial.satisfied, partial.total), (4, 6));
+        assert!(partial.score > 0 && partial.score < 100);
+        assert_eq!(partial.issues.len(), 2);
+
+        let complete = json!({ "name": "Ada", "email": "ada@example.com", "age": 36 });
+        let complete = completeness_report(&schema, &complete, &options).unwrap();
+        assert_eq!(complete.score, 100);
+        assert!(complete.issues.is_empty());
+    }
+
+    #[test]
+    fn test_validate_first_match_returns_matching_index() {
+        let schemas = [
+            json!({ "required": ["v1_id"] }),
+            json!({ "required": ["id"], "properties": { "id": { "type": "string" } } }),
+            json!({ "required": ["id"] }),
+        ];
+        let options = ValidatorOptions::default();
+        assert_eq!(
+            first_matching_schema(&schemas, &json!({ "id": "a" }), &options),
+            Ok(1)
+        );
+        let issues = first_matching_schema(&schemas, &json!({}), &options).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "\"id\" is a required property");
+
+        let broken = [json!({ "type": "strnig" }), json!({ "type": "object" })];
+        assert_eq!(first_matching_schema(&broken, &json!({}), &options), Ok(1));
+        let issues = first_matching_schema(&[], &json!({}), &options).unwrap_err();
+        assert_eq!(issues[0].code, "invalid_schema");
+    }
+
+    #[test]
+    fn test_sarif_log_structure() {
+        let schema = json!({
+          "required": ["name"],
+          "properties": { "port": { "type": "integer" } },
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let issues =
+            validate_internal(&validator, &schema, &json!({ "port": "80" }), &options).unwrap_err();
+        let log = sarif_log(&issues, "config/app.json");
+        assert_eq!(log["version"], json!("2.1.0"));
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        let rule_ids: Vec<&Value> = results.iter().map(|result| &result["ruleId"]).collect();
+        assert_eq!(
+            rule_ids,
+            [&json!("missing_property"), &json!("invalid_type")]
+        );
+        let location = &results[1]["locations"][0];
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            json!("config/app.json")
+        );
+        assert_eq!(
+            location["logicalLocations"][0]["fullyQualifiedName"],
+            json!("/port")
+        );
+        assert_eq!(results[1]["message"]["text"], json!(issues[1].message));
+        assert_eq!(sarif_log(&[], "a.json")["runs"][0]["results"], json!([]));
+    }
+
+    #[test]
+    fn test_example_for_same_seed_same_example() {
+        let schema = json!({
+          "type": "object",
+          "required": ["color", "size", "shape"],
+          "properties": {
+            "color": { "enum": ["red", "green", "blue", "cyan", "magenta"] },
+            "size": { "oneOf": [{ "const": "S" }, { "const": "M" }, { "const": "L" }] },
+            "shape": { "enum": ["circle", "square", "triangle"] },
+          },
+        });
+        let example = |seed| example::example_for(&schema, &schema, seed).unwrap();
+        assert_eq!(example(Some(7)), example(Some(7)));
+        assert_eq!(
+            example(None),
+            json!({ "color": "red", "size": "S", "shape": "circle" })
+        );
+        let varied: HashSet<String> = (0..8).map(|seed| example(Some(seed)).to_string()).collect();
+        assert!(varied.len() > 1);
+    }
+
+    #[test]
+    fn test_validate_patch_reports_changed_paths_only() {
+        let schema = get_complex_schema();
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        // Already invalid elsewhere: `username` is too short and `status` missing.
+        let base = json!({
           "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
           "username": "a",
-          "status": "pending",
-          "tags": ["masked"],
+          "profile": { "fullName": "Ada", "age": 30 },
+          "tags": ["rust"],
         });
-        let issues = perform_validation(&validator, &invalid_instance, true).unwrap_err();
+        let patch = [json!({ "op": "replace", "path": "/profile/age", "value": 12 })];
+        let issues = validate_patched(&validator, &schema, &base, &patch, &options)
+            .unwrap()
+            .unwrap_err();
         assert_eq!(issues.len(), 1);
-        let issue = &issues[0];
-        assert!(!issue.message.contains(r#""a""#));
-        assert!(issue.message.contains("value is shorter than 3 characters"));
+        assert_eq!(issues[0].path, "/profile/age");
+        assert_eq!(issues[0].code, "too_small");
+
+        let patch = [json!({ "op": "remove", "path": "/profile/fullName" })];
+        let issues = validate_patched(&validator, &schema, &base, &patch, &options)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "missing_property");
+
+        let patch = [json!({ "op": "replace", "path": "/profile/age", "value": 40 })];
+        assert_eq!(
+            validate_patched(&validator, &schema, &base, &patch, &options),
+            Ok(Ok(()))
+        );
+        let patch = [json!({ "op": "remove", "path": "/missing" })];
+        assert!(validate_patched(&validator, &schema, &base, &patch, &options).is_err());
+
+        let dot = ValidatorOptions {
+            path_format: PathFormat::Dot,
+            ..ValidatorOptions::default()
+        };
+        let patch = [json!({ "op": "replace", "path": "/tags/0", "value": 7 })];
+        let issues = validate_patched(&validator, &schema, &base, &patch, &dot)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "/tags/0");
+        assert_eq!(issues[0].display_path.as_deref(), Some("tags[0]"));
+    }
+
+    #[test]
+    fn test_max_message_len_truncates_with_ellipsis() {
+        let options: Vec<String> = (0..200).map(|i| format!("option-{i}")).collect();
+        let schema = json!({ "enum": options });
+        let validator = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        let options = ValidatorOptions {
+            max_message_len: Some(40),
+            ..Default::default()
+        };
+        let issues = validate_internal(&validator, &schema, &json!("é"), &options).unwrap_err();
+        assert_eq!(issues[0].message.chars().count(), 40);
+        assert!(issues[0].message.starts_with("\"é\" is not one of"));
+        assert!(issues[0].message.ends_with('…'));
+        assert_eq!(issues[0].code, "enum_mismatch");
+
+        let mut short = vec![ValidationIssue::new(
+            "/".to_string(),
+            "x".repeat(40),
+            "custom_error",
+        )];
+        truncate_messages(&mut short, &options);
+        assert_eq!(short[0].message, "x".repeat(40));
+
+        // No room even for the ellipsis.
+        let empty = ValidatorOptions {
+            max_message_len: Some(0),
+            ..Default::default()
+        };
+        truncate_messages(&mut short, &empty);
+        assert_eq!(short[0].message, "");
+    }
+
+    #[test]
+    fn test_validate_summary_counts_instance_nodes() {
+        let schema = get_complex_schema();
+        // The root, 4 top-level values, `fullName` and `age`, and 3 tags.
+        let instance = json!({
+          "id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+          "username": "testuser",
+          "profile": { "fullName": "Test User", "age": 30 },
+          "tags": ["rust", "validate", "nodejs"],
+        });
+        let summary = summarize(&schema, &instance, &ValidatorOptions::default()).unwrap();
+        assert_eq!(summary.instance_nodes, 10);
+        assert!(!summary.valid);
+        assert_eq!(summary.issues[0].code, "missing_property");
+        let summary = summarize(&schema, &json!(null), &ValidatorOptions::default()).unwrap();
+        assert_eq!(summary.instance_nodes, 1);
+    }
+
+    #[test]
+    fn test_codes_from_comment_override_builtin_code() {
+        let schema = json!({
+          "properties": {
+            "age": { "type": "integer", "minimum": 18, "$comment": "code:AGE_TOO_LOW" },
+            "name": { "type": "string", "$comment": "Display name" },
+          },
+        });
+        let validator = compile_schema(&schema, &ValidatorOptions::default()).unwrap();
+        let options = ValidatorOptions {
+            codes_from_comment: true,
+            ..Default::default()
+        };
+        let instance = json!({ "age": 12, "name": 1 });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        let codes: Vec<(&str, &str, u16)> = issues
+            .iter()
+            .map(|issue| (issue.path.as_str(), issue.code.as_str(), issue.code_id))
+            .collect();
+        assert_eq!(
+            codes,
+            [("/age", "AGE_TOO_LOW", 0), ("/name", "invalid_type", 2)]
+        );
+        assert_eq!(
+            issues[0].id,
+            issue_id("/age", "/properties/age/minimum", "AGE_TOO_LOW")
+        );
+    }
+
+    #[test]
+    fn test_pattern_property_map_names_matched_pattern() {
+        let schema = json!({
+          "type": "object",
+          "patternProperties": {
+            "^x-": { "type": "string" },
+            "^[0-9]+$": { "type": "integer" },
+          },
+          "additionalProperties": false,
+        });
+        let instance = json!({ "x-trace": "abc", "42": 1, "other": true, "7": 2 });
+        let matched = matched_patterns(&schema, &instance);
+        assert_eq!(
+            Value::Object(matched),
+            json!({ "x-trace": "^x-", "42": "^[0-9]+$", "other": null, "7": "^[0-9]+$" })
+        );
+        assert!(matched_patterns(&schema, &json!([1])).is_empty());
+    }
+
+    #[test]
+    fn test_issues_csv_round_trips() {
+        let schema = json!({
+          "properties": {
+            "status": { "enum": ["a", "b"] },
+            "name": { "type": "string" },
+          },
+          "required": ["id"],
+        });
+        let options = ValidatorOptions::default();
+        let validator = compile_schema(&schema, &options).unwrap();
+        let instance = json!({ "status": "say \"hi\"", "name": 1 });
+        let issues = validate_internal(&validator, &schema, &instance, &options).unwrap_err();
+        let records = csv::parse(&issues_csv(&issues)).unwrap();
+        assert_eq!(records[0].cells, ["path", "code", "message"]);
+        let rows: Vec<Vec<String>> = records[1..].iter().map(|r| r.cells.clone()).collect();
+        let expected: Vec<Vec<String>> = issues
+            .iter()
+            .map(|issue| {
+                vec![
+                    issue.path.clone(),
+                    issue.code.clone(),
+                    issue.message.clone(),
+                ]
+            })
+            .collect();
+        assert_eq!(rows, expected);
+        assert!(issues.iter().any(|issue| issue.message.contains(',')));
+        assert!(issues.iter().any(|issue| issue.message.contains('"')));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,135 @@
+use serde_json::Value;
+
+/// The first limit an instance was found to exceed.
+#[derive(Debug, PartialEq)]
+pub(crate) enum LimitExceeded {
+    Depth(usize),
+    Nodes(usize),
+}
+
+/// The first limit a schema was found to exceed.
+#[derive(Debug, PartialEq)]
+pub(crate) enum SchemaLimitExceeded {
+    Depth(usize),
+    Keywords(usize),
+}
+
+/// Walks `value` iteratively (so hostile nesting can't overflow the stack) and
+/// stops at the first limit exceeded. Depth counts path segments, so a scalar
+/// root has depth 0 and `{"a": {"b": 1}}` has depth 2; every value is a node.
+pub(crate) fn check_value_size(
+    value: &Value,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+) -> Result<(), LimitExceeded> {
+    let mut stack = vec![(value, 0usize)];
+    let mut nodes = 0usize;
+    while let Some((current, depth)) = stack.pop() {
+        nodes += 1;
+        if let Some(limit) = max_nodes {
+            if nodes > limit {
+                return Err(LimitExceeded::Nodes(limit));
+            }
+        }
+        if let Some(limit) = max_depth {
+            if depth > limit {
+                return Err(LimitExceeded::Depth(limit));
+            }
+        }
+        match current {
+            Value::Array(items) => stack.extend(items.iter().map(|item| (item, depth + 1))),
+            Value::Object(map) => stack.extend(map.values().map(|item| (item, depth + 1))),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Counts every value in `value`, containers and scalars alike, iteratively
+/// like [`check_value_size`].
+pub(crate) fn count_nodes(value: &Value) -> usize {
+    let mut stack = vec![value];
+    let mut nodes = 0usize;
+    while let Some(current) = stack.pop() {
+        nodes += 1;
+        match current {
+            Value::Array(items) => stack.extend(items),
+            Value::Object(map) => stack.extend(map.values()),
+            _ => {}
+        }
+    }
+    nodes
+}
+
+/// Like [`check_value_size`] for a schema document, but counting keywords:
+/// the members of every object in it, wherever it is nested.
+pub(crate) fn check_schema_size(
+    schema: &Value,
+    max_depth: Option<usize>,
+    max_keywords: Option<usize>,
+) -> Result<(), SchemaLimitExceeded> {
+    let mut stack = vec![(schema, 0usize)];
+    let mut keywords = 0usize;
+    while let Some((current, depth)) = stack.pop() {
+        if let Some(limit) = max_depth {
+            if depth > limit {
+                return Err(SchemaLimitExceeded::Depth(limit));
+            }
+        }
+        match current {
+            Value::Array(items) => stack.extend(items.iter().map(|item| (item, depth + 1))),
+            Value::Object(map) => {
+                keywords += map.len();
+                if let Some(limit) = max_keywords {
+                    if keywords > limit {
+                        return Err(SchemaLimitExceeded::Keywords(limit));
+                    }
+                }
+                stack.extend(map.values().map(|item| (item, depth + 1)));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_depth_counts_path_segments() {
+        let value = json!({ "a": { "b": [1] } });
+        assert_eq!(check_value_size(&value, Some(3), None), Ok(()));
+        assert_eq!(
+            check_value_size(&value, Some(2), None),
+            Err(LimitExceeded::Depth(2))
+        );
+        assert_eq!(check_value_size(&json!(1), Some(0), None), Ok(()));
+    }
+
+    #[test]
+    fn test_nodes_count_every_value() {
+        let value = json!({ "a": 1, "b": [true, null] });
+        assert_eq!(check_value_size(&value, None, Some(5)), Ok(()));
+        assert_eq!(
+            check_value_size(&value, None, Some(4)),
+            Err(LimitExceeded::Nodes(4))
+        );
+    }
+
+    #[test]
+    fn test_schema_keywords_count_object_members() {
+        let schema = json!({ "type": "object", "properties": { "a": { "type": "string" } } });
+        assert_eq!(check_schema_size(&schema, None, Some(4)), Ok(()));
+        assert_eq!(
+            check_schema_size(&schema, None, Some(3)),
+            Err(SchemaLimitExceeded::Keywords(3))
+        );
+        assert_eq!(
+            check_schema_size(&schema, Some(2), None),
+            Err(SchemaLimitExceeded::Depth(2))
+        );
+    }
+}
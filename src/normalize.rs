@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::walk;
+
+/// Applies the `x-trim` and `x-lowercase` annotations of every subschema that
+/// applies to a string in `instance`, in place.
+pub(crate) fn normalize(schema: &Value, instance: &mut Value) {
+    let mut edits = Vec::new();
+    walk::for_each_applied(schema, instance, &mut |node| {
+        if node.instance.is_string() {
+            let flag = |keyword| node.schema.get(keyword).and_then(Value::as_bool) == Some(true);
+            let (trim, lowercase) = (flag("x-trim"), flag("x-lowercase"));
+            if trim || lowercase {
+                edits.push((node.instance_path.clone(), trim, lowercase));
+            }
+        }
+        true
+    });
+    for (path, trim, lowercase) in edits {
+        if let Some(Value::String(value)) = instance.pointer_mut(&path) {
+            if trim {
+                *value = value.trim().to_string();
+            }
+            if lowercase {
+                *value = value.to_lowercase();
+            }
+        }
+    }
+}
+
+/// Replaces every string in `instance` that parses as a number, where the
+/// subschemas applying to it declare a `number`/`integer` type and none allows
+/// `string`, with that number.
+pub(crate) fn parse_numeric_strings(schema: &Value, instance: &mut Value) {
+    // Per string: whether some subschema wants a number, and whether one allows a string.
+    let mut types: HashMap<String, (bool, bool)> = HashMap::new();
+    walk::for_each_applied(schema, instance, &mut |node| {
+        if node.instance.is_string() {
+            let declared: Vec<&str> = match node.schema.get("type") {
+                Some(Value::String(name)) => vec![name.as_str()],
+                Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let (numeric, string) = types.entry(node.instance_path.clone()).or_default();
+            *numeric |= declared.contains(&"number") || declared.contains(&"integer");
+            *string |= declared.contains(&"string");
+        }
+        true
+    });
+    for (path, (numeric, string)) in types {
+        if !numeric || string {
+            continue;
+        }
+        if let Some(value) = instance.pointer_mut(&path) {
+            if let Some(number) = value.as_str().and_then(parse_number) {
+                *value = number;
+            }
+        }
+    }
+}
+
+/// Removes every object property whose value is `null`, at any depth, so it
+/// validates as absent. `null` array items are kept.
+pub(crate) fn remove_null_properties(instance: &mut Value) {
+    match instance {
+        Value::Object(properties) => {
+            properties.retain(|_, value| !value.is_null());
+            properties.values_mut().for_each(remove_null_properties);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_null_properties),
+        _ => {}
+    }
+}
+
+fn parse_number(text: &str) -> Option<Value> {
+    let text = text.trim();
+    text.parse::<i64>()
+        .ok()
+        .map(Value::from)
+        .or_else(|| serde_json::Number::from_f64(text.parse().ok()?).map(Value::Number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_annotated_strings_only() {
+        let schema = json!({
+          "properties": {
+            "email": { "type": "string", "x-trim": true, "x-lowercase": true },
+            "name": { "type": "string" },
+            "tags": { "items": { "x-trim": true } },
+          },
+        });
+        let mut instance = json!({
+          "email": "  Ada@Example.COM ",
+          "name": "  Ada ",
+          "tags": [" a ", 1],
+        });
+        normalize(&schema, &mut instance);
+        assert_eq!(
+            instance,
+            json!({ "email": "ada@example.com", "name": "  Ada ", "tags": ["a", 1] })
+        );
+    }
+}
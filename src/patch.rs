@@ -0,0 +1,145 @@
+use serde_json::Value;
+
+/// Applies an RFC 6902 JSON Patch to `document` in place and returns the paths
+/// it changed: the target of each `add`, `remove`, `replace` and `copy`, and
+/// both ends of each `move`. Fails on the first operation that can't apply,
+/// including a failed `test`, leaving `document` partly patched.
+pub(crate) fn apply_patch(document: &mut Value, patch: &[Value]) -> Result<Vec<String>, String> {
+    let mut changed = Vec::new();
+    for (index, operation) in patch.iter().enumerate() {
+        let field = |name: &str| {
+            operation
+                .get(name)
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("operation {} has no `{}`", index, name))
+        };
+        let value = || {
+            operation
+                .get("value")
+                .cloned()
+                .ok_or_else(|| format!("operation {} has no `value`", index))
+        };
+        let path = field("path")?;
+        let fail = |reason: &str| format!("operation {} ({}): {}", index, path, reason);
+        match field("op")? {
+            "add" => changed.push(add(document, path, value()?).map_err(fail)?),
+            "remove" => {
+                remove(document, path).map_err(fail)?;
+                changed.push(path.to_string());
+            }
+            "replace" => {
+                let target = document
+                    .pointer_mut(path)
+                    .ok_or_else(|| fail("path does not exist"))?;
+                *target = value()?;
+                changed.push(path.to_string());
+            }
+            "move" => {
+                let from = field("from")?;
+                if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                    return Err(fail("cannot move a value into itself"));
+                }
+                let moved = remove(document, from).map_err(fail)?;
+                changed.push(from.to_string());
+                changed.push(add(document, path, moved).map_err(fail)?);
+            }
+            "copy" => {
+                let copied = document
+                    .pointer(field("from")?)
+                    .cloned()
+                    .ok_or_else(|| fail("`from` does not exist"))?;
+                changed.push(add(document, path, copied).map_err(fail)?);
+            }
+            "test" => {
+                if document.pointer(path) != Some(&value()?) {
+                    return Err(fail("test failed"));
+                }
+            }
+            other => return Err(fail(&format!("unknown op `{}`", other))),
+        }
+    }
+    Ok(changed)
+}
+
+/// Splits a non-root pointer into its parent pointer and unescaped last token.
+fn split(path: &str) -> Result<(&str, String), &'static str> {
+    let (parent, token) = path.rsplit_once('/').ok_or("path is not a JSON Pointer")?;
+    Ok((parent, token.replace("~1", "/").replace("~0", "~")))
+}
+
+/// Adds `value` at `path` and returns where it landed, with an appending `-`
+/// resolved to the index.
+fn add(document: &mut Value, path: &str, value: Value) -> Result<String, &'static str> {
+    if path.is_empty() {
+        *document = value;
+        return Ok(String::new());
+    }
+    let (parent, token) = split(path)?;
+    match document.pointer_mut(parent) {
+        Some(Value::Object(object)) => {
+            object.insert(token, value);
+        }
+        Some(Value::Array(items)) => {
+            let index = if token == "-" {
+                items.len()
+            } else {
+                token.parse().map_err(|_| "array index is not a number")?
+            };
+            if index > items.len() {
+                return Err("array index is out of bounds");
+            }
+            items.insert(index, value);
+            return Ok(format!("{}/{}", parent, index));
+        }
+        _ => return Err("parent does not exist"),
+    }
+    Ok(path.to_string())
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, &'static str> {
+    let (parent, token) = split(path)?;
+    match document.pointer_mut(parent) {
+        Some(Value::Object(object)) => object.remove(&token).ok_or("path does not exist"),
+        Some(Value::Array(items)) => match token.parse::<usize>() {
+            Ok(index) if index < items.len() => Ok(items.remove(index)),
+            _ => Err("path does not exist"),
+        },
+        _ => Err("path does not exist"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_patch_operations() {
+        let mut document = json!({ "a": { "b": 1 }, "list": [1, 2], "x~y": true });
+        let patch = [
+            json!({ "op": "test", "path": "/a/b", "value": 1 }),
+            json!({ "op": "replace", "path": "/a/b", "value": 2 }),
+            json!({ "op": "add", "path": "/list/-", "value": 3 }),
+            json!({ "op": "remove", "path": "/list/0" }),
+            json!({ "op": "move", "from": "/x~0y", "path": "/moved" }),
+            json!({ "op": "copy", "from": "/a", "path": "/c" }),
+        ];
+        let changed = apply_patch(&mut document, &patch).unwrap();
+        assert_eq!(
+            document,
+            json!({ "a": { "b": 2 }, "list": [2, 3], "moved": true, "c": { "b": 2 } })
+        );
+        assert_eq!(
+            changed,
+            ["/a/b", "/list/2", "/list/0", "/x~0y", "/moved", "/c"]
+        );
+
+        let error = apply_patch(
+            &mut document,
+            &[json!({ "op": "test", "path": "/a/b", "value": 1 })],
+        )
+        .unwrap_err();
+        assert!(error.contains("test failed"));
+        assert!(apply_patch(&mut document, &[json!({ "op": "remove", "path": "/nope" })]).is_err());
+    }
+}
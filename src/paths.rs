@@ -0,0 +1,89 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// How `ValidationIssue.path` is spelled.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PathFormat {
+    /// A JSON Pointer, e.g. `/tags/0`.
+    #[default]
+    Pointer,
+    /// Dotted properties with bracketed array indices, e.g. `tags[0]`.
+    Dot,
+}
+
+/// Converts an issue path, a JSON Pointer into `instance` behind an optional
+/// `base_path`, to dot notation: `profile.age`, `tags[0]`. A segment is an index
+/// only where the instance has an array, so an object key such as `"0"` stays a
+/// property. Keys that aren't plain identifiers are bracketed and quoted, e.g.
+/// `headers["content-type"]`. `base_path` segments are treated as properties.
+pub(crate) fn to_dot_path(path: &str, base_path: Option<&str>, instance: &Value) -> String {
+    let (base, rest) = base_path
+        .and_then(|base| Some((base, path.strip_prefix(base)?)))
+        .unwrap_or(("", path));
+    let mut dotted = String::new();
+    push_segments(&mut dotted, base, None);
+    push_segments(&mut dotted, rest, Some(instance));
+    dotted
+}
+
+fn push_segments(dotted: &mut String, pointer: &str, mut current: Option<&Value>) {
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        let index = segment.parse::<usize>().ok();
+        match (current, index) {
+            (Some(Value::Array(items)), Some(index)) => {
+                dotted.push_str(&format!("[{}]", index));
+                current = items.get(index);
+            }
+            _ => {
+                if is_identifier(&segment) {
+                    if !dotted.is_empty() {
+                        dotted.push('.');
+                    }
+                    dotted.push_str(&segment);
+                } else {
+                    dotted.push('[');
+                    dotted.push_str(&Value::from(segment.as_str()).to_string());
+                    dotted.push(']');
+                }
+                current = current.and_then(|value| value.get(&segment));
+            }
+        }
+    }
+}
+
+fn is_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_dot_path() {
+        let instance = json!({
+          "profile": { "age": 17 },
+          "tags": ["a", { "x": 1 }],
+          "byId": { "0": true },
+          "headers": { "content-type": "" },
+        });
+        let dot = |pointer| to_dot_path(pointer, None, &instance);
+        assert_eq!(dot("/profile/age"), "profile.age");
+        assert_eq!(dot("/tags/0"), "tags[0]");
+        assert_eq!(dot("/tags/1/x"), "tags[1].x");
+        assert_eq!(dot("/byId/0"), "byId[\"0\"]");
+        assert_eq!(dot("/headers/content-type"), "headers[\"content-type\"]");
+        assert_eq!(dot(""), "");
+        assert_eq!(
+            to_dot_path("/body/tags/0", Some("/body"), &instance),
+            "body.tags[0]"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::walk;
+
+/// A property some `properties` keyword declares.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct SchemaProperty {
+    /// JSON Pointer of the property within an instance, e.g. `/profile/fullName`.
+    pub path: String,
+    /// The declared `type`, a string or an array of strings, if any.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<Value>,
+    /// Whether the enclosing object's `required` lists the property.
+    pub required: bool,
+}
+
+/// Lists every property declared under `properties`, depth first, descending
+/// into the properties of nested objects. A property whose schema is a local
+/// `$ref` reports the type and properties of its target; refs aren't followed
+/// further, and a target already being listed above isn't entered again, so
+/// recursive schemas stay finite.
+pub(crate) fn schema_properties(root: &Value) -> Vec<SchemaProperty> {
+    let mut listed = Vec::new();
+    push_properties(root, root, "", &mut Vec::new(), &mut listed);
+    listed
+}
+
+fn push_properties<'a>(
+    root: &'a Value,
+    schema: &'a Value,
+    path: &str,
+    entered: &mut Vec<&'a str>,
+    listed: &mut Vec<SchemaProperty>,
+) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    let required = schema.get("required").and_then(Value::as_array);
+    for (name, property) in properties {
+        let reference = property.get("$ref").and_then(Value::as_str);
+        let target = reference
+            .and_then(|reference| walk::resolve_local_ref(root, reference))
+            .unwrap_or(property);
+        let property_path = walk::join(path, name);
+        listed.push(SchemaProperty {
+            path: property_path.clone(),
+            type_: property.get("type").or(target.get("type")).cloned(),
+            required: required.is_some_and(|required| required.iter().any(|r| r == name)),
+        });
+        if let Some(reference) = reference {
+            if entered.contains(&reference) {
+                continue;
+            }
+            entered.push(reference);
+            push_properties(root, target, &property_path, entered, listed);
+            entered.pop();
+        } else {
+            push_properties(root, target, &property_path, entered, listed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_properties_follow_refs_once() {
+        let schema = json!({
+          "properties": {
+            "name": { "type": "string" },
+            "parent": { "$ref": "#/$defs/person" },
+          },
+          "$defs": {
+            "person": {
+              "type": "object",
+              "properties": { "name": { "type": "string" }, "parent": { "$ref": "#/$defs/person" } },
+              "required": ["name"],
+            },
+          },
+        });
+        let listed: Vec<_> = schema_properties(&schema)
+            .into_iter()
+            .map(|property| (property.path, property.type_, property.required))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("/name".to_string(), Some(json!("string")), false),
+                ("/parent".to_string(), Some(json!("object")), false),
+                ("/parent/name".to_string(), Some(json!("string")), true),
+                ("/parent/parent".to_string(), Some(json!("object")), false),
+            ]
+        );
+    }
+}
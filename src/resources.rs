@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use jsonschema::{Retrieve, Uri};
+use serde_json::Value;
+
+use crate::walk;
+
+/// Serves documents from memory by URI. Anything else fails to resolve, which
+/// keeps compilation offline and deterministic.
+#[derive(Default)]
+pub(crate) struct MapRetriever {
+    documents: HashMap<String, Value>,
+    /// URIs served so far, in order, shared with whoever asked for [`Self::retrieved`].
+    retrieved: Arc<Mutex<Vec<String>>>,
+}
+
+impl MapRetriever {
+    pub(crate) fn insert(&mut self, uri: &str, document: Value) {
+        self.documents
+            .insert(normalize_uri(uri).to_string(), document);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// A handle on the URIs this retriever serves, which stays readable after the
+    /// retriever has been moved into a compiler.
+    pub(crate) fn retrieved(&self) -> Arc<Mutex<Vec<String>>> {
+        self.retrieved.clone()
+    }
+}
+
+impl Retrieve for MapRetriever {
+    fn retrieve(
+        &self,
+        uri: &Uri<String>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let document = self
+            .documents
+            .get(normalize_uri(uri.as_str()))
+            .cloned()
+            .ok_or_else(|| format!("Resource '{}' is not available", uri.as_str()))?;
+        self.retrieved
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(uri.as_str().to_string());
+        Ok(document)
+    }
+}
+
+fn normalize_uri(uri: &str) -> &str {
+    uri.trim_end_matches('#')
+}
+
+/// Fetches the document at `uri` and, transitively, every document its external
+/// `$ref`s point to, so that compilation can then run offline from the returned
+/// retriever. Returns the root document too. Relative references resolve against
+/// the document's top-level `$id`, or the URI it was fetched from.
+pub(crate) fn prefetch(
+    uri: &str,
+    fetch: &mut dyn FnMut(&str) -> Result<Value, String>,
+) -> Result<(Value, MapRetriever), String> {
+    let mut retriever = MapRetriever::default();
+    let mut seen = HashSet::from([normalize_uri(uri).to_string()]);
+    let mut pending = vec![normalize_uri(uri).to_string()];
+    while let Some(next) = pending.pop() {
+        let document = fetch(&next)
+            .and_then(|document| match document {
+                Value::Object(_) | Value::Bool(_) => Ok(document),
+                _ => Err("expected a schema object or boolean".to_string()),
+            })
+            .map_err(|e| format!("Failed to fetch '{}': {}", next, e))?;
+        let base = document.get("$id").and_then(Value::as_str).unwrap_or(&next);
+        let base = referencing::uri::resolve_against(
+            &referencing::uri::from_str(&next)
+                .map_err(|e| e.to_string())?
+                .borrow(),
+            base,
+        )
+        .map_err(|e| e.to_string())?;
+        let mut references = Vec::new();
+        walk::for_each_subschema(&document, &mut |subschema, _, _| {
+            if let Some(reference) = subschema.get("$ref").and_then(Value::as_str) {
+                if !reference.starts_with('#') {
+                    references.push(reference.to_string());
+                }
+            }
+            true
+        });
+        for reference in references {
+            let target = referencing::uri::resolve_against(&base.borrow(), &reference)
+                .map_err(|e| e.to_string())?;
+            let target = target.as_str().split('#').next().unwrap_or_default();
+            if seen.insert(target.to_string()) {
+                pending.push(target.to_string());
+            }
+        }
+        retriever.insert(&next, document);
+    }
+    let root = retriever.documents[normalize_uri(uri)].clone();
+    Ok((root, retriever))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_prefetch_follows_relative_refs() {
+        let mut fetched = Vec::new();
+        let mut fetch = |uri: &str| {
+            fetched.push(uri.to_string());
+            match uri {
+                "https://example.com/schemas/root.json" => Ok(json!({
+                  "properties": {
+                    "a": { "$ref": "common.json#/$defs/name" },
+                    "b": { "$ref": "https://example.com/schemas/common.json" },
+                  },
+                })),
+                "https://example.com/schemas/common.json" => Ok(json!({ "$defs": { "name": {} } })),
+                _ => Err("not found".to_string()),
+            }
+        };
+        let (root, retriever) =
+            prefetch("https://example.com/schemas/root.json", &mut fetch).unwrap();
+        assert!(root.get("properties").is_some());
+        assert_eq!(retriever.documents.len(), 2);
+        assert_eq!(fetched.len(), 2);
+    }
+
+    #[test]
+    fn test_prefetch_rejects_non_schema_documents() {
+        let mut fetch = |_: &str| Ok(json!("<html>Not found</html>"));
+        let Err(error) = prefetch("https://example.com/root.json", &mut fetch) else {
+            panic!("expected a fetch error");
+        };
+        assert!(error.contains("expected a schema object or boolean"));
+    }
+}
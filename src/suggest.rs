@@ -0,0 +1,50 @@
+use serde_json::Value;
+
+/// Levenshtein distance between two strings, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Picks the allowed string closest to `value`, preferring earlier options on
+/// ties. Non-string options are ignored.
+pub(crate) fn closest_option<'a>(
+    value: &str,
+    options: impl IntoIterator<Item = &'a Value>,
+) -> Option<&'a str> {
+    options
+        .into_iter()
+        .filter_map(Value::as_str)
+        .min_by_key(|option| edit_distance(value, option))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_closest_option_skips_non_strings() {
+        let options = json!([1, "pending", null, "active"]);
+        let options = options.as_array().unwrap();
+        assert_eq!(closest_option("actve", options), Some("active"));
+        let numbers = json!([1, 2]);
+        assert_eq!(closest_option("x", numbers.as_array().unwrap()), None);
+    }
+}
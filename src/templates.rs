@@ -0,0 +1,159 @@
+use jsonschema::{
+    error::{TypeKind, ValidationErrorKind},
+    ValidationError,
+};
+use serde_json::{Map, Value};
+
+/// A re-renderable message: `{name}` placeholders in `template` are substituted
+/// from `params`.
+pub(crate) struct MessageTemplate {
+    pub template: String,
+    pub params: Map<String, Value>,
+}
+
+/// Derives the message template for `error`. The offending instance is passed
+/// as the `value` param unless `mask_values` is set; every other param comes
+/// from the schema. Kinds without a fixed phrasing use `{message}`.
+pub(crate) fn message_template(error: &ValidationError, mask_values: bool) -> MessageTemplate {
+    let mut params = Map::new();
+    let mut param = |name: &str, value: Value| {
+        params.insert(name.to_string(), value);
+    };
+    let template = match &error.kind {
+        ValidationErrorKind::Minimum { limit } => {
+            param("limit", limit.clone());
+            "{value} is less than the minimum of {limit}"
+        }
+        ValidationErrorKind::Maximum { limit } => {
+            param("limit", limit.clone());
+            "{value} is greater than the maximum of {limit}"
+        }
+        ValidationErrorKind::ExclusiveMinimum { limit } => {
+            param("limit", limit.clone());
+            "{value} is less than or equal to the minimum of {limit}"
+        }
+        ValidationErrorKind::ExclusiveMaximum { limit } => {
+            param("limit", limit.clone());
+            "{value} is greater than or equal to the maximum of {limit}"
+        }
+        ValidationErrorKind::MinLength { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} is shorter than {limit} characters"
+        }
+        ValidationErrorKind::MaxLength { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} is longer than {limit} characters"
+        }
+        ValidationErrorKind::MinItems { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} has less than {limit} items"
+        }
+        ValidationErrorKind::MaxItems { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} has more than {limit} items"
+        }
+        ValidationErrorKind::AdditionalItems { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} has more than {limit} items, and additional items are not allowed"
+        }
+        ValidationErrorKind::MinProperties { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} has less than {limit} properties"
+        }
+        ValidationErrorKind::MaxProperties { limit } => {
+            param("limit", Value::from(*limit));
+            "{value} has more than {limit} properties"
+        }
+        ValidationErrorKind::MultipleOf { multiple_of } => {
+            param("multiple_of", Value::from(*multiple_of));
+            "{value} is not a multiple of {multiple_of}"
+        }
+        ValidationErrorKind::Required { property } => {
+            param("property", property.clone());
+            "{property} is a required property"
+        }
+        ValidationErrorKind::Type { kind } => {
+            let expected = match kind {
+                TypeKind::Single(type_) => Value::from(type_.to_string()),
+                TypeKind::Multiple(types) => types.iter().map(|t| t.to_string()).collect(),
+            };
+            param("expected", expected);
+            "{value} is not of type {expected}"
+        }
+        ValidationErrorKind::Pattern { pattern } => {
+            param("pattern", Value::from(pattern.as_str()));
+            "{value} does not match {pattern}"
+        }
+        ValidationErrorKind::Format { format } => {
+            param("format", Value::from(format.as_str()));
+            "{value} is not a {format}"
+        }
+        ValidationErrorKind::Enum { options } => {
+            param("options", options.clone());
+            "{value} is not one of {options}"
+        }
+        ValidationErrorKind::Constant { expected_value } => {
+            param("expected", expected_value.clone());
+            "{expected} was expected"
+        }
+        ValidationErrorKind::Not { schema } => {
+            param("schema", schema.clone());
+            "{schema} is not allowed for {value}"
+        }
+        ValidationErrorKind::AdditionalProperties { unexpected } => {
+            param("unexpected", Value::from(unexpected.clone()));
+            "Additional properties are not allowed ({unexpected} unexpected)"
+        }
+        ValidationErrorKind::UnevaluatedProperties { unexpected } => {
+            param("unexpected", Value::from(unexpected.clone()));
+            "Unevaluated properties are not allowed ({unexpected} unexpected)"
+        }
+        ValidationErrorKind::UnevaluatedItems { unexpected } => {
+            param("unexpected", Value::from(unexpected.clone()));
+            "Unevaluated items are not allowed ({unexpected} unexpected)"
+        }
+        ValidationErrorKind::ContentEncoding { content_encoding } => {
+            param("encoding", Value::from(content_encoding.as_str()));
+            "{value} is not compliant with {encoding} content encoding"
+        }
+        ValidationErrorKind::ContentMediaType { content_media_type } => {
+            param("media_type", Value::from(content_media_type.as_str()));
+            "{value} is not compliant with {media_type} media type"
+        }
+        ValidationErrorKind::AnyOf => {
+            "{value} is not valid under any of the schemas listed in the 'anyOf' keyword"
+        }
+        ValidationErrorKind::OneOfNotValid => {
+            "{value} is not valid under any of the schemas listed in the 'oneOf' keyword"
+        }
+        ValidationErrorKind::OneOfMultipleValid => {
+            "{value} is valid under more than one of the schemas listed in the 'oneOf' keyword"
+        }
+        ValidationErrorKind::Contains => "None of {value} are valid under the given schema",
+        ValidationErrorKind::FalseSchema => "False schema does not allow {value}",
+        ValidationErrorKind::UniqueItems => "{value} has non-unique elements",
+        ValidationErrorKind::Custom { message } => {
+            param("message", Value::from(message.as_str()));
+            "{message}"
+        }
+        ValidationErrorKind::BacktrackLimitExceeded { .. }
+        | ValidationErrorKind::FromUtf8 { .. }
+        | ValidationErrorKind::PropertyNames { .. }
+        | ValidationErrorKind::Referencing(..) => {
+            let message = if mask_values {
+                error.masked().to_string()
+            } else {
+                error.to_string()
+            };
+            param("message", Value::from(message));
+            "{message}"
+        }
+    };
+    if !mask_values && template.contains("{value}") {
+        params.insert("value".to_string(), error.instance.clone().into_owned());
+    }
+    MessageTemplate {
+        template: template.to_string(),
+        params,
+    }
+}
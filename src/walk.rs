@@ -0,0 +1,475 @@
+use std::collections::HashSet;
+
+use jsonschema::paths::write_escaped_str;
+use serde_json::Value;
+
+/// A subschema together with the part of the instance it applies to.
+pub(crate) struct Applied<'a> {
+    pub schema: &'a Value,
+    pub instance: &'a Value,
+    pub schema_path: String,
+    pub instance_path: String,
+}
+
+/// Resolves a same-document `$ref` (`#` or `#/json/pointer`) against `root`.
+pub(crate) fn resolve_local_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+/// Appends an escaped JSON Pointer segment to `path`.
+pub(crate) fn join(path: &str, segment: &str) -> String {
+    let mut joined = String::with_capacity(path.len() + segment.len() + 1);
+    joined.push_str(path);
+    joined.push('/');
+    write_escaped_str(&mut joined, segment);
+    joined
+}
+
+/// Percent-encodes a JSON Pointer for use as a URI fragment, leaving the
+/// characters RFC 3986 allows in a fragment as they are.
+pub(crate) fn pointer_fragment(pointer: &str) -> String {
+    let mut fragment = String::with_capacity(pointer.len());
+    for byte in pointer.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/?".contains(&byte) {
+            fragment.push(byte as char);
+        } else {
+            fragment.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    fragment
+}
+
+/// Visits every object subschema that applies to some part of `instance`,
+/// following `properties`, `additionalProperties`, `items`, `prefixItems`,
+/// `allOf` and same-document `$ref`s. Conditional and alternative branches
+/// (`anyOf`, `oneOf`, `if`) are not followed since they may not apply.
+/// `visit` returns whether to descend below the given node.
+pub(crate) fn for_each_applied<'a>(
+    root: &'a Value,
+    instance: &'a Value,
+    visit: &mut dyn FnMut(&Applied<'a>) -> bool,
+) {
+    let mut seen = HashSet::new();
+    let start = Applied {
+        schema: root,
+        instance,
+        schema_path: String::new(),
+        instance_path: String::new(),
+    };
+    walk(root, start, visit, &mut seen);
+}
+
+fn walk<'a>(
+    root: &'a Value,
+    node: Applied<'a>,
+    visit: &mut dyn FnMut(&Applied<'a>) -> bool,
+    seen: &mut HashSet<(*const Value, *const Value)>,
+) {
+    let Value::Object(schema) = node.schema else {
+        return;
+    };
+    // A `$ref` cycle that doesn't descend into the instance would loop forever.
+    if !seen.insert((node.schema as *const Value, node.instance as *const Value)) {
+        return;
+    }
+    if !visit(&node) {
+        return;
+    }
+    let subschema =
+        |keyword: &str, schema: &'a Value, instance: &'a Value, instance_path| Applied {
+            schema,
+            instance,
+            schema_path: join(&node.schema_path, keyword),
+            instance_path,
+        };
+    if let Some(target) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| resolve_local_ref(root, reference))
+    {
+        walk(
+            root,
+            subschema("$ref", target, node.instance, node.instance_path.clone()),
+            visit,
+            seen,
+        );
+    }
+    if let Some(branches) = schema.get("allOf").and_then(Value::as_array) {
+        for (index, branch) in branches.iter().enumerate() {
+            let mut applied = subschema("allOf", branch, node.instance, node.instance_path.clone());
+            applied.schema_path = join(&applied.schema_path, &index.to_string());
+            walk(root, applied, visit, seen);
+        }
+    }
+    match node.instance {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, value) in object {
+                let instance_path = join(&node.instance_path, key);
+                if let Some(property) = properties.and_then(|properties| properties.get(key)) {
+                    let mut applied = subschema("properties", property, value, instance_path);
+                    applied.schema_path = join(&applied.schema_path, key);
+                    walk(root, applied, visit, seen);
+                } else if let Some(additional) = schema.get("additionalProperties") {
+                    walk(
+                        root,
+                        subschema("additionalProperties", additional, value, instance_path),
+                        visit,
+                        seen,
+                    );
+                }
+            }
+        }
+        Value::Array(items) => {
+            let prefix = schema
+                .get("prefixItems")
+                .or_else(|| schema.get("items").filter(|items| items.is_array()))
+                .and_then(Value::as_array);
+            let prefix_keyword = if schema.contains_key("prefixItems") {
+                "prefixItems"
+            } else {
+                "items"
+            };
+            let prefix_len = prefix.map_or(0, Vec::len);
+            for (index, item) in items.iter().enumerate() {
+                let instance_path = join(&node.instance_path, &index.to_string());
+                if let Some(positional) = prefix.and_then(|prefix| prefix.get(index)) {
+                    let mut applied = subschema(prefix_keyword, positional, item, instance_path);
+                    applied.schema_path = join(&applied.schema_path, &index.to_string());
+                    walk(root, applied, visit, seen);
+                } else if index >= prefix_len {
+                    let rest = if prefix_keyword == "items" && prefix.is_some() {
+                        schema
+                            .get("additionalItems")
+                            .map(|rest| ("additionalItems", rest))
+                    } else {
+                        schema
+                            .get("items")
+                            .filter(|items| !items.is_array())
+                            .map(|rest| ("items", rest))
+                    };
+                    if let Some((keyword, rest)) = rest {
+                        walk(
+                            root,
+                            subschema(keyword, rest, item, instance_path),
+                            visit,
+                            seen,
+                        );
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Finds the subschema governing the value at `instance_pointer`, without the
+/// instance itself, and returns its JSON Pointer within `root`. Each segment is
+/// looked up in `properties`, then positional/rest `items`, then
+/// `additionalProperties`, falling back to a same-document `$ref`. Subschemas
+/// reached only through `allOf`, `anyOf`, `if` or `patternProperties` aren't found.
+pub(crate) fn subschema_pointer(root: &Value, instance_pointer: &str) -> Option<String> {
+    let mut path = String::new();
+    for segment in instance_pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        path = child_subschema(root, path, &segment)?;
+    }
+    Some(path)
+}
+
+fn child_subschema(root: &Value, mut path: String, segment: &str) -> Option<String> {
+    // Bounds `$ref` chains, which may be cyclic.
+    for _ in 0..32 {
+        let schema = root.pointer(&path)?.as_object()?;
+        if schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .is_some_and(|properties| properties.contains_key(segment))
+        {
+            return Some(join(&join(&path, "properties"), segment));
+        }
+        if let Ok(index) = segment.parse::<usize>() {
+            let (prefix_keyword, rest_keyword) = if schema.contains_key("prefixItems") {
+                ("prefixItems", "items")
+            } else if schema.get("items").is_some_and(Value::is_array) {
+                ("items", "additionalItems")
+            } else {
+                ("prefixItems", "items")
+            };
+            let prefix_len = schema
+                .get(prefix_keyword)
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            if index < prefix_len {
+                return Some(join(&join(&path, prefix_keyword), segment));
+            }
+            if schema.contains_key(rest_keyword) {
+                return Some(join(&path, rest_keyword));
+            }
+        }
+        if schema.contains_key("additionalProperties") {
+            return Some(join(&path, "additionalProperties"));
+        }
+        let reference = schema.get("$ref").and_then(Value::as_str)?;
+        path = reference.strip_prefix('#')?.to_string();
+    }
+    None
+}
+
+/// Looks up the node at an evaluation `schema_path`, such as an issue's
+/// `schema_path`, following each `$ref` segment through its same-document
+/// reference. Returns `None` if the path crosses a remote `$ref`.
+pub(crate) fn at_schema_path<'a>(root: &'a Value, schema_path: &str) -> Option<&'a Value> {
+    let mut node = root;
+    for segment in schema_path.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        node = if segment == "$ref" {
+            resolve_local_ref(root, node.get("$ref")?.as_str()?)?
+        } else {
+            match node {
+                Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+                _ => node.get(&segment)?,
+            }
+        };
+    }
+    Some(node)
+}
+
+/// The object subschemas that apply to the value at `instance_pointer`, in the
+/// order [`for_each_applied`] reaches them. When `instance` has no value there,
+/// the subschema found by [`subschema_pointer`] and the local `$ref`s and
+/// `allOf` branches it leads to are returned instead.
+pub(crate) fn applied_at<'a>(
+    root: &'a Value,
+    instance: &'a Value,
+    instance_pointer: &str,
+) -> Vec<&'a Value> {
+    let mut applied = Vec::new();
+    if instance.pointer(instance_pointer).is_some() {
+        for_each_applied(root, instance, &mut |node| {
+            if node.instance_path == instance_pointer {
+                applied.push(node.schema);
+            }
+            // Paths below the target can't lead back to it.
+            instance_pointer.starts_with(&node.instance_path)
+        });
+        return applied;
+    }
+    let Some(start) =
+        subschema_pointer(root, instance_pointer).and_then(|path| root.pointer(&path))
+    else {
+        return applied;
+    };
+    let mut pending = vec![start];
+    while let Some(schema) = pending.pop() {
+        if !schema.is_object() || applied.iter().any(|seen| std::ptr::eq(*seen, schema)) {
+            continue;
+        }
+        applied.push(schema);
+        let branches = schema.get("allOf").and_then(Value::as_array);
+        pending.extend(branches.into_iter().flatten().rev());
+        if let Some(target) = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| resolve_local_ref(root, reference))
+        {
+            pending.push(target);
+        }
+    }
+    applied
+}
+
+/// Keywords whose value is a single subschema.
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "additionalItems",
+    "additionalProperties",
+    "contains",
+    "else",
+    "if",
+    "items",
+    "not",
+    "propertyNames",
+    "then",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+];
+/// Keywords whose value is an array of subschemas.
+const ARRAY_KEYWORDS: &[&str] = &["allOf", "anyOf", "items", "oneOf", "prefixItems"];
+/// Keywords whose value maps names to subschemas.
+const MAP_KEYWORDS: &[&str] = &[
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+    "patternProperties",
+    "properties",
+];
+
+/// Visits `schema` and every subschema nested in it, in document order, with its
+/// JSON Pointer, without looking at any instance. `$ref`s are not followed, so
+/// each subschema is visited exactly once. `visit` receives the nesting depth
+/// (the root is 0) and returns whether to descend below the given subschema.
+pub(crate) fn for_each_subschema<'a>(
+    schema: &'a Value,
+    visit: &mut dyn FnMut(&'a Value, &str, usize) -> bool,
+) {
+    let mut stack = vec![(schema, String::new(), 0)];
+    while let Some((schema, path, depth)) = stack.pop() {
+        if !visit(schema, &path, depth) {
+            continue;
+        }
+        let Value::Object(object) = schema else {
+            continue;
+        };
+        let mut children = Vec::new();
+        for (keyword, value) in object {
+            let keyword_path = join(&path, keyword);
+            let keyword = keyword.as_str();
+            match value {
+                Value::Array(items) if ARRAY_KEYWORDS.contains(&keyword) => {
+                    for (index, item) in items.iter().enumerate() {
+                        children.push((item, join(&keyword_path, &index.to_string())));
+                    }
+                }
+                Value::Object(map) if MAP_KEYWORDS.contains(&keyword) => {
+                    for (name, item) in map {
+                        children.push((item, join(&keyword_path, name)));
+                    }
+                }
+                // Draft 4-7 `dependencies` mixes subschemas with property lists.
+                Value::Object(map) if keyword == "dependencies" => {
+                    for (name, item) in map.iter().filter(|(_, item)| !item.is_array()) {
+                        children.push((item, join(&keyword_path, name)));
+                    }
+                }
+                Value::Object(_) | Value::Bool(_) if SCHEMA_KEYWORDS.contains(&keyword) => {
+                    children.push((value, keyword_path));
+                }
+                _ => {}
+            }
+        }
+        // Reversed so that popping the stack yields document order.
+        stack.extend(
+            children
+                .into_iter()
+                .rev()
+                .map(|(child, child_path)| (child, child_path, depth + 1)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn applied_paths(schema: &Value, instance: &Value) -> Vec<(String, String)> {
+        let mut paths = Vec::new();
+        for_each_applied(schema, instance, &mut |node| {
+            paths.push((node.instance_path.clone(), node.schema_path.clone()));
+            true
+        });
+        paths
+    }
+
+    #[test]
+    fn test_walk_follows_properties_items_and_refs() {
+        let schema = json!({
+          "$defs": { "tag": { "type": "string" } },
+          "properties": {
+            "tags": { "items": { "$ref": "#/$defs/tag" } },
+            "pair": { "prefixItems": [{ "type": "integer" }], "items": false },
+          },
+          "additionalProperties": { "type": "number" },
+        });
+        let instance = json!({ "tags": ["a"], "pair": [1, 2], "extra": 1 });
+        let paths = applied_paths(&schema, &instance);
+        assert!(paths.contains(&("/tags/0".into(), "/properties/tags/items/$ref".into())));
+        assert!(paths.contains(&("/pair/0".into(), "/properties/pair/prefixItems/0".into())));
+        assert!(paths.contains(&("/extra".into(), "/additionalProperties".into())));
+        // `false` is not an object schema, so it isn't visited.
+        assert!(!paths
+            .iter()
+            .any(|(instance_path, _)| instance_path == "/pair/1"));
+    }
+
+    #[test]
+    fn test_walk_terminates_on_self_reference() {
+        let schema = json!({ "allOf": [{ "$ref": "#" }] });
+        assert_eq!(applied_paths(&schema, &json!(1)).len(), 2);
+    }
+
+    #[test]
+    fn test_for_each_subschema_visits_keyword_positions_only() {
+        let schema = json!({
+          "properties": {
+            "items": { "type": "array", "items": [{ "const": 1 }] },
+            "if": { "not": { "type": "null" } },
+          },
+          "anyOf": [true],
+          "enum": [{ "properties": {} }],
+        });
+        let mut visited = Vec::new();
+        for_each_subschema(&schema, &mut |_, path, depth| {
+            visited.push((path.to_string(), depth));
+            true
+        });
+        assert_eq!(
+            visited,
+            [
+                ("".to_string(), 0),
+                ("/properties/items".to_string(), 1),
+                ("/properties/items/items/0".to_string(), 2),
+                ("/properties/if".to_string(), 1),
+                ("/properties/if/not".to_string(), 2),
+                ("/anyOf/0".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subschema_pointer_follows_properties_items_and_refs() {
+        let schema = json!({
+          "properties": {
+            "order": { "$ref": "#/$defs/order" },
+            "a~b": { "type": "string" },
+          },
+          "$defs": {
+            "order": {
+              "properties": { "lines": { "type": "array", "items": { "$ref": "#/$defs/line" } } },
+            },
+            "line": { "prefixItems": [{ "type": "string" }], "items": { "type": "number" } },
+          },
+        });
+        let pointer = |instance_pointer| subschema_pointer(&schema, instance_pointer);
+        assert_eq!(pointer("").as_deref(), Some(""));
+        assert_eq!(pointer("/a~0b").as_deref(), Some("/properties/a~0b"));
+        assert_eq!(
+            pointer("/order/lines/3").as_deref(),
+            Some("/$defs/order/properties/lines/items")
+        );
+        assert_eq!(
+            pointer("/order/lines/3/0").as_deref(),
+            Some("/$defs/line/prefixItems/0")
+        );
+        assert_eq!(
+            pointer("/order/lines/3/5").as_deref(),
+            Some("/$defs/line/items")
+        );
+        assert_eq!(pointer("/missing"), None);
+    }
+
+    #[test]
+    fn test_applied_at_with_and_without_instance_value() {
+        let schema = json!({
+          "$defs": { "named": { "properties": { "name": { "type": "string" } } } },
+          "properties": {
+            "user": { "$ref": "#/$defs/named", "allOf": [{ "required": ["name"] }] },
+          },
+        });
+        let (present, absent) = (json!({ "user": {} }), json!({}));
+        assert_eq!(applied_at(&schema, &present, "/user").len(), 3);
+        assert_eq!(applied_at(&schema, &absent, "/user").len(), 3);
+        assert_eq!(applied_at(&schema, &absent, "/user/name").len(), 1);
+    }
+}
@@ -39,12 +39,29 @@ export type ValidationErrorCode =
     | 'unevaluated_items'
     | 'unevaluated_properties'
     | 'duplicate_items'
-    | 'schema_reference_error';
+    | 'schema_reference_error'
+    | 'instance_too_large'
+    | 'invalid_json'
+    | 'readonly_write'
+    | 'writeonly_read'
+    | 'operation_limit_exceeded'
+    | 'schema_too_large'
+    | 'invalid_csv'
+    | 'dependent_required'
+    | 'dependent_schema';
 
 /**
  * Describes the structure of a single validation error.
  */
 export interface ValidationIssue {
+  /**
+   * A stable key for the issue, derived from `path`, `schema_path` and `code` (plus
+   * the property name for `missing_property`, `dependent_required` and
+   * `dependent_schema`). It does not depend on the offending
+   * value or the message, so it stays the same across revalidations.
+   * @example "9f3c1a2b7d4e5f60"
+   */
+  id: string;
   /**
    * A JSON Pointer (RFC 6901) to the location in the instance where the error occurred.
    * @example "/profile/age"
@@ -58,8 +75,162 @@ export interface ValidationIssue {
   message: string;
   /**
    * A stable, machine-readable code representing the specific type of validation error.
+   * With the `codes_from_comment` option, it may also be a code the schema defines.
+   */
+  code: ValidationErrorCode | (string & {});
+  /**
+   * A compact numeric form of `code`. Ids are never reassigned or reused across
+   * versions; `0` means the code has no id.
+   * - `1`: `too_small`
+   * - `2`: `invalid_type`
+   * - `3`: `missing_property`
+   * - `4`: `too_large`
+   * - `5`: `too_short`
+   * - `6`: `too_long`
+   * - `7`: `pattern_mismatch`
+   * - `8`: `enum_mismatch`
+   * - `9`: `const_mismatch`
+   * - `10`: `format_mismatch`
+   * - `11`: `additional_properties`
+   * - `12`: `additional_items`
+   * - `13`: `too_few_items`
+   * - `14`: `too_many_items`
+   * - `15`: `duplicate_items`
+   * - `16`: `too_few_properties`
+   * - `17`: `too_many_properties`
+   * - `18`: `exclusive_min`
+   * - `19`: `exclusive_max`
+   * - `20`: `not_a_multiple`
+   * - `21`: `any_of_mismatch`
+   * - `22`: `one_of_no_match`
+   * - `23`: `one_of_multiple_matches`
+   * - `24`: `negated_schema_match`
+   * - `25`: `disallowed_value`
+   * - `26`: `no_match_in_contains`
+   * - `27`: `invalid_property_name`
+   * - `28`: `unevaluated_items`
+   * - `29`: `unevaluated_properties`
+   * - `30`: `invalid_content_encoding`
+   * - `31`: `invalid_media_type`
+   * - `32`: `invalid_utf8`
+   * - `33`: `regex_backtrack_limit`
+   * - `34`: `custom_error`
+   * - `35`: `schema_reference_error`
+   * - `36`: `invalid_schema`
+   * - `37`: `instance_too_large`
+   * - `38`: `invalid_json`
+   * - `39`: `readonly_write`
+   * - `40`: `writeonly_read`
+   * - `41`: `operation_limit_exceeded`
+   * - `42`: `schema_too_large`
+   * - `43`: `invalid_csv`
+   * - `44`: `dependent_required`
+   * - `45`: `dependent_schema`
+   */
+  code_id: number;
+  /**
+   * A JSON Pointer to the schema keyword that produced the error.
+   * @example "/properties/profile/properties/age/minimum"
+   */
+  schema_path: string;
+  /**
+   * For `too_short`/`too_long` errors on strings, the user-perceived length of the
+   * value in grapheme clusters. The `minLength`/`maxLength` limits themselves count
+   * Unicode code points, so an emoji with a skin tone modifier counts as 2 there but 1 here.
+   */
+  grapheme_count?: number;
+  /**
+   * For `enum_mismatch` and `const_mismatch` errors on a string, the allowed string
+   * value closest to it by edit distance, for "did you mean" hints.
+   * @example "active"
+   */
+  suggestion?: string;
+  /**
+   * `message` with `{name}` placeholders, so it can be localized or restyled
+   * without parsing the prose.
+   * @example "{value} is less than the minimum of {limit}"
+   */
+  message_template: string;
+  /**
+   * The substitutions for `message_template`, as JSON values. `value` (the
+   * offending instance value) is omitted when values are masked.
+   * @example { "value": 12, "limit": 18 }
+   */
+  message_params: Record<string, any>;
+  /** For a summary issue produced by `collapse_arrays`, how many items failed. */
+  count?: number;
+  /** For a summary issue produced by `collapse_arrays`, the indices of the failing items. */
+  indices?: number[];
+  /**
+   * For issues raised inside a `$ref`'d schema, where the keyword lives: the
+   * referenced document's URI with a JSON Pointer fragment into it. Only the
+   * document's URI when the path crosses several references.
+   * @example "https://example.com/address.json#/properties/zip/minLength"
+   */
+  absolute_keyword_location?: string;
+  /**
+   * For `too_few_items`, `too_few_properties` and `too_short`: how many more
+   * items, properties or code points are needed, e.g. for "add 2 more items".
+   */
+  shortfall?: number;
+  /**
+   * For `too_many_items`, `too_many_properties` and `too_long`: how many must be
+   * removed.
+   */
+  excess?: number;
+  /**
+   * For `one_of_multiple_matches`, the indices of the `oneOf` branches the value
+   * matched, e.g. `[0, 2]`. Absent for a `oneOf` reached through `$ref`.
+   */
+  matched_branches?: number[];
+  /**
+   * For `pattern_mismatch`, and `invalid_property_name` caused by a pattern, the
+   * regular expression from the schema.
+   * @example "^[0-9]{5}$"
+   */
+  pattern?: string;
+  /**
+   * Number of segments in the instance path: `0` for the root, `2` for
+   * `/profile/age`. Useful for indenting nested issues.
+   */
+  depth: number;
+  /**
+   * With `path_format: 'dot'`, `path` spelled as `tags[0]`. `path` itself stays
+   * a JSON Pointer.
    */
-  code: ValidationErrorCode;
+  display_path?: string;
+  /**
+   * With the `doc_base_url` option, a link to the docs for the failed keyword,
+   * e.g. `https://docs.example.com/errors#minimum`.
+   */
+  doc_url?: string;
+  /**
+   * With the `include_both_messages` option, `message` with instance values
+   * redacted, e.g. to show users while `message` is logged in full.
+   */
+  masked_message?: string;
+  /**
+   * From `validateWithContext`, the instance subtree around the failure, with
+   * leaves nulled when values are masked.
+   */
+  context_value?: any;
+  /**
+   * For `negated_schema_match`, the `not` subschema the value matched, to explain
+   * what it must not be.
+   */
+  negated_schema?: JSONSchema | boolean;
+  /**
+   * The JSON type of the failing value: `string`, `number`, `boolean`, `null`,
+   * `array` or `object`. Unlike the expected type in the message, it's set for
+   * every keyword failure, and kept when values are masked.
+   */
+  instance_type?: 'string' | 'number' | 'boolean' | 'null' | 'array' | 'object';
+  /**
+   * With the `include_sibling_keys` option, the keys the object does have, on
+   * `missing_property` and `additional_properties` issues. Values are never
+   * included, even without masking.
+   */
+  sibling_keys?: string[];
 }
 
 /**
@@ -67,6 +238,242 @@ export interface ValidationIssue {
  */
 export type JSONSchema = Record<string, any>;
 
+/**
+ * Options accepted by the validator. A bare boolean is treated as `mask_values`.
+ */
+export interface ValidatorOptions {
+  /**
+   * If true, potentially sensitive data will be redacted from error messages.
+   * @default false
+   */
+  mask_values?: boolean;
+  /**
+   * A JSON Pointer prepended to every issue `path`, useful when the instance is a
+   * fragment of a larger document. A root-level error gets exactly this path, less
+   * any trailing `/`.
+   * @example "/order"
+   */
+  base_path?: string;
+  /**
+   * Rejects instances nested deeper than this many levels with a single
+   * `instance_too_large` issue, without running schema validation.
+   */
+  max_depth?: number;
+  /**
+   * Rejects instances containing more than this many values (objects, arrays and
+   * scalars) with a single `instance_too_large` issue, without running schema validation.
+   */
+  max_nodes?: number;
+  /**
+   * A custom meta-schema identified by its `$id`. Schemas whose `$schema` names it
+   * compile against it, and must conform to it or the constructor throws `invalid_schema`.
+   */
+  meta_schema?: JSONSchema;
+  /**
+   * Asserts the `date`, `time` and `date-time` formats (as `format_mismatch`) even on
+   * drafts where `format` is only an annotation, without enabling costlier formats
+   * such as `uri` or `email`. Drafts 4, 6 and 7 already assert all formats.
+   * @default false
+   */
+  enforce_date_formats?: boolean;
+  /**
+   * Replaces the strict RFC `email` and `hostname` checks with pragmatic ones, e.g.
+   * accepting `first..last@example.com` or underscores in host names. Whether
+   * `format` is asserted at all still depends on the draft.
+   * @default false
+   */
+  lax_email?: boolean;
+  /**
+   * The direction of the exchange. With `"write"`, properties whose schema is
+   * `readOnly` are reported as `readonly_write`; with `"read"`, `writeOnly`
+   * properties are reported as `writeonly_read`.
+   */
+  context?: 'read' | 'write';
+  /**
+   * Re-checks `not_a_multiple` failures using exact decimal arithmetic, so binary
+   * rounding no longer rejects values such as `0.3` against `multipleOf: 0.1`.
+   * `multipleOf` inside `anyOf`/`oneOf`/`not` branches still uses floating point.
+   * @default false
+   */
+  decimal_multiple_of?: boolean;
+  /**
+   * Applies the `"x-trim": true` and `"x-lowercase": true` schema annotations to the
+   * string values they govern before validating. `validate` then returns the
+   * normalized copy instead of the original instance.
+   * @default false
+   */
+  normalize?: boolean;
+  /**
+   * In functions that take JSON text (`validateStr`), reject an instance whose
+   * objects repeat a key with an `invalid_json` issue naming the key, instead of
+   * silently keeping the last value.
+   * @default false
+   */
+  reject_duplicate_keys?: boolean;
+  /**
+   * Adds a `timing` breakdown to the result of `validateResult`.
+   * @default false
+   */
+  profile?: boolean;
+  /**
+   * Replaces issues that repeat across the items of an array (same constraint,
+   * same code) with one summary issue at the array path, e.g. "3 of 50 items are
+   * invalid: ...", carrying `count` and `indices`. Leave it off to get per-item issues.
+   * @default false
+   */
+  collapse_arrays?: boolean;
+  /**
+   * Caps the number of errors per validation: after this many errors have been
+   * produced, validation stops and returns the issues gathered so far plus an
+   * `operation_limit_exceeded` issue. Errors are counted before `only_codes`,
+   * `ignore_codes` and `structural_only` apply, and the marker issue is never
+   * filtered out. This does not interrupt a single long-running check such as a
+   * pathological regular expression.
+   */
+  operation_limit?: number;
+  /**
+   * Compiles the schema under this draft instead of detecting it from `$schema`.
+   */
+  draft?: SchemaDraft;
+  /**
+   * Compiles schemas that don't declare `$schema` as Draft 4, so legacy schemas
+   * using `id`, `#/definitions/...` refs and boolean `exclusiveMinimum` work.
+   * `draft` takes precedence.
+   */
+  legacy_definitions?: boolean;
+  /**
+   * Uses the failing subschema's `errorMessage` entry for the failed keyword as
+   * the issue message, like `ajv-errors`:
+   * `{ minimum: 18, errorMessage: { minimum: 'Too young' } }`. Keywords without
+   * an entry keep the default message.
+   */
+  use_schema_messages?: boolean;
+  /**
+   * Sets each issue's `doc_url` to this URL plus `#` and the failed keyword.
+   */
+  doc_base_url?: string;
+  /**
+   * On a `WasmValidator`, remembers the result for each distinct instance (a
+   * bounded number of them) and returns it for an identical instance without
+   * revalidating, e.g. for resubmitted payloads. Instances are compared by their
+   * JSON text, so key order matters.
+   * @default false
+   */
+  memoize?: boolean;
+  /**
+   * Fills in both an unmasked `message` and a redacted `masked_message` in one
+   * pass. `message` is unmasked even when `mask_values` is set.
+   * @default false
+   */
+  include_both_messages?: boolean;
+  /**
+   * Validates object properties set to `null` as if they were absent, for APIs
+   * where an explicit `null` means "not provided": a required property that is
+   * `null` is reported as `missing_property` rather than `invalid_type`.
+   * @default false
+   */
+  treat_null_as_missing?: boolean;
+  /**
+   * Checks only the shape of the instance: keeps `invalid_type`,
+   * `missing_property`, `dependent_required`, `additional_properties`,
+   * `unevaluated_properties`, `additional_items` and `unevaluated_items` issues
+   * and drops those about values, such as `too_small` or `pattern_mismatch`.
+   * @default false
+   */
+  structural_only?: boolean;
+  /**
+   * Lowercases the first letter of the messages this library writes itself,
+   * such as instance limit or CSV messages, and drops their trailing period, so
+   * they slot into your own sentences. A leading acronym is kept. Messages
+   * describing a keyword failure, and those from `use_schema_messages`, are left
+   * as is.
+   * @default false
+   */
+  normalize_messages?: boolean;
+  /**
+   * On a `WasmValidator`, records the URIs of the external documents that
+   * compiling the schema resolved `$ref`s to, for `refReport()`.
+   * @default false
+   */
+  record_refs?: boolean;
+  /**
+   * Sets `sibling_keys` on `missing_property` and `additional_properties`
+   * issues to the keys present on the object, to help spot typos such as
+   * `usernam` for `username`.
+   * @default false
+   */
+  include_sibling_keys?: boolean;
+  /**
+   * Truncates longer `message`s (and `masked_message`s) to this many
+   * characters, ending in `…`, e.g. for messages listing a huge `enum`. Codes
+   * and the structured fields such as `message_params` keep the full details.
+   * `0` leaves messages empty.
+   */
+  max_message_len?: number;
+  /**
+   * Uses the rest of a `$comment` starting with `code:` on the subschema that
+   * failed as the issue's `code`, e.g. `AGE_TOO_LOW` for
+   * `{ "minimum": 18, "$comment": "code:AGE_TOO_LOW" }`. Such codes have a
+   * `code_id` of 0, and `ignore_codes`/`only_codes` match them. Other issues keep
+   * the built-in code.
+   * @default false
+   */
+  codes_from_comment?: boolean;
+  /**
+   * Drops issues with these codes, e.g. `['format_mismatch']`. Instance size limit
+   * violations are always reported.
+   */
+  ignore_codes?: ValidationErrorCode[];
+  /**
+   * Keeps only issues with these codes. Applied together with `ignore_codes`.
+   */
+  only_codes?: ValidationErrorCode[];
+  /**
+   * Treats every object described by `properties` or `patternProperties` as
+   * closed, as if it had `additionalProperties: false`: undeclared properties are
+   * reported as `additional_properties`. Declarations are combined across the
+   * subschemas that apply through `allOf` and `$ref`; objects without any
+   * declared properties, such as free-form maps, stay open.
+   */
+  closed?: boolean;
+  /**
+   * Accepts strings that parse as numbers, such as values from HTML number
+   * inputs, where the schema expects a `number` or `integer` and doesn't allow a
+   * `string`. The parsed number is what gets validated, so `"17"` still fails
+   * `minimum: 18`. The instance itself is not modified.
+   */
+  numeric_strings?: boolean;
+  /**
+   * With `'dot'`, each issue also gets a `display_path` spelled `tags[0]`-style,
+   * as used by form libraries. `path` and `id` always use the JSON Pointer
+   * (`'pointer'`, the default).
+   */
+  path_format?: 'pointer' | 'dot';
+  /**
+   * Custom keywords, by name. Wherever a schema uses the keyword, its function is
+   * called with the instance value and the keyword's value from the schema, and
+   * returns `true`/`false` or `{ valid, message }`. A failure, or an exception
+   * thrown by the function, is reported as a `custom_error` issue with that
+   * message. Custom keywords take precedence over built-in ones of the same name.
+   * @example { divisibleByField: (instance, { field, by }) => instance[field] % instance[by] === 0 }
+   */
+  keywords?: Record<string, CustomKeywordCheck>;
+}
+
+/** Checks an instance against a custom keyword; see `ValidatorOptions.keywords`. */
+export type CustomKeywordCheck = (
+  instance: any,
+  keywordValue: any
+) => boolean | { valid: boolean; message?: string };
+
+/** A JSON Schema draft name. */
+export type SchemaDraft =
+  | 'draft4'
+  | 'draft6'
+  | 'draft7'
+  | 'draft2019-09'
+  | 'draft2020-12';
+
 /**
  * An Error subclass that is thrown when validation fails.
  * It contains an array of `ValidationIssue` objects.
@@ -85,36 +492,1078 @@ export class WasmValidator {
   /**
    * Creates and compiles a new validator instance.
    * @param schema The JSON Schema object to validate against.
-   * @param mask_values If true, potentially sensitive data will be redacted from error messages. Defaults to `false`.
-   * @throws {ValidationError} Throws if the schema itself is invalid.
+   * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+   * @throws {ValidationError} Throws if the schema itself is invalid, with a
+   * `schema_reference_error` issue if a `$ref`, `$dynamicRef` or `$recursiveRef`
+   * doesn't resolve and `invalid_schema` otherwise.
+   */
+  constructor(schema: JSONSchema, options?: boolean | ValidatorOptions);
+
+  /**
+   * Loads and compiles the schema at `uri`. `fetch` is called with the root URI,
+   * then with the URI of every document an external `$ref` points to (resolved
+   * against the referring document's `$id` or URI), and must synchronously return
+   * the parsed schema, e.g. from a preloaded cache.
+   * @param uri Absolute URI of the root schema.
+   * @param fetch Returns the schema for a URI, or throws if it is unavailable.
+   * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+   * @throws {ValidationError} Throws with an `invalid_schema` issue if `fetch` throws,
+   * returns a Promise or anything but an object or boolean, or the schema is invalid.
+   */
+  static fromUri(
+    uri: string,
+    fetch: (uri: string) => JSONSchema,
+    options?: boolean | ValidatorOptions
+  ): WasmValidator;
+
+  /**
+   * Compiles a schema without throwing on an invalid one, so callers can branch
+   * on `ok` instead of using try/catch.
+   * @param schema The JSON Schema object to validate against.
+   * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+   * @returns `{ ok: true, validator }`, or `{ ok: false, issues }` with an
+   * `invalid_schema` issue.
    */
-  constructor(schema: JSONSchema, mask_values?: boolean);
+  static tryNew(
+    schema: JSONSchema,
+    options?: boolean | ValidatorOptions
+  ): { ok: true; validator: WasmValidator } | { ok: false; issues: ValidationIssue[] };
 
   /**
    * Validates a JSON object instance against the pre-compiled schema.
    * @param instance The JSON instance to validate.
-   * @returns The validated instance if validation is successful.
+   * @returns The validated instance if validation is successful, normalized if the
+   * `normalize` option is set.
    * @throws {ValidationError} Throws if the instance is invalid.
    */
   validate<T extends any>(instance: T): T;
+
+  /**
+   * Reports which external documents compiling the schema resolved `$ref`s to,
+   * e.g. to audit what a `fromUri` schema depends on.
+   * @returns `{ used_refs, refs }` when the `record_refs` option is set, and
+   * `undefined` otherwise.
+   */
+  refReport(): RefReport | undefined;
+
+  /**
+   * Releases the memory held by the compiled schema immediately, rather than
+   * waiting for garbage collection. Subsequent `validate` calls throw.
+   */
+  dispose(): void;
+}
+
+/**
+ * The external references a `WasmValidator` resolved while compiling.
+ */
+export interface RefReport {
+  /** Whether any external `$ref` was resolved. */
+  used_refs: boolean;
+  /** URIs of the resolved documents, in resolution order. Local `#` refs aren't listed. */
+  refs: string[];
+}
+
+/**
+ * Validates a stream of instances one at a time against a schema compiled once,
+ * for producers that yield instances as they arrive.
+ */
+export class StreamingValidator {
+  /**
+   * @param schema The JSON Schema object to validate against.
+   * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+   * @throws {ValidationError} Throws if the schema itself is invalid.
+   */
+  constructor(schema: JSONSchema, options?: boolean | ValidatorOptions);
+
+  /**
+   * Validates the next instance. Never throws on invalid input.
+   * @param instance The JSON instance to validate.
+   * @param maskValues Overrides the `mask_values` option for this call.
+   */
+  push(instance: any, maskValues?: boolean): ValidationResult;
+
+  /** The number of instances pushed since creation or the last `reset()`. */
+  readonly pushed: number;
+
+  /** The number of pushed instances that were invalid. */
+  readonly invalid: number;
+
+  /** Clears `pushed` and `invalid`, keeping the compiled schema. */
+  reset(): void;
 }
 
 /**
  * A stateless validation function for one-off use.
- * This is less performant for repeated use as it compiles the schema on every call.
+ * Compiled schemas are kept in a bounded cache (see `schemaCacheStats`), but each
+ * call still serializes the schema to look it up, so prefer `WasmValidator` for
+ * repeated use.
  *
  * @param schema The JSON Schema object to validate against.
  * @param instance The JSON instance to validate.
- * @param mask_values If true, potentially sensitive data will be redacted from error messages. Defaults to `false`.
- * @returns The validated instance if validation is successful.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns The validated instance if validation is successful, normalized if the
+ * `normalize` option is set.
  * @throws {ValidationError} Throws if the instance is invalid.
  */
 export function validate<T extends any>(
     schema: JSONSchema,
     instance: T,
-    mask_values?: boolean
+    options?: boolean | ValidatorOptions
+): T;
+
+/**
+ * Validates an instance against the intersection of several schemas, as if they
+ * were combined under a single `allOf`. Each issue's `schema_path` starts with
+ * `/allOf/<index>` of the schema that rejected it.
+ *
+ * @param schemas The JSON Schema objects that must all be satisfied.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns The validated instance if validation is successful.
+ * @throws {ValidationError} Throws if the instance is invalid.
+ */
+export function validateAllOf<T extends any>(
+    schemas: JSONSchema[],
+    instance: T,
+    options?: boolean | ValidatorOptions
+): T;
+
+/**
+ * Finds which branch of the schema's root `oneOf`/`anyOf` the instance matches.
+ * Useful for debugging discriminated unions.
+ *
+ * @param schema A JSON Schema whose root has a `oneOf` or `anyOf` keyword.
+ * @param instance The JSON instance to validate.
+ * @returns The index of the first matching branch.
+ * @throws {ValidationError} Throws if no branch matches, or with an `invalid_schema`
+ * issue if the root has no `oneOf`/`anyOf`.
+ */
+export function validateWhichBranch(schema: JSONSchema, instance: any): number;
+
+/**
+ * The validation state of a top-level property, as reported by `validateFieldStatus`.
+ */
+export type FieldStatus = 'valid' | 'invalid' | 'missing';
+
+/**
+ * Reports the status of each top-level property declared in the schema's
+ * `properties` or `required`, keyed by JSON Pointer (e.g. `"/username"`).
+ * A present property is `invalid` if any issue points at or below it; a required
+ * property that is absent is `missing`. Absent optional properties are omitted.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateFieldStatus(
+    schema: JSONSchema,
+    instance: any
+): Record<string, FieldStatus>;
+
+/**
+ * The result of `validateDiff`. Issues are matched on `(path, code)`.
+ */
+export interface ValidationDiff {
+  /** Issues present only in the new instance. */
+  added: ValidationIssue[];
+  /** Issues present only in the old instance, i.e. fixed by the edit. */
+  removed: ValidationIssue[];
+  /** Issues present in both, as reported for the new instance. */
+  unchanged: ValidationIssue[];
+}
+
+/**
+ * Validates an old and a new version of an instance and reports which issues
+ * the edit introduced or fixed.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param oldInstance The instance before the edit.
+ * @param newInstance The instance after the edit.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateDiff(
+    schema: JSONSchema,
+    oldInstance: any,
+    newInstance: any,
+    options?: boolean | ValidatorOptions
+): ValidationDiff;
+
+/**
+ * A validation result returned as a value rather than thrown.
+ */
+export interface ValidationResult {
+  /** True if the instance satisfies the schema. */
+  valid: boolean;
+  /** The validation issues; empty when `valid` is true. */
+  issues: ValidationIssue[];
+  /** Wall-clock durations in microseconds, present with the `profile` option. */
+  timing?: {
+    /** Time spent compiling the schema. */
+    compile_us: number;
+    /** Time spent validating the instance. */
+    validate_us: number;
+  };
+}
+
+/**
+ * A non-throwing variant of `validate` for hot paths, where throwing and catching
+ * on every invalid input is slow in some engines. Failures that would otherwise
+ * throw are reported as issues instead: an invalid schema as `invalid_schema`, and
+ * a schema, instance or options value that can't be converted to JSON as `invalid_json`.
+ * The tradeoff is that callers must check `valid` rather than relying on exceptions.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ */
+export function validateResult(
+    schema: JSONSchema,
+    instance: any,
+    options?: boolean | ValidatorOptions
+): ValidationResult;
+
+/**
+ * Lists the JSON Pointer of every required property missing from the instance,
+ * including the property name (e.g. `"/profile/fullName"`). Required properties of
+ * absent parent objects are not listed.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to check.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function missingRequired(schema: JSONSchema, instance: any): string[];
+
+/**
+ * Validates only the values present in the instance, ignoring every
+ * `missing_property`, `dependent_required` and `dependent_schema` issue. Useful while a form is being filled in progressively.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The (possibly incomplete) JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns The validated instance if validation is successful.
+ * @throws {ValidationError} Throws if a present value is invalid.
+ */
+export function validatePartial<T extends any>(
+    schema: JSONSchema,
+    instance: T,
+    options?: boolean | ValidatorOptions
 ): T;
 
+/**
+ * A keyword the effective draft doesn't define and will ignore, or an embedded
+ * `$schema` that won't be honored.
+ */
+export interface CompileWarning {
+  /** The ignored keyword, e.g. `"prefixItems"`. */
+  keyword: string;
+  /** JSON Pointer to the keyword within the schema. */
+  schema_path: string;
+  message: string;
+}
+
+/** The outcome of compiling a schema, returned by `compileReport`. */
+export interface CompileReport {
+  /** True if the schema compiled. */
+  valid: boolean;
+  /** Compilation errors; empty when `valid` is true. */
+  issues: ValidationIssue[];
+  /** Keywords that compile but have no effect under the effective draft. */
+  warnings: CompileWarning[];
+}
+
+/**
+ * Compiles a schema and reports keywords the draft would silently ignore, such
+ * as `$defs` under Draft 4 or `prefixItems` under Draft 7.
+ *
+ * A referenced resource loaded separately (see `WasmValidator.fromUri`) is
+ * compiled under the draft its own `$schema` declares. A `$schema` inside an
+ * embedded subschema is not honored, since the subschema follows the root's
+ * draft; each one is reported as a warning.
+ *
+ * @param schema The JSON Schema object to compile.
+ * @param draft The draft to compile under. Detected from `$schema` when omitted.
+ */
+export function compileReport(schema: JSONSchema, draft?: SchemaDraft): CompileReport;
+
+/**
+ * Validates many instances and returns the results as a MessagePack-encoded
+ * `Uint8Array`, which can be transferred to another thread via `postMessage`
+ * without a structured clone.
+ *
+ * Layout: an array with one entry per instance, in input order. Each entry is an
+ * array of issues (empty when the instance is valid), and each issue is a map with
+ * the same keys as `ValidationIssue`. Decoding yields `ValidationIssue[][]`.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instances The JSON instances to validate.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateManyBinary(schema: JSONSchema, instances: any[]): Uint8Array;
+
+/**
+ * Lists the distinct issue codes an instance produces, sorted. Returns an empty
+ * array when the instance is valid.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function errorCodes(schema: JSONSchema, instance: any): ValidationErrorCode[];
+
+/**
+ * Validates one region of a larger JSON document without reparsing the rest, for
+ * incremental editor integrations. The bytes from `startByte` to `endByte` of the UTF-8
+ * encoding of `instanceJson` (as `TextEncoder` produces it, which differs from
+ * string indices once non-ASCII text comes before) are parsed as the value at
+ * `pointer` and checked against the subschema for that location.
+ * Subschemas reachable only through `allOf`, `anyOf`, `if` or `patternProperties`
+ * are not considered; a location no subschema governs is accepted.
+ *
+ * @param schemaJson The JSON Schema, as JSON text.
+ * @param instanceJson The whole document, as JSON text.
+ * @param startByte UTF-8 byte offset where the value begins.
+ * @param endByte UTF-8 byte offset just past the value.
+ * @param pointer JSON Pointer of the value within the document, e.g. `"/profile"`.
+ * @throws {ValidationError} Throws if the value is invalid. Issue paths are relative
+ * to the whole document.
+ */
+export function validateSlice(
+    schemaJson: string,
+    instanceJson: string,
+    startByte: number,
+    endByte: number,
+    pointer: string
+): void;
+
+/**
+ * Validates JSON text against a schema given as JSON text. Malformed text is
+ * reported as an `invalid_json` issue rather than a parse exception.
+ *
+ * @param schemaJson The JSON Schema, as JSON text.
+ * @param instanceJson The instance, as JSON text.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws if either text is malformed or the instance is invalid.
+ */
+export function validateStr(
+    schemaJson: string,
+    instanceJson: string,
+    options?: boolean | ValidatorOptions
+): void;
+
+/**
+ * Checks that an untrusted schema is within size limits and compiles, scanning
+ * its size before compiling so that huge or deeply nested schemas are rejected
+ * cheaply.
+ *
+ * @param schema The JSON Schema object to check.
+ * @param maxDepth Maximum nesting depth of the schema document.
+ * @param maxKeywords Maximum number of object members across the whole document.
+ * @throws {ValidationError} Throws a `schema_too_large` issue if a limit is exceeded,
+ * or `invalid_schema` issues if the schema doesn't compile.
+ */
+export function checkSchemaBounded(
+    schema: JSONSchema,
+    maxDepth?: number,
+    maxKeywords?: number
+): void;
+
+/** The result of `applyDefaults`. */
+export interface DefaultsResult<T = any> extends ValidationResult {
+  /** The instance with defaults filled in. */
+  value: T;
+}
+
+/**
+ * Fills every absent object property with its schema `default`, recursively
+ * (including inside inserted defaults), then validates the filled instance.
+ * Present values, including `null`, are never overwritten. Only defaults under
+ * `properties` reached through `properties`, `items`, `allOf` and `$ref` apply.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to fill and validate. It is not modified.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function applyDefaults<T = any>(schema: JSONSchema, instance: any): DefaultsResult<T>;
+
+/** An `examples` entry that doesn't satisfy its own subschema. */
+export interface ExampleFailure {
+  /** JSON Pointer to the example within the schema, e.g. `"/properties/age/examples/1"`. */
+  schema_path: string;
+  /** The issues the example produces; paths are relative to the example. */
+  issues: ValidationIssue[];
+}
+
+/**
+ * Checks every value in the schema's `examples` arrays against the subschema
+ * that declares it, to catch broken example data while authoring.
+ *
+ * @param schema The JSON Schema object to check.
+ * @returns The failing examples; empty if every example is valid.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateExamples(schema: JSONSchema): ExampleFailure[];
+
+/**
+ * Returns the effective subschema governing a location in the instance, e.g. for
+ * hover tooltips. Subschemas reached through `$ref`, `allOf`, `properties`,
+ * `additionalProperties`, `items` and `prefixItems` are merged into one object:
+ * `properties` and `required` are combined, and otherwise the first declaration
+ * of a keyword wins. Conditional branches (`anyOf`, `oneOf`, `if`) are not merged.
+ *
+ * @param schema The JSON Schema object.
+ * @param instance The instance, used to pick array items and additional properties.
+ * @param pointer JSON Pointer to the location, e.g. `"/profile/age"`.
+ * @returns The merged subschema, or `null` if nothing governs the location.
+ */
+export function schemaAt(schema: JSONSchema, instance: any, pointer: string): JSONSchema | null;
+
+/** The result for one CSV data row. */
+export interface CsvRowResult {
+  /** 1-based line the row starts on; the header is line 1. */
+  line: number;
+  valid: boolean;
+  /** Issue paths are relative to the row object, e.g. `"/age"`. */
+  issues: ValidationIssue[];
+}
+
+/**
+ * Validates tabular data: the first CSV line names the properties, and every
+ * following row is validated as an object. Quoted cells may contain commas, line
+ * breaks and `""` escapes. Cells are coerced to `integer`, `number` or `boolean`
+ * when their property's `type` allows it and not `string`; empty cells become
+ * `null` where allowed and are otherwise left out. A row whose cell count differs
+ * from the header's gets an `invalid_csv` issue.
+ *
+ * @param schema The JSON Schema object each row is validated against.
+ * @param csv The CSV text.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws an `invalid_csv` issue for malformed CSV, or
+ * if the schema itself is invalid.
+ */
+export function validateCsv(
+  schema: JSONSchema,
+  csv: string,
+  options?: boolean | ValidatorOptions
+): CsvRowResult[];
+
+/**
+ * Groups the issues an instance produces by the schema keyword that raised them,
+ * e.g. for dashboards showing which rules are broken most often.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns A map from `schema_path` to the instance paths that violated it; empty
+ * if the instance is valid.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateBySchemaPath(
+  schema: JSONSchema,
+  instance: any,
+  options?: boolean | ValidatorOptions
+): Record<string, string[]>;
+
+/** An RFC 9457 Problem Details body for a validation failure. */
+export interface ProblemDetails {
+  type: string;
+  title: string;
+  status: number;
+  detail: string;
+  /** Messages by instance path, in issue order. */
+  errors: Record<string, string[]>;
+}
+
+/**
+ * Wraps issues in an RFC 9457 Problem Details object, e.g. for an
+ * `application/problem+json` response.
+ *
+ * @param issues The issues, e.g. from `ValidationError.issues`. Only `path` and
+ * `message` are read.
+ * @param status The HTTP status code to report, e.g. `422`.
+ */
+export function toProblemDetails(
+  issues: Pick<ValidationIssue, 'path' | 'message'>[],
+  status: number
+): ProblemDetails;
+
+/**
+ * Bundles a schema and the documents its `$ref`s point to into one
+ * self-contained schema, so it can be compiled without resolving anything.
+ * Each referenced document is embedded once under `$defs`, keyed by its file
+ * name, and every `$ref` is rewritten to a same-document pointer; cyclic
+ * references stay references. Only JSON Pointer fragments are supported, not
+ * anchors or `$id`s nested inside a document.
+ *
+ * @param schema The root schema. Relative `$ref`s resolve against its `$id`.
+ * @param resources Referenced documents by absolute URI.
+ * @returns The bundled schema.
+ * @throws {ValidationError} Throws a `schema_reference_error` issue if a
+ * referenced document is missing, or if the bundled schema doesn't compile.
+ */
+export function bundleSchema(
+  schema: JSONSchema,
+  resources?: Record<string, JSONSchema>
+): JSONSchema;
+
+/** The result of `isRefinement`. */
+export interface RefinementReport {
+  /** Whether every sample valid under the old schema is still valid under the new one. */
+  compatible: boolean;
+  /** Samples the old schema accepts but the new one rejects. */
+  breaking: { index: number; issues: ValidationIssue[] }[];
+}
+
+/**
+ * Checks a schema change for breaking changes by validating sample instances
+ * against both versions. This is sample-based, not a proof: it only finds
+ * breakage the samples exercise. Samples the old schema rejects are ignored.
+ *
+ * @param oldSchema The current schema.
+ * @param newSchema The proposed schema.
+ * @param samples Instances representative of existing data.
+ * @throws {ValidationError} Throws if either schema is invalid.
+ */
+export function isRefinement(
+  oldSchema: JSONSchema,
+  newSchema: JSONSchema,
+  samples: any[]
+): RefinementReport;
+
+/** Counters for the schema cache behind `validate`. */
+export interface SchemaCacheStats {
+  /** Calls that reused a compiled schema since the last clear. */
+  hits: number;
+  /** Calls that had to compile the schema since the last clear. */
+  misses: number;
+  /** Compiled schemas currently held, at most 64. */
+  size: number;
+}
+
+/**
+ * Drops every schema `validate` has compiled and resets the counters, e.g. to
+ * release stale schemas after a deploy.
+ */
+export function clearSchemaCache(): void;
+
+/**
+ * Reports how effective the schema cache behind `validate` is. The cache is
+ * keyed by the schema and the options that affect compilation, and evicts the
+ * least recently used schema when full. Schemas with custom `keywords` are never
+ * cached.
+ */
+export function schemaCacheStats(): SchemaCacheStats;
+
+/** A JSON Patch (RFC 6902) operation produced by `suggestFixes`. */
+export type FixOperation =
+  | { op: 'add'; path: string; value: any }
+  | { op: 'replace'; path: string; value: any };
+
+/**
+ * Suggests a JSON Patch that repairs the trivially fixable issues of an instance,
+ * e.g. for a "fix it for me" button:
+ * - a missing required property whose schema has a `default` is added;
+ * - a value of the wrong type that converts losslessly (`"42"` to `42`, `"true"`
+ *   to `true`, `5` to `"5"`) is replaced;
+ * - a number below `minimum` or above `maximum` is clamped to the limit.
+ *
+ * Other issues are omitted, so the patched instance may still be invalid.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to repair. It is not modified.
+ * @param seed Like `exampleFor`'s, for fixes with several candidate values. Every
+ * fix has a single candidate today, so the patch is the same with or without it.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function suggestFixes(
+  schema: JSONSchema,
+  instance: any,
+  seed?: number | bigint
+): FixOperation[];
+
+/**
+ * Validates a dictionary-shaped object, e.g. one described by
+ * `additionalProperties` or `patternProperties`, and reports the issues of each
+ * entry separately. Issues about the object as a whole, such as `required` or
+ * `minProperties`, belong to no key and are not included.
+ *
+ * @param schema The JSON Schema object describing the map.
+ * @param instance The object to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns Every key of the instance, in order, with its issues; an empty list
+ * means the entry is valid.
+ * @throws {ValidationError} Throws if the schema itself is invalid, or with the
+ * instance's issues if it isn't an object.
+ */
+export function validateMap(
+  schema: JSONSchema,
+  instance: Record<string, any>,
+  options?: boolean | ValidatorOptions
+): Record<string, ValidationIssue[]>;
+
+/** A property declared by a schema, as listed by `schemaProperties`. */
+export interface SchemaProperty {
+  /** JSON Pointer of the property within an instance, e.g. `/profile/fullName`. */
+  path: string;
+  /** The declared `type`, omitted when the property doesn't declare one. */
+  type?: string | string[];
+  /** Whether the enclosing object lists the property in `required`. */
+  required: boolean;
+}
+
+/**
+ * Lists every property the schema declares, e.g. for building a form. Nested
+ * objects are flattened depth first. A property that is a local `$ref` reports
+ * its target's type and properties; refs aren't followed further, so recursive
+ * schemas yield a finite list.
+ *
+ * @param schema The JSON Schema object to inspect.
+ */
+export function schemaProperties(schema: JSONSchema): SchemaProperty[];
+
+/**
+ * Validates a JSON5 document, such as a config file with comments, trailing
+ * commas or unquoted keys, against a schema.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param json5Instance The instance, as JSON5 text.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws if the value is invalid. A syntax error is
+ * reported as an `invalid_json` issue ending in `at line L column C`.
+ */
+export function validateJson5(
+  schema: JSONSchema,
+  json5Instance: string,
+  options?: boolean | ValidatorOptions
+): void;
+
+/** The result of `presenceReport`. */
+export interface PresenceReport {
+  /** True if the instance satisfies the schema. */
+  valid: boolean;
+  /** The validation issues; empty when `valid` is true. */
+  issues: ValidationIssue[];
+  /** JSON Pointers of the declared, non-required properties the instance includes. */
+  present_optional: string[];
+  /** JSON Pointers of the declared, non-required properties it leaves out. */
+  absent_optional: string[];
+}
+
+/**
+ * Validates an instance and reports which optional properties it includes, e.g.
+ * for analytics on form usage. Properties are those `schemaProperties` lists.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function presenceReport(schema: JSONSchema, instance: any): PresenceReport;
+
+/**
+ * Validates an instance like `validate`, attaching to each issue the part of the
+ * instance around the failure as `context_value`, so users see surrounding data
+ * without the full payload.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @param contextDepth How many levels above the failing path the subtree is
+ * rooted: `0` for the failing value itself, `1` for its parent.
+ * @param options Validator options, or a boolean for `mask_values`. With
+ * `mask_values`, every leaf of the subtree is `null`.
+ * @throws {ValidationError} Throws if the value is invalid.
+ */
+export function validateWithContext(
+  schema: JSONSchema,
+  instance: any,
+  contextDepth: number,
+  options?: boolean | ValidatorOptions
+): void;
+
+/** The issues of one failing element, as reported by `validateArray`. */
+export interface ItemIssues {
+  /** The element's index in the array. */
+  index: number;
+  /** Its issues, with paths into the whole array, e.g. `/3/name`. */
+  issues: ValidationIssue[];
+}
+
+/**
+ * Validates each element of a large array against the schema's `items`
+ * subschema on its own, reporting the failing elements in order. Keywords about
+ * the array as a whole, such as `minItems`, aren't checked.
+ *
+ * @param schema A JSON Schema with a single `items` subschema.
+ * @param instance The array to validate.
+ * @param maxFailingItems Stop after this many elements have failed. Unlimited
+ * when omitted.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws if the schema has no single `items`
+ * subschema, or if the instance isn't an array.
+ */
+export function validateArray(
+  schema: JSONSchema,
+  instance: any[],
+  maxFailingItems?: number,
+  options?: boolean | ValidatorOptions
+): ItemIssues[];
+
+/**
+ * Lists the values a subschema allows, e.g. for autocomplete: its `const`, its
+ * `enum`, or `true`/`false`/`null` for a `type` made only of `boolean` and
+ * `null`. A subschema that is a local `$ref` is resolved first.
+ *
+ * @param schema The JSON Schema object.
+ * @param pointer JSON Pointer to the subschema within `schema`, e.g.
+ * `/properties/status`.
+ * @returns The allowed values, or `null` if they aren't a closed set or nothing
+ * is at the pointer.
+ */
+export function allowedValuesAt(schema: JSONSchema, pointer: string): any[] | null;
+
+/**
+ * Validates an instance like `validate`, but with the smallest error payload,
+ * for bandwidth-constrained clients: it throws a plain array of
+ * `[path, message]` pairs rather than a `ValidationError`. Schema errors are
+ * thrown in the same shape.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {[string, string][]} The `[path, message]` of each issue.
+ */
+export function validateFlat(
+  schema: JSONSchema,
+  instance: any,
+  options?: boolean | ValidatorOptions
+): void;
+
+/** A rough measure of a schema's validation cost, returned by `schemaCost`. */
+export interface SchemaCost {
+  /** Members of every subschema object, `$defs` included. */
+  keyword_count: number;
+  /** `pattern` keywords plus `patternProperties` entries. */
+  regex_count: number;
+  /**
+   * The longest chain of `$ref`s that resolve into subschemas holding further
+   * `$ref`s. A cycle is counted once around.
+   */
+  max_ref_depth: number;
+  /**
+   * The product of the branch counts of every `oneOf` and `anyOf`, an upper
+   * bound on the combinations validation may try.
+   */
+  branch_product: number;
+}
+
+/**
+ * Estimates how costly a schema is to validate against, without compiling it,
+ * e.g. to decide whether to accept an untrusted schema. The figures are a
+ * heuristic guardrail, not a precise prediction.
+ *
+ * @param schema The JSON Schema object to measure.
+ */
+export function schemaCost(schema: JSONSchema): SchemaCost;
+
+/** The validation result for one NDJSON line. */
+export interface NdjsonLineResult {
+  /** 1-based line number. */
+  line: number;
+  valid: boolean;
+  /** The line's issues; a line that isn't JSON has an `invalid_json` issue. */
+  issues: ValidationIssue[];
+}
+
+/**
+ * Validates newline-delimited JSON against a schema, handing each line's result
+ * to `callback` as soon as it's ready instead of buffering them. Blank lines are
+ * skipped.
+ *
+ * @param schema The JSON Schema object to validate each line against.
+ * @param ndjson The NDJSON text.
+ * @param callback Called with each line's result. Return `false` to stop.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns The number of lines processed.
+ * @throws {ValidationError} Throws if the schema itself is invalid. Errors
+ * thrown by `callback` propagate.
+ */
+export function validateNdjsonEach(
+  schema: JSONSchema,
+  ndjson: string,
+  callback: (result: NdjsonLineResult) => boolean | void,
+  options?: boolean | ValidatorOptions
+): number;
+
+/**
+ * Validates an instance against a schema stored inside a larger document, such
+ * as an OpenAPI `components/schemas` entry. References like
+ * `#/components/schemas/Address` resolve against the whole document.
+ *
+ * @param document The document holding the schema.
+ * @param schemaPointer JSON Pointer to the schema within `document`, e.g.
+ * `/components/schemas/User`.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws if the value is invalid, or with an
+ * `invalid_schema` issue if there is no schema at the pointer.
+ */
+export function validateEmbedded(
+  document: any,
+  schemaPointer: string,
+  instance: any,
+  options?: boolean | ValidatorOptions
+): void;
+
+/** The issues of each side of a request/response pair. */
+export interface PairIssues {
+  request: ValidationIssue[];
+  response: ValidationIssue[];
+}
+
+/**
+ * Validates a request and a response against their own schemas in one call,
+ * e.g. for API contract tests. Each schema is compiled once and cached like in
+ * `validate`.
+ *
+ * @param requestSchema The JSON Schema for the request.
+ * @param request The request body.
+ * @param responseSchema The JSON Schema for the response.
+ * @param response The response body.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns The issues of each side; empty lists mean both are valid.
+ * @throws {ValidationError} Throws if either schema is invalid.
+ */
+export function validatePair(
+  requestSchema: JSONSchema,
+  request: any,
+  responseSchema: JSONSchema,
+  response: any,
+  options?: boolean | ValidatorOptions
+): PairIssues;
+
+/** The outcome of `validateWithFallback`. */
+export interface FallbackResult {
+  /** Which schema the instance was validated against. */
+  schema: 'primary' | 'fallback';
+  valid: boolean;
+  issues: ValidationIssue[];
+}
+
+/**
+ * Validates against a dynamically loaded schema, falling back to a looser
+ * built-in schema instead of failing when the primary doesn't compile. Both
+ * schemas are compiled once and cached like in `validate`.
+ *
+ * @param primarySchema The preferred JSON Schema, e.g. one loaded at runtime.
+ * @param fallbackSchema The JSON Schema to use if `primarySchema` is invalid.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns `{ schema, valid, issues }`, where `schema` tells which one was used.
+ * @throws {ValidationError} Throws if neither schema compiles.
+ */
+export function validateWithFallback(
+  primarySchema: JSONSchema,
+  fallbackSchema: JSONSchema,
+  instance: any,
+  options?: boolean | ValidatorOptions
+): FallbackResult;
+
+/**
+ * Synthesizes a minimal value that the subschema at a JSON Pointer into the
+ * schema accepts, e.g. to show what a failing field expects. Best-effort: uses
+ * the `const` or first `enum` value, or a value of the declared type sized to
+ * its lower bounds, such as a string of `minLength` letters or a number at
+ * `minimum`. Objects get their required properties. `pattern` isn't considered.
+ * The output is deterministic: without a seed, the same schema always gives the
+ * same example.
+ *
+ * @param schema The JSON Schema object.
+ * @param pointer JSON Pointer to the subschema, e.g. `/properties/status`. Use
+ * an issue's `schema_path` minus the failed keyword for a failing field.
+ * @param seed Picks the `enum` value and `oneOf`/`anyOf` branch pseudo-randomly
+ * instead of taking the first, for varied examples. The same seed gives the same
+ * example.
+ * @returns The example, or `undefined` if the pointer doesn't resolve or no
+ * example could be made.
+ */
+export function exampleFor(schema: JSONSchema, pointer: string, seed?: number | bigint): any;
+
+/**
+ * Compiles a batch of schemas independently, e.g. as a CI lint gate for a
+ * schema repository, so one bad schema doesn't hide problems in the others.
+ * Never throws.
+ *
+ * @param schemas An array of schemas, or an object of schemas keyed by name
+ * (e.g. file path). Any object is read as the keyed form, so pass a single
+ * schema as `[schema]`: `{ type: 'string' }` would check `'string'` as a schema
+ * named `type` and report it invalid.
+ * @returns `{ valid, issues }` for each schema, as an array or an object with
+ * the same keys as the input. Invalid schemas get `invalid_schema` issues, or
+ * `schema_reference_error` ones for a `$ref` that doesn't resolve.
+ */
+export function checkSchemas(schemas: JSONSchema[]): ValidationResult[];
+export function checkSchemas(
+  schemas: Record<string, JSONSchema>
+): Record<string, ValidationResult>;
+
+/** How close an instance is to satisfying its schema. */
+export interface Completeness {
+  /** `satisfied` as a whole percentage of `total`, from 0 to 100. */
+  score: number;
+  /** Applicable constraints that raised no issue: `total` minus the issue count. */
+  satisfied: number;
+  /**
+   * Applicable constraints: one per required property, plus one per assertion
+   * keyword, such as `type` or `minLength`, on each value that is present.
+   */
+  total: number;
+  issues: ValidationIssue[];
+}
+
+/**
+ * Scores how close a partly filled instance is to valid, e.g. to drive a form
+ * progress bar. Filling in a property can raise `total` as its own constraints
+ * start to apply, so the score is a guide rather than an exact measure.
+ *
+ * @param schema The JSON Schema object.
+ * @param instance The JSON instance, possibly incomplete.
+ * @returns `{ score, satisfied, total, issues }`.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function completeness(schema: JSONSchema, instance: any): Completeness;
+
+/**
+ * Validates against several schemas in order, e.g. the supported versions of a
+ * payload, stopping at the first that compiles and accepts the instance. Schemas
+ * that don't compile are skipped. Each is compiled once and cached like in
+ * `validate`.
+ *
+ * @param schemas The candidate JSON Schemas, in order of preference.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns The index of the first matching schema.
+ * @throws {ValidationError} Throws the issues of the last schema if none match,
+ * or an `invalid_schema` issue if `schemas` is empty.
+ */
+export function validateFirstMatch(
+  schemas: JSONSchema[],
+  instance: any,
+  options?: boolean | ValidatorOptions
+): number;
+
+/**
+ * Validates an instance and returns the issues as a minimal SARIF 2.1.0 log,
+ * for CI code-scanning UIs that annotate pull requests. Each issue becomes a
+ * result whose `ruleId` is its code and whose location is `sourceUri`, with the
+ * instance path as a logical location.
+ *
+ * @param schema The JSON Schema object.
+ * @param instance The JSON instance to validate.
+ * @param sourceUri The file the instance was read from, e.g. `config/app.json`.
+ * @returns The SARIF log; its run has no results when the instance is valid.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateSarif(schema: JSONSchema, instance: any, sourceUri: string): any;
+
+/**
+ * Reports, for each top-level property declared in the schema's `properties` or
+ * `required`, the code of its first issue, keyed by JSON Pointer (e.g.
+ * `"/username"`), without the full issue list, e.g. to color form fields. A
+ * required property that is absent maps to `missing_property`, a valid one to
+ * `null`. Absent optional properties are omitted.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function propertyStatus(
+  schema: JSONSchema,
+  instance: any
+): Record<string, ValidationErrorCode | null>;
+
+/** An RFC 6902 JSON Patch operation. */
+export type PatchOperation =
+  | { op: 'add' | 'replace' | 'test'; path: string; value: any }
+  | { op: 'remove'; path: string }
+  | { op: 'move' | 'copy'; from: string; path: string };
+
+/**
+ * Applies a JSON Patch to an instance and validates the result, reporting only
+ * the issues at or under the paths the patch changed (both ends of a `move`),
+ * so editing a large document doesn't resurface unrelated issues. Removing a
+ * required property reports its `missing_property` issue. The base instance
+ * is not modified. The schema is compiled once and cached like in `validate`.
+ *
+ * @param schema The JSON Schema object.
+ * @param baseInstance The document before the edit.
+ * @param patch The edit, as JSON Patch operations.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {ValidationError} Throws if the patched document has issues at a changed
+ * path, or if the schema itself is invalid.
+ * @throws {string} Throws a `Patch error: ...` message if an operation doesn't
+ * apply, including a failed `test`.
+ */
+export function validatePatch(
+  schema: JSONSchema,
+  baseInstance: any,
+  patch: PatchOperation[],
+  options?: boolean | ValidatorOptions
+): void;
+
+/** A validation result together with the size of the instance. */
+export interface ValidationSummary {
+  valid: boolean;
+  issues: ValidationIssue[];
+  /** Every value in the instance, counting objects, arrays and scalars alike. */
+  instance_nodes: number;
+}
+
+/**
+ * Validates an instance and reports how big it was, e.g. to bill or rate-limit
+ * by payload complexity. The schema is compiled once and cached like in
+ * `validate`.
+ *
+ * @param schema The JSON Schema object.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @returns `{ valid, issues, instance_nodes }`.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function validateSummary(
+  schema: JSONSchema,
+  instance: any,
+  options?: boolean | ValidatorOptions
+): ValidationSummary;
+
+/**
+ * Reports which of the root schema's `patternProperties` patterns each key of a
+ * dictionary-shaped instance matched, e.g. to debug schemas with several
+ * patterns. A key matching several patterns maps to the first, in schema order.
+ *
+ * @param schema The JSON Schema object.
+ * @param instance The object whose keys to classify; other values give `{}`.
+ * @returns `{ [key]: pattern }`, with `null` for keys that matched no pattern
+ * and so fall to `properties` or `additionalProperties`.
+ * @throws {ValidationError} Throws if the schema itself is invalid.
+ */
+export function patternPropertyMap(schema: JSONSchema, instance: any): Record<string, string | null>;
+
+/**
+ * Validates an instance like `validate`, but throws the issues as a CSV string
+ * for spreadsheet triage instead of a `ValidationError`: a `path,code,message`
+ * header, then one row per issue, with cells containing commas, quotes or line
+ * breaks quoted as in RFC 4180. Schema errors are thrown in the same shape.
+ *
+ * @param schema The JSON Schema object to validate against.
+ * @param instance The JSON instance to validate.
+ * @param options Validator options, or a boolean for `mask_values`. Defaults to `false`.
+ * @throws {string} The issues as CSV, with `\r\n` line endings.
+ */
+export function validateCsvReport(
+  schema: JSONSchema,
+  instance: any,
+  options?: boolean | ValidatorOptions
+): void;
+
 /**
  * Type guard to check if a value is a `ValidationError`.
  * @param value The value to check.
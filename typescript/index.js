@@ -1,17 +1,71 @@
 const {
   WasmValidator: WasmValidatorRaw,
+  StreamingValidator: StreamingValidatorRaw,
   validate: validateRaw,
+  validate_all_of: validateAllOfRaw,
+  validate_which_branch: validateWhichBranchRaw,
+  validate_field_status: validateFieldStatusRaw,
+  validate_diff: validateDiffRaw,
+  validate_result: validateResult,
+  missing_required: missingRequiredRaw,
+  validate_partial: validatePartialRaw,
+  compile_report: compileReport,
+  validate_many_binary: validateManyBinaryRaw,
+  error_codes: errorCodesRaw,
+  validate_slice: validateSliceRaw,
+  validate_str: validateStrRaw,
+  check_schema_bounded: checkSchemaBoundedRaw,
+  apply_defaults: applyDefaultsRaw,
+  validate_examples: validateExamplesRaw,
+  schema_at: schemaAt,
+  validate_csv: validateCsvRaw,
+  validate_by_schema_path: validateBySchemaPathRaw,
+  to_problem_details: toProblemDetails,
+  bundle_schema: bundleSchemaRaw,
+  is_refinement: isRefinementRaw,
+  clear_schema_cache: clearSchemaCache,
+  schema_cache_stats: schemaCacheStats,
+  suggest_fixes: suggestFixesRaw,
+  validate_map: validateMapRaw,
+  schema_properties: schemaProperties,
+  validate_json5: validateJson5Raw,
+  presence_report: presenceReportRaw,
+  validate_with_context: validateWithContextRaw,
+  validate_array: validateArrayRaw,
+  allowed_values_at: allowedValuesAt,
+  validate_flat: validateFlat,
+  schema_cost: schemaCost,
+  validate_ndjson_each: validateNdjsonEachRaw,
+  validate_embedded: validateEmbeddedRaw,
+  validate_pair: validatePairRaw,
+  validate_with_fallback: validateWithFallbackRaw,
+  example_for: exampleForRaw,
+  check_schemas: checkSchemas,
+  completeness: completenessRaw,
+  validate_first_match: validateFirstMatchRaw,
+  validate_sarif: validateSarifRaw,
+  property_status: propertyStatusRaw,
+  validate_patch: validatePatchRaw,
+  validate_summary: validateSummaryRaw,
+  pattern_property_map: patternPropertyMapRaw,
+  validate_csv_report: validateCsvReport,
 } = require('./wasm_validator.js');
 
 class ValidationError extends Error {
   constructor(issues) {
-    const issue_list = issues.map(i => `- ${i.path || 'instance'}: ${i.message}`).join('\n');
+    const issue_list = issues.map(i => `- ${(i.display_path ?? i.path) || 'instance'}: ${i.message}`).join('\n');
     super(`Validation failed with ${issues.length} error(s):\n${issue_list}`);
     this.name = 'ValidationError';
     this.issues = issues;
   }
 }
 
+// Converts issue arrays thrown by the Wasm module into a `ValidationError`.
+function rethrowIssues(e) {
+  if (!Array.isArray(e)) throw e;
+  throw new ValidationError(e);
+}
+
 function isValidationError(value) {
   return value instanceof ValidationError;
 }
@@ -22,13 +76,20 @@ function assertValidationError(value) {
   }
 }
 
+// Passed as the schema to wrap an already compiled raw instance.
+const fromRaw = Symbol('fromRaw');
+
 // The new performant, stateful validator class.
 class WasmValidator {
   #validator; // Private field for the raw Wasm instance
 
-  constructor(schema, mask_values = false) {
+  constructor(schema, options = false) {
+    if (schema === fromRaw) {
+      this.#validator = options;
+      return;
+    }
     try {
-      this.#validator = new WasmValidatorRaw(schema, mask_values);
+      this.#validator = new WasmValidatorRaw(schema, options);
     } catch(e) {
       if(!Array.isArray(e)) throw e
       throw new ValidationError(e)
@@ -37,28 +98,445 @@ class WasmValidator {
 
   validate(instance) {
     try {
-      this.#validator.validate(instance);
-      return instance;
+      const normalized = this.#validator.validate(instance);
+      return normalized === undefined ? instance : normalized;
     } catch (e) {
       if (!Array.isArray(e)) throw e;
       throw new ValidationError(e);
     }
   }
+
+  // Compiles the schema at `uri`, loading it and its `$ref`s through a synchronous `fetch(uri)`.
+  static fromUri(uri, fetch, options = false) {
+    try {
+      return new WasmValidator(fromRaw, WasmValidatorRaw.from_uri(uri, fetch, options));
+    } catch (e) {
+      rethrowIssues(e);
+    }
+  }
+
+  // Compiles without throwing on an invalid schema: `{ ok: true, validator }` or `{ ok: false, issues }`.
+  static tryNew(schema, options = false) {
+    const result = WasmValidatorRaw.try_new(schema, options);
+    if (!result.ok) return result;
+    return { ok: true, validator: new WasmValidator(fromRaw, result.validator) };
+  }
+
+  // `{ used_refs, refs }` with the `record_refs` option, `undefined` otherwise.
+  refReport() {
+    return this.#validator.ref_report();
+  }
+
+  // Releases the compiled schema; later `validate` calls throw.
+  dispose() {
+    this.#validator.dispose();
+  }
+}
+
+// Validates instances one at a time as they arrive, compiling the schema once.
+class StreamingValidator {
+  #validator;
+
+  constructor(schema, options = false) {
+    try {
+      this.#validator = new StreamingValidatorRaw(schema, options);
+    } catch (e) {
+      rethrowIssues(e);
+    }
+  }
+
+  // Returns `{ valid, issues }` for the next instance.
+  push(instance, maskValues) {
+    return this.#validator.push(instance, maskValues);
+  }
+
+  get pushed() {
+    return this.#validator.pushed();
+  }
+
+  get invalid() {
+    return this.#validator.invalid();
+  }
+
+  reset() {
+    this.#validator.reset();
+  }
 }
 
 // The original stateless function, useful for one-off validations.
-function validate(schema, instance, mask_values) {
+function validate(schema, instance, options) {
   try {
-    validateRaw(schema, instance, mask_values);
-    return instance;
+    const normalized = validateRaw(schema, instance, options);
+    return normalized === undefined ? instance : normalized;
   } catch (e) {
     if (!Array.isArray(e)) throw e;
     throw new ValidationError(e);
   }
 }
 
+// Validates against the intersection of several schemas, as if combined with `allOf`.
+function validateAllOf(schemas, instance, options) {
+  try {
+    validateAllOfRaw(schemas, instance, options);
+    return instance;
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Returns the index of the first root `oneOf`/`anyOf` branch the instance matches.
+function validateWhichBranch(schema, instance) {
+  try {
+    return validateWhichBranchRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Maps each declared top-level property to 'valid', 'invalid' or 'missing'.
+function validateFieldStatus(schema, instance) {
+  try {
+    return validateFieldStatusRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Reports which issues were added, removed or kept between two versions of an instance.
+function validateDiff(schema, oldInstance, newInstance, options) {
+  try {
+    return validateDiffRaw(schema, oldInstance, newInstance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Lists the JSON Pointers of required properties that are absent.
+function missingRequired(schema, instance) {
+  try {
+    return missingRequiredRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates only the values present in the instance, ignoring missing required properties.
+function validatePartial(schema, instance, options) {
+  try {
+    validatePartialRaw(schema, instance, options);
+    return instance;
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates many instances into a transferable MessagePack `Uint8Array` of issue lists.
+function validateManyBinary(schema, instances) {
+  try {
+    return validateManyBinaryRaw(schema, instances);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Returns the sorted, distinct issue codes an instance produces.
+function errorCodes(schema, instance) {
+  try {
+    return errorCodesRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates the value at `pointer` from a UTF-8 byte range of a larger JSON document.
+function validateSlice(schemaJson, instanceJson, startByte, endByte, pointer) {
+  try {
+    validateSliceRaw(schemaJson, instanceJson, startByte, endByte, pointer);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates JSON text, reporting malformed text as an `invalid_json` issue.
+function validateStr(schemaJson, instanceJson, options) {
+  try {
+    validateStrRaw(schemaJson, instanceJson, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Rejects oversized schemas as `schema_too_large` before compiling them.
+function checkSchemaBounded(schema, maxDepth, maxKeywords) {
+  try {
+    checkSchemaBoundedRaw(schema, maxDepth, maxKeywords);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Fills absent properties with schema defaults, then returns `{ value, valid, issues }`.
+function applyDefaults(schema, instance) {
+  try {
+    return applyDefaultsRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Lists `examples` entries that don't satisfy their own subschema.
+function validateExamples(schema) {
+  try {
+    return validateExamplesRaw(schema);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates each CSV data row as an object keyed by the header row.
+function validateCsv(schema, csv, options) {
+  try {
+    return validateCsvRaw(schema, csv, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Maps each violated `schema_path` to the instance paths that broke it.
+function validateBySchemaPath(schema, instance, options) {
+  try {
+    return validateBySchemaPathRaw(schema, instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Inlines externally referenced documents into `$defs`, leaving only internal `$ref`s.
+function bundleSchema(schema, resources) {
+  try {
+    return bundleSchemaRaw(schema, resources);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Lists samples the old schema accepts but the new one rejects.
+function isRefinement(oldSchema, newSchema, samples) {
+  try {
+    return isRefinementRaw(oldSchema, newSchema, samples);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Returns a JSON Patch repairing the trivially fixable issues of an instance.
+function suggestFixes(schema, instance, seed) {
+  try {
+    return suggestFixesRaw(schema, instance, seed === undefined ? undefined : BigInt(seed));
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates a dictionary-shaped object, returning its issues per key.
+function validateMap(schema, instance, options) {
+  try {
+    return validateMapRaw(schema, instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates JSON5 text, e.g. a config file with comments, against a schema.
+function validateJson5(schema, json5Instance, options) {
+  try {
+    validateJson5Raw(schema, json5Instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates an instance and reports which optional properties it includes.
+function presenceReport(schema, instance) {
+  try {
+    return presenceReportRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates an instance, attaching the surrounding subtree to each issue.
+function validateWithContext(schema, instance, contextDepth, options) {
+  try {
+    validateWithContextRaw(schema, instance, contextDepth, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates each element of an array against the schema's `items`.
+function validateArray(schema, instance, maxFailingItems, options) {
+  try {
+    return validateArrayRaw(schema, instance, maxFailingItems, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates NDJSON line by line, reporting each result through a callback.
+function validateNdjsonEach(schema, ndjson, callback, options) {
+  try {
+    return validateNdjsonEachRaw(schema, ndjson, callback, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates against a schema embedded in a larger document, e.g. OpenAPI.
+function validateEmbedded(document, schemaPointer, instance, options) {
+  try {
+    validateEmbeddedRaw(document, schemaPointer, instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates a request and a response against their own schemas in one call.
+function validatePair(requestSchema, request, responseSchema, response, options) {
+  try {
+    return validatePairRaw(requestSchema, request, responseSchema, response, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates against `primarySchema`, or `fallbackSchema` if the primary doesn't compile.
+function validateWithFallback(primarySchema, fallbackSchema, instance, options) {
+  try {
+    return validateWithFallbackRaw(primarySchema, fallbackSchema, instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Scores from 0 to 100 how much of the schema an instance already satisfies.
+function completeness(schema, instance) {
+  try {
+    return completenessRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Returns the index of the first schema that compiles and accepts the instance.
+function validateFirstMatch(schemas, instance, options) {
+  try {
+    return validateFirstMatchRaw(schemas, instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Validates and returns the issues as a SARIF log located in `sourceUri`.
+function validateSarif(schema, instance, sourceUri) {
+  try {
+    return validateSarifRaw(schema, instance, sourceUri);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Maps each top-level property path to the code of its first issue, or `null`.
+function propertyStatus(schema, instance) {
+  try {
+    return propertyStatusRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Synthesizes a value the subschema at `pointer` accepts; a seed may be a number or a bigint.
+function exampleFor(schema, pointer, seed) {
+  return exampleForRaw(schema, pointer, seed === undefined ? undefined : BigInt(seed));
+}
+
+// Applies a JSON Patch and validates only the paths it changed.
+function validatePatch(schema, baseInstance, patch, options) {
+  try {
+    validatePatchRaw(schema, baseInstance, patch, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Returns `{ valid, issues, instance_nodes }`.
+function validateSummary(schema, instance, options) {
+  try {
+    return validateSummaryRaw(schema, instance, options);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
+// Maps each key of a dictionary-shaped instance to the `patternProperties` pattern it matched.
+function patternPropertyMap(schema, instance) {
+  try {
+    return patternPropertyMapRaw(schema, instance);
+  } catch (e) {
+    rethrowIssues(e);
+  }
+}
+
 module.exports.validate = validate;
+module.exports.validateAllOf = validateAllOf;
+module.exports.validateWhichBranch = validateWhichBranch;
+module.exports.validateFieldStatus = validateFieldStatus;
+module.exports.validateDiff = validateDiff;
+module.exports.validateResult = validateResult;
+module.exports.missingRequired = missingRequired;
+module.exports.validatePartial = validatePartial;
+module.exports.compileReport = compileReport;
+module.exports.validateManyBinary = validateManyBinary;
+module.exports.errorCodes = errorCodes;
+module.exports.validateSlice = validateSlice;
+module.exports.validateStr = validateStr;
+module.exports.checkSchemaBounded = checkSchemaBounded;
+module.exports.applyDefaults = applyDefaults;
+module.exports.validateExamples = validateExamples;
+module.exports.schemaAt = schemaAt;
+module.exports.validateCsv = validateCsv;
+module.exports.validateBySchemaPath = validateBySchemaPath;
+module.exports.toProblemDetails = toProblemDetails;
+module.exports.bundleSchema = bundleSchema;
+module.exports.isRefinement = isRefinement;
+module.exports.clearSchemaCache = clearSchemaCache;
+module.exports.schemaCacheStats = schemaCacheStats;
+module.exports.suggestFixes = suggestFixes;
+module.exports.validateMap = validateMap;
+module.exports.schemaProperties = schemaProperties;
+module.exports.validateJson5 = validateJson5;
+module.exports.presenceReport = presenceReport;
+module.exports.validateWithContext = validateWithContext;
+module.exports.validateArray = validateArray;
+module.exports.allowedValuesAt = allowedValuesAt;
+module.exports.validateFlat = validateFlat;
+module.exports.schemaCost = schemaCost;
+module.exports.validateNdjsonEach = validateNdjsonEach;
+module.exports.validateEmbedded = validateEmbedded;
+module.exports.validatePair = validatePair;
+module.exports.validateWithFallback = validateWithFallback;
+module.exports.exampleFor = exampleFor;
+module.exports.checkSchemas = checkSchemas;
+module.exports.completeness = completeness;
+module.exports.validateFirstMatch = validateFirstMatch;
+module.exports.validateSarif = validateSarif;
+module.exports.propertyStatus = propertyStatus;
+module.exports.validatePatch = validatePatch;
+module.exports.validateSummary = validateSummary;
+module.exports.patternPropertyMap = patternPropertyMap;
+module.exports.validateCsvReport = validateCsvReport;
 module.exports.WasmValidator = WasmValidator;
+module.exports.StreamingValidator = StreamingValidator;
 module.exports.ValidationError = ValidationError;
 module.exports.isValidationError = isValidationError;
 module.exports.assertValidationError = assertValidationError;
\ No newline at end of file